@@ -15,6 +15,7 @@ mod auto;
 pub use crate::auto::*;
 
 pub mod prelude;
+pub mod subclass;
 
 pub use crate::attribute::Attribute;
 pub use crate::attribute_set::AttributeSet;
@@ -0,0 +1,217 @@
+// Take a look at the license at the top of the repository in the LICENSE file.
+
+use glib::GString;
+use glib::subclass::prelude::*;
+use glib::translate::*;
+
+use super::store_str_at;
+use crate::{Action, ffi, prelude::*};
+
+pub trait ActionImpl: ObjectImpl + ObjectSubclass<Type: IsA<Action>> {
+    fn do_action(&self, i: i32) -> bool;
+
+    fn n_actions(&self) -> i32;
+
+    fn description(&self, i: i32) -> Option<GString> {
+        self.parent_description(i)
+    }
+
+    // Non-localized name of the action, e.g. "click" or "press"
+    fn name(&self, i: i32) -> Option<GString>;
+
+    fn keybinding(&self, i: i32) -> Option<GString> {
+        self.parent_keybinding(i)
+    }
+
+    fn set_description(&self, i: i32, desc: &str) -> bool {
+        self.parent_set_description(i, desc)
+    }
+
+    fn localized_name(&self, i: i32) -> Option<GString> {
+        self.parent_localized_name(i)
+    }
+}
+
+pub trait ActionImplExt: ActionImpl {
+    fn parent_description(&self, i: i32) -> Option<GString> {
+        unsafe {
+            let type_data = Self::type_data();
+            let parent_iface =
+                type_data.as_ref().parent_interface::<Action>() as *const ffi::AtkActionIface;
+
+            (*parent_iface).get_description.and_then(|func| {
+                from_glib_none(func(
+                    self.obj().unsafe_cast_ref::<Action>().to_glib_none().0,
+                    i,
+                ))
+            })
+        }
+    }
+
+    fn parent_keybinding(&self, i: i32) -> Option<GString> {
+        unsafe {
+            let type_data = Self::type_data();
+            let parent_iface =
+                type_data.as_ref().parent_interface::<Action>() as *const ffi::AtkActionIface;
+
+            (*parent_iface).get_keybinding.and_then(|func| {
+                from_glib_none(func(
+                    self.obj().unsafe_cast_ref::<Action>().to_glib_none().0,
+                    i,
+                ))
+            })
+        }
+    }
+
+    fn parent_set_description(&self, i: i32, desc: &str) -> bool {
+        unsafe {
+            let type_data = Self::type_data();
+            let parent_iface =
+                type_data.as_ref().parent_interface::<Action>() as *const ffi::AtkActionIface;
+
+            if let Some(func) = (*parent_iface).set_description {
+                from_glib(func(
+                    self.obj().unsafe_cast_ref::<Action>().to_glib_none().0,
+                    i,
+                    desc.to_glib_none().0,
+                ))
+            } else {
+                false
+            }
+        }
+    }
+
+    fn parent_localized_name(&self, i: i32) -> Option<GString> {
+        unsafe {
+            let type_data = Self::type_data();
+            let parent_iface =
+                type_data.as_ref().parent_interface::<Action>() as *const ffi::AtkActionIface;
+
+            (*parent_iface).get_localized_name.and_then(|func| {
+                from_glib_none(func(
+                    self.obj().unsafe_cast_ref::<Action>().to_glib_none().0,
+                    i,
+                ))
+            })
+        }
+    }
+}
+
+impl<T: ActionImpl> ActionImplExt for T {}
+
+unsafe impl<T: ActionImpl> IsImplementable<T> for Action {
+    fn interface_init(iface: &mut glib::Interface<Self>) {
+        let iface = iface.as_mut();
+
+        iface.do_action = Some(action_do_action::<T>);
+        iface.get_n_actions = Some(action_get_n_actions::<T>);
+        iface.get_description = Some(action_get_description::<T>);
+        iface.get_name = Some(action_get_name::<T>);
+        iface.get_keybinding = Some(action_get_keybinding::<T>);
+        iface.set_description = Some(action_set_description::<T>);
+        iface.get_localized_name = Some(action_get_localized_name::<T>);
+    }
+}
+
+unsafe extern "C" fn action_do_action<T: ActionImpl>(
+    action: *mut ffi::AtkAction,
+    i: i32,
+) -> glib::ffi::gboolean {
+    unsafe {
+        let instance = &*(action as *mut T::Instance);
+        let imp = instance.imp();
+
+        imp.do_action(i).into_glib()
+    }
+}
+
+unsafe extern "C" fn action_get_n_actions<T: ActionImpl>(action: *mut ffi::AtkAction) -> i32 {
+    unsafe {
+        let instance = &*(action as *mut T::Instance);
+        let imp = instance.imp();
+
+        imp.n_actions()
+    }
+}
+
+unsafe extern "C" fn action_get_description<T: ActionImpl>(
+    action: *mut ffi::AtkAction,
+    i: i32,
+) -> *const libc::c_char {
+    unsafe {
+        let instance = &*(action as *mut T::Instance);
+        let imp = instance.imp();
+
+        store_str_at(
+            imp.obj().unsafe_cast_ref(),
+            "atk-rs-subclass-action-description",
+            i,
+            imp.description(i),
+        )
+    }
+}
+
+unsafe extern "C" fn action_get_name<T: ActionImpl>(
+    action: *mut ffi::AtkAction,
+    i: i32,
+) -> *const libc::c_char {
+    unsafe {
+        let instance = &*(action as *mut T::Instance);
+        let imp = instance.imp();
+
+        store_str_at(
+            imp.obj().unsafe_cast_ref(),
+            "atk-rs-subclass-action-name",
+            i,
+            imp.name(i),
+        )
+    }
+}
+
+unsafe extern "C" fn action_get_keybinding<T: ActionImpl>(
+    action: *mut ffi::AtkAction,
+    i: i32,
+) -> *const libc::c_char {
+    unsafe {
+        let instance = &*(action as *mut T::Instance);
+        let imp = instance.imp();
+
+        store_str_at(
+            imp.obj().unsafe_cast_ref(),
+            "atk-rs-subclass-action-keybinding",
+            i,
+            imp.keybinding(i),
+        )
+    }
+}
+
+unsafe extern "C" fn action_set_description<T: ActionImpl>(
+    action: *mut ffi::AtkAction,
+    i: i32,
+    descptr: *const libc::c_char,
+) -> glib::ffi::gboolean {
+    unsafe {
+        let instance = &*(action as *mut T::Instance);
+        let imp = instance.imp();
+
+        imp.set_description(i, &GString::from_glib_borrow(descptr))
+            .into_glib()
+    }
+}
+
+unsafe extern "C" fn action_get_localized_name<T: ActionImpl>(
+    action: *mut ffi::AtkAction,
+    i: i32,
+) -> *const libc::c_char {
+    unsafe {
+        let instance = &*(action as *mut T::Instance);
+        let imp = instance.imp();
+
+        store_str_at(
+            imp.obj().unsafe_cast_ref(),
+            "atk-rs-subclass-action-localized-name",
+            i,
+            imp.localized_name(i),
+        )
+    }
+}
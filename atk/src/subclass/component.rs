@@ -0,0 +1,265 @@
+// Take a look at the license at the top of the repository in the LICENSE file.
+
+use glib::subclass::prelude::*;
+use glib::translate::*;
+
+#[cfg(feature = "v2_30")]
+use crate::ScrollType;
+use crate::{Component, CoordType, Layer, Object, ffi, prelude::*};
+
+pub trait ComponentImpl: ObjectImpl + ObjectSubclass<Type: IsA<Component>> {
+    // Returns `(x, y, width, height)` in the coordinate system given by `coord_type`
+    fn extents(&self, coord_type: CoordType) -> (i32, i32, i32, i32);
+
+    fn contains(&self, x: i32, y: i32, coord_type: CoordType) -> bool {
+        self.parent_contains(x, y, coord_type)
+    }
+
+    fn ref_accessible_at_point(&self, x: i32, y: i32, coord_type: CoordType) -> Option<Object> {
+        self.parent_ref_accessible_at_point(x, y, coord_type)
+    }
+
+    fn grab_focus(&self) -> bool {
+        self.parent_grab_focus()
+    }
+
+    fn layer(&self) -> Layer {
+        self.parent_layer()
+    }
+
+    fn alpha(&self) -> f64 {
+        self.parent_alpha()
+    }
+
+    #[cfg(feature = "v2_30")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "v2_30")))]
+    fn scroll_to(&self, type_: ScrollType) -> bool {
+        self.parent_scroll_to(type_)
+    }
+}
+
+pub trait ComponentImplExt: ComponentImpl {
+    fn parent_contains(&self, x: i32, y: i32, coord_type: CoordType) -> bool {
+        unsafe {
+            let type_data = Self::type_data();
+            let parent_iface =
+                type_data.as_ref().parent_interface::<Component>() as *const ffi::AtkComponentIface;
+
+            if let Some(func) = (*parent_iface).contains {
+                from_glib(func(
+                    self.obj().unsafe_cast_ref::<Component>().to_glib_none().0,
+                    x,
+                    y,
+                    coord_type.into_glib(),
+                ))
+            } else {
+                let (ex, ey, width, height) = self.extents(coord_type);
+                x >= ex && x < ex + width && y >= ey && y < ey + height
+            }
+        }
+    }
+
+    fn parent_ref_accessible_at_point(
+        &self,
+        x: i32,
+        y: i32,
+        coord_type: CoordType,
+    ) -> Option<Object> {
+        unsafe {
+            let type_data = Self::type_data();
+            let parent_iface =
+                type_data.as_ref().parent_interface::<Component>() as *const ffi::AtkComponentIface;
+
+            (*parent_iface).ref_accessible_at_point.and_then(|func| {
+                from_glib_full(func(
+                    self.obj().unsafe_cast_ref::<Component>().to_glib_none().0,
+                    x,
+                    y,
+                    coord_type.into_glib(),
+                ))
+            })
+        }
+    }
+
+    fn parent_grab_focus(&self) -> bool {
+        unsafe {
+            let type_data = Self::type_data();
+            let parent_iface =
+                type_data.as_ref().parent_interface::<Component>() as *const ffi::AtkComponentIface;
+
+            if let Some(func) = (*parent_iface).grab_focus {
+                from_glib(func(
+                    self.obj().unsafe_cast_ref::<Component>().to_glib_none().0,
+                ))
+            } else {
+                false
+            }
+        }
+    }
+
+    fn parent_layer(&self) -> Layer {
+        unsafe {
+            let type_data = Self::type_data();
+            let parent_iface =
+                type_data.as_ref().parent_interface::<Component>() as *const ffi::AtkComponentIface;
+
+            if let Some(func) = (*parent_iface).get_layer {
+                from_glib(func(
+                    self.obj().unsafe_cast_ref::<Component>().to_glib_none().0,
+                ))
+            } else {
+                // Same default as atk_component_get_layer()
+                Layer::Widget
+            }
+        }
+    }
+
+    fn parent_alpha(&self) -> f64 {
+        unsafe {
+            let type_data = Self::type_data();
+            let parent_iface =
+                type_data.as_ref().parent_interface::<Component>() as *const ffi::AtkComponentIface;
+
+            if let Some(func) = (*parent_iface).get_alpha {
+                func(self.obj().unsafe_cast_ref::<Component>().to_glib_none().0)
+            } else {
+                1.0
+            }
+        }
+    }
+
+    #[cfg(feature = "v2_30")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "v2_30")))]
+    fn parent_scroll_to(&self, type_: ScrollType) -> bool {
+        unsafe {
+            let type_data = Self::type_data();
+            let parent_iface =
+                type_data.as_ref().parent_interface::<Component>() as *const ffi::AtkComponentIface;
+
+            if let Some(func) = (*parent_iface).scroll_to {
+                from_glib(func(
+                    self.obj().unsafe_cast_ref::<Component>().to_glib_none().0,
+                    type_.into_glib(),
+                ))
+            } else {
+                false
+            }
+        }
+    }
+}
+
+impl<T: ComponentImpl> ComponentImplExt for T {}
+
+unsafe impl<T: ComponentImpl> IsImplementable<T> for Component {
+    fn interface_init(iface: &mut glib::Interface<Self>) {
+        let iface = iface.as_mut();
+
+        iface.get_extents = Some(component_get_extents::<T>);
+        iface.contains = Some(component_contains::<T>);
+        iface.ref_accessible_at_point = Some(component_ref_accessible_at_point::<T>);
+        iface.grab_focus = Some(component_grab_focus::<T>);
+        iface.get_layer = Some(component_get_layer::<T>);
+        iface.get_alpha = Some(component_get_alpha::<T>);
+        #[cfg(feature = "v2_30")]
+        {
+            iface.scroll_to = Some(component_scroll_to::<T>);
+        }
+    }
+}
+
+unsafe extern "C" fn component_get_extents<T: ComponentImpl>(
+    component: *mut ffi::AtkComponent,
+    xptr: *mut i32,
+    yptr: *mut i32,
+    widthptr: *mut i32,
+    heightptr: *mut i32,
+    coord_type: ffi::AtkCoordType,
+) {
+    unsafe {
+        let instance = &*(component as *mut T::Instance);
+        let imp = instance.imp();
+
+        let (x, y, width, height) = imp.extents(from_glib(coord_type));
+        // All of the out parameters are nullable
+        for (ptr, value) in [(xptr, x), (yptr, y), (widthptr, width), (heightptr, height)] {
+            if !ptr.is_null() {
+                *ptr = value;
+            }
+        }
+    }
+}
+
+unsafe extern "C" fn component_contains<T: ComponentImpl>(
+    component: *mut ffi::AtkComponent,
+    x: i32,
+    y: i32,
+    coord_type: ffi::AtkCoordType,
+) -> glib::ffi::gboolean {
+    unsafe {
+        let instance = &*(component as *mut T::Instance);
+        let imp = instance.imp();
+
+        imp.contains(x, y, from_glib(coord_type)).into_glib()
+    }
+}
+
+unsafe extern "C" fn component_ref_accessible_at_point<T: ComponentImpl>(
+    component: *mut ffi::AtkComponent,
+    x: i32,
+    y: i32,
+    coord_type: ffi::AtkCoordType,
+) -> *mut ffi::AtkObject {
+    unsafe {
+        let instance = &*(component as *mut T::Instance);
+        let imp = instance.imp();
+
+        imp.ref_accessible_at_point(x, y, from_glib(coord_type))
+            .to_glib_full()
+    }
+}
+
+unsafe extern "C" fn component_grab_focus<T: ComponentImpl>(
+    component: *mut ffi::AtkComponent,
+) -> glib::ffi::gboolean {
+    unsafe {
+        let instance = &*(component as *mut T::Instance);
+        let imp = instance.imp();
+
+        imp.grab_focus().into_glib()
+    }
+}
+
+unsafe extern "C" fn component_get_layer<T: ComponentImpl>(
+    component: *mut ffi::AtkComponent,
+) -> ffi::AtkLayer {
+    unsafe {
+        let instance = &*(component as *mut T::Instance);
+        let imp = instance.imp();
+
+        imp.layer().into_glib()
+    }
+}
+
+unsafe extern "C" fn component_get_alpha<T: ComponentImpl>(
+    component: *mut ffi::AtkComponent,
+) -> f64 {
+    unsafe {
+        let instance = &*(component as *mut T::Instance);
+        let imp = instance.imp();
+
+        imp.alpha()
+    }
+}
+
+#[cfg(feature = "v2_30")]
+unsafe extern "C" fn component_scroll_to<T: ComponentImpl>(
+    component: *mut ffi::AtkComponent,
+    type_: ffi::AtkScrollType,
+) -> glib::ffi::gboolean {
+    unsafe {
+        let instance = &*(component as *mut T::Instance);
+        let imp = instance.imp();
+
+        imp.scroll_to(from_glib(type_)).into_glib()
+    }
+}
@@ -0,0 +1,110 @@
+// Take a look at the license at the top of the repository in the LICENSE file.
+
+// rustdoc-stripper-ignore-next
+//! Traits intended for creating custom accessible objects.
+//!
+//! To use a custom [`Object`](crate::Object) subclass as the accessible of a GTK widget,
+//! pass its type to `gtk::subclass::widget::WidgetClassSubclassExt::set_accessible_type()`.
+//! GTK constructs accessibles with a `widget` property, so the subclass has to declare a
+//! writable `widget` property holding the widget it represents.
+
+use std::collections::HashMap;
+use std::ptr::NonNull;
+
+use glib::{GString, Quark, prelude::*, translate::*};
+
+use crate::ffi;
+
+pub mod action;
+pub mod component;
+pub mod object;
+pub mod selection;
+pub mod table;
+pub mod text;
+pub mod value;
+
+pub mod prelude {
+    #[doc(hidden)]
+    pub use glib::subclass::prelude::*;
+
+    pub use super::action::{ActionImpl, ActionImplExt};
+    pub use super::component::{ComponentImpl, ComponentImplExt};
+    pub use super::object::{AtkObjectImpl, AtkObjectImplExt};
+    pub use super::selection::{SelectionImpl, SelectionImplExt};
+    pub use super::table::{TableImpl, TableImplExt};
+    pub use super::text::{TextImpl, TextImplExt};
+    pub use super::value::{ValueImpl, ValueImplExt};
+}
+
+// Keeps `value` alive until the next call with the same `key` and `index` on `obj`, so that
+// it can be returned from the virtual methods with a `transfer none` return value. Each
+// value is boxed, so that its address doesn't change when values for other indices are
+// stored.
+unsafe fn store<T: 'static>(
+    obj: &glib::Object,
+    key: &str,
+    index: i32,
+    value: Option<T>,
+) -> Option<NonNull<T>> {
+    unsafe {
+        let quark = Quark::from_str(key);
+        let values = match obj.qdata::<HashMap<i32, Box<T>>>(quark) {
+            Some(values) => values,
+            None => {
+                obj.set_qdata(quark, HashMap::<i32, Box<T>>::new());
+                obj.qdata::<HashMap<i32, Box<T>>>(quark).unwrap()
+            }
+        };
+        let values = &mut *values.as_ptr();
+        match value {
+            Some(value) => {
+                let value = values.entry(index).insert_entry(Box::new(value)).into_mut();
+                Some(NonNull::from(&mut **value))
+            }
+            None => {
+                values.remove(&index);
+                None
+            }
+        }
+    }
+}
+
+pub(crate) unsafe fn store_str(
+    obj: &glib::Object,
+    key: &str,
+    s: Option<GString>,
+) -> *const libc::c_char {
+    unsafe { store_str_at(obj, key, 0, s) }
+}
+
+// Like `store_str()`, for the values of a virtual method taking an index, like a row or an
+// action
+pub(crate) unsafe fn store_str_at(
+    obj: &glib::Object,
+    key: &str,
+    index: i32,
+    s: Option<GString>,
+) -> *const libc::c_char {
+    unsafe { store(obj, key, index, s).map_or(std::ptr::null(), |s| s.as_ref().as_ptr()) }
+}
+
+pub(crate) unsafe fn store_object(
+    obj: &glib::Object,
+    key: &str,
+    object: Option<crate::Object>,
+) -> *mut ffi::AtkObject {
+    unsafe { store_object_at(obj, key, 0, object) }
+}
+
+pub(crate) unsafe fn store_object_at(
+    obj: &glib::Object,
+    key: &str,
+    index: i32,
+    object: Option<crate::Object>,
+) -> *mut ffi::AtkObject {
+    unsafe {
+        store(obj, key, index, object).map_or(std::ptr::null_mut(), |object| {
+            object.as_ref().to_glib_none().0
+        })
+    }
+}
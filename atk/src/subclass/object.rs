@@ -0,0 +1,250 @@
+// Take a look at the license at the top of the repository in the LICENSE file.
+
+use glib::GString;
+use glib::subclass::prelude::*;
+use glib::translate::*;
+
+use super::store_str;
+use crate::{Object, RelationSet, Role, StateSet, ffi, prelude::*};
+
+pub trait AtkObjectImpl: AtkObjectImplExt + ObjectImpl + ObjectSubclass<Type: IsA<Object>> {
+    fn name(&self) -> Option<GString> {
+        self.parent_name()
+    }
+
+    fn description(&self) -> Option<GString> {
+        self.parent_description()
+    }
+
+    fn role(&self) -> Role {
+        self.parent_role()
+    }
+
+    fn n_children(&self) -> i32 {
+        self.parent_n_children()
+    }
+
+    fn ref_child(&self, i: i32) -> Option<Object> {
+        self.parent_ref_child(i)
+    }
+
+    fn index_in_parent(&self) -> i32 {
+        self.parent_index_in_parent()
+    }
+
+    fn ref_state_set(&self) -> StateSet {
+        self.parent_ref_state_set()
+    }
+
+    fn ref_relation_set(&self) -> RelationSet {
+        self.parent_ref_relation_set()
+    }
+}
+
+mod sealed {
+    pub trait Sealed {}
+    impl<T: super::AtkObjectImpl> Sealed for T {}
+}
+
+pub trait AtkObjectImplExt: ObjectSubclass + sealed::Sealed {
+    fn parent_name(&self) -> Option<GString> {
+        unsafe {
+            let data = Self::type_data();
+            let parent_class = data.as_ref().parent_class() as *mut ffi::AtkObjectClass;
+            (*parent_class).get_name.and_then(|f| {
+                from_glib_none(f(self.obj().unsafe_cast_ref::<Object>().to_glib_none().0))
+            })
+        }
+    }
+
+    fn parent_description(&self) -> Option<GString> {
+        unsafe {
+            let data = Self::type_data();
+            let parent_class = data.as_ref().parent_class() as *mut ffi::AtkObjectClass;
+            (*parent_class).get_description.and_then(|f| {
+                from_glib_none(f(self.obj().unsafe_cast_ref::<Object>().to_glib_none().0))
+            })
+        }
+    }
+
+    fn parent_role(&self) -> Role {
+        unsafe {
+            let data = Self::type_data();
+            let parent_class = data.as_ref().parent_class() as *mut ffi::AtkObjectClass;
+            match (*parent_class).get_role {
+                Some(f) => from_glib(f(self.obj().unsafe_cast_ref::<Object>().to_glib_none().0)),
+                None => Role::Unknown,
+            }
+        }
+    }
+
+    fn parent_n_children(&self) -> i32 {
+        unsafe {
+            let data = Self::type_data();
+            let parent_class = data.as_ref().parent_class() as *mut ffi::AtkObjectClass;
+            match (*parent_class).get_n_children {
+                Some(f) => f(self.obj().unsafe_cast_ref::<Object>().to_glib_none().0),
+                None => 0,
+            }
+        }
+    }
+
+    fn parent_ref_child(&self, i: i32) -> Option<Object> {
+        unsafe {
+            let data = Self::type_data();
+            let parent_class = data.as_ref().parent_class() as *mut ffi::AtkObjectClass;
+            (*parent_class).ref_child.and_then(|f| {
+                from_glib_full(f(
+                    self.obj().unsafe_cast_ref::<Object>().to_glib_none().0,
+                    i,
+                ))
+            })
+        }
+    }
+
+    fn parent_index_in_parent(&self) -> i32 {
+        unsafe {
+            let data = Self::type_data();
+            let parent_class = data.as_ref().parent_class() as *mut ffi::AtkObjectClass;
+            match (*parent_class).get_index_in_parent {
+                Some(f) => f(self.obj().unsafe_cast_ref::<Object>().to_glib_none().0),
+                None => -1,
+            }
+        }
+    }
+
+    fn parent_ref_state_set(&self) -> StateSet {
+        unsafe {
+            let data = Self::type_data();
+            let parent_class = data.as_ref().parent_class() as *mut ffi::AtkObjectClass;
+            match (*parent_class).ref_state_set {
+                Some(f) => {
+                    from_glib_full(f(self.obj().unsafe_cast_ref::<Object>().to_glib_none().0))
+                }
+                None => StateSet::new(),
+            }
+        }
+    }
+
+    fn parent_ref_relation_set(&self) -> RelationSet {
+        unsafe {
+            let data = Self::type_data();
+            let parent_class = data.as_ref().parent_class() as *mut ffi::AtkObjectClass;
+            match (*parent_class).ref_relation_set {
+                Some(f) => {
+                    from_glib_full(f(self.obj().unsafe_cast_ref::<Object>().to_glib_none().0))
+                }
+                None => RelationSet::new(),
+            }
+        }
+    }
+}
+
+impl<T: AtkObjectImpl> AtkObjectImplExt for T {}
+
+unsafe impl<T: AtkObjectImpl> IsSubclassable<T> for Object {
+    fn class_init(class: &mut glib::Class<Self>) {
+        Self::parent_class_init::<T>(class);
+
+        let klass = class.as_mut();
+        klass.get_name = Some(object_get_name::<T>);
+        klass.get_description = Some(object_get_description::<T>);
+        klass.get_role = Some(object_get_role::<T>);
+        klass.get_n_children = Some(object_get_n_children::<T>);
+        klass.ref_child = Some(object_ref_child::<T>);
+        klass.get_index_in_parent = Some(object_get_index_in_parent::<T>);
+        klass.ref_state_set = Some(object_ref_state_set::<T>);
+        klass.ref_relation_set = Some(object_ref_relation_set::<T>);
+    }
+}
+
+unsafe extern "C" fn object_get_name<T: AtkObjectImpl>(
+    ptr: *mut ffi::AtkObject,
+) -> *const libc::c_char {
+    unsafe {
+        let instance = &*(ptr as *mut T::Instance);
+        let imp = instance.imp();
+
+        store_str(
+            imp.obj().unsafe_cast_ref(),
+            "atk-rs-subclass-object-name",
+            imp.name(),
+        )
+    }
+}
+
+unsafe extern "C" fn object_get_description<T: AtkObjectImpl>(
+    ptr: *mut ffi::AtkObject,
+) -> *const libc::c_char {
+    unsafe {
+        let instance = &*(ptr as *mut T::Instance);
+        let imp = instance.imp();
+
+        store_str(
+            imp.obj().unsafe_cast_ref(),
+            "atk-rs-subclass-object-description",
+            imp.description(),
+        )
+    }
+}
+
+unsafe extern "C" fn object_get_role<T: AtkObjectImpl>(ptr: *mut ffi::AtkObject) -> ffi::AtkRole {
+    unsafe {
+        let instance = &*(ptr as *mut T::Instance);
+        let imp = instance.imp();
+
+        imp.role().into_glib()
+    }
+}
+
+unsafe extern "C" fn object_get_n_children<T: AtkObjectImpl>(ptr: *mut ffi::AtkObject) -> i32 {
+    unsafe {
+        let instance = &*(ptr as *mut T::Instance);
+        let imp = instance.imp();
+
+        imp.n_children()
+    }
+}
+
+unsafe extern "C" fn object_ref_child<T: AtkObjectImpl>(
+    ptr: *mut ffi::AtkObject,
+    i: i32,
+) -> *mut ffi::AtkObject {
+    unsafe {
+        let instance = &*(ptr as *mut T::Instance);
+        let imp = instance.imp();
+
+        imp.ref_child(i).to_glib_full()
+    }
+}
+
+unsafe extern "C" fn object_get_index_in_parent<T: AtkObjectImpl>(ptr: *mut ffi::AtkObject) -> i32 {
+    unsafe {
+        let instance = &*(ptr as *mut T::Instance);
+        let imp = instance.imp();
+
+        imp.index_in_parent()
+    }
+}
+
+unsafe extern "C" fn object_ref_state_set<T: AtkObjectImpl>(
+    ptr: *mut ffi::AtkObject,
+) -> *mut ffi::AtkStateSet {
+    unsafe {
+        let instance = &*(ptr as *mut T::Instance);
+        let imp = instance.imp();
+
+        imp.ref_state_set().into_glib_ptr()
+    }
+}
+
+unsafe extern "C" fn object_ref_relation_set<T: AtkObjectImpl>(
+    ptr: *mut ffi::AtkObject,
+) -> *mut ffi::AtkRelationSet {
+    unsafe {
+        let instance = &*(ptr as *mut T::Instance);
+        let imp = instance.imp();
+
+        imp.ref_relation_set().into_glib_ptr()
+    }
+}
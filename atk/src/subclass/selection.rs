@@ -0,0 +1,196 @@
+// Take a look at the license at the top of the repository in the LICENSE file.
+
+use glib::subclass::prelude::*;
+use glib::translate::*;
+
+use crate::{Object, Selection, ffi, prelude::*};
+
+pub trait SelectionImpl: ObjectImpl + ObjectSubclass<Type: IsA<Selection>> {
+    // Returns the `i`-th selected child, not the `i`-th child
+    fn ref_selection(&self, i: i32) -> Option<Object>;
+
+    fn selection_count(&self) -> i32;
+
+    fn is_child_selected(&self, i: i32) -> bool;
+
+    fn add_selection(&self, i: i32) -> bool {
+        self.parent_add_selection(i)
+    }
+
+    fn remove_selection(&self, i: i32) -> bool {
+        self.parent_remove_selection(i)
+    }
+
+    fn clear_selection(&self) -> bool {
+        self.parent_clear_selection()
+    }
+
+    fn select_all_selection(&self) -> bool {
+        self.parent_select_all_selection()
+    }
+}
+
+pub trait SelectionImplExt: SelectionImpl {
+    fn parent_add_selection(&self, i: i32) -> bool {
+        unsafe {
+            let type_data = Self::type_data();
+            let parent_iface =
+                type_data.as_ref().parent_interface::<Selection>() as *const ffi::AtkSelectionIface;
+
+            if let Some(func) = (*parent_iface).add_selection {
+                from_glib(func(
+                    self.obj().unsafe_cast_ref::<Selection>().to_glib_none().0,
+                    i,
+                ))
+            } else {
+                false
+            }
+        }
+    }
+
+    fn parent_remove_selection(&self, i: i32) -> bool {
+        unsafe {
+            let type_data = Self::type_data();
+            let parent_iface =
+                type_data.as_ref().parent_interface::<Selection>() as *const ffi::AtkSelectionIface;
+
+            if let Some(func) = (*parent_iface).remove_selection {
+                from_glib(func(
+                    self.obj().unsafe_cast_ref::<Selection>().to_glib_none().0,
+                    i,
+                ))
+            } else {
+                false
+            }
+        }
+    }
+
+    fn parent_clear_selection(&self) -> bool {
+        unsafe {
+            let type_data = Self::type_data();
+            let parent_iface =
+                type_data.as_ref().parent_interface::<Selection>() as *const ffi::AtkSelectionIface;
+
+            if let Some(func) = (*parent_iface).clear_selection {
+                from_glib(func(
+                    self.obj().unsafe_cast_ref::<Selection>().to_glib_none().0,
+                ))
+            } else {
+                false
+            }
+        }
+    }
+
+    fn parent_select_all_selection(&self) -> bool {
+        unsafe {
+            let type_data = Self::type_data();
+            let parent_iface =
+                type_data.as_ref().parent_interface::<Selection>() as *const ffi::AtkSelectionIface;
+
+            if let Some(func) = (*parent_iface).select_all_selection {
+                from_glib(func(
+                    self.obj().unsafe_cast_ref::<Selection>().to_glib_none().0,
+                ))
+            } else {
+                false
+            }
+        }
+    }
+}
+
+impl<T: SelectionImpl> SelectionImplExt for T {}
+
+unsafe impl<T: SelectionImpl> IsImplementable<T> for Selection {
+    fn interface_init(iface: &mut glib::Interface<Self>) {
+        let iface = iface.as_mut();
+
+        iface.ref_selection = Some(selection_ref_selection::<T>);
+        iface.get_selection_count = Some(selection_get_selection_count::<T>);
+        iface.is_child_selected = Some(selection_is_child_selected::<T>);
+        iface.add_selection = Some(selection_add_selection::<T>);
+        iface.remove_selection = Some(selection_remove_selection::<T>);
+        iface.clear_selection = Some(selection_clear_selection::<T>);
+        iface.select_all_selection = Some(selection_select_all_selection::<T>);
+    }
+}
+
+unsafe extern "C" fn selection_ref_selection<T: SelectionImpl>(
+    selection: *mut ffi::AtkSelection,
+    i: i32,
+) -> *mut ffi::AtkObject {
+    unsafe {
+        let instance = &*(selection as *mut T::Instance);
+        let imp = instance.imp();
+
+        imp.ref_selection(i).to_glib_full()
+    }
+}
+
+unsafe extern "C" fn selection_get_selection_count<T: SelectionImpl>(
+    selection: *mut ffi::AtkSelection,
+) -> i32 {
+    unsafe {
+        let instance = &*(selection as *mut T::Instance);
+        let imp = instance.imp();
+
+        imp.selection_count()
+    }
+}
+
+unsafe extern "C" fn selection_is_child_selected<T: SelectionImpl>(
+    selection: *mut ffi::AtkSelection,
+    i: i32,
+) -> glib::ffi::gboolean {
+    unsafe {
+        let instance = &*(selection as *mut T::Instance);
+        let imp = instance.imp();
+
+        imp.is_child_selected(i).into_glib()
+    }
+}
+
+unsafe extern "C" fn selection_add_selection<T: SelectionImpl>(
+    selection: *mut ffi::AtkSelection,
+    i: i32,
+) -> glib::ffi::gboolean {
+    unsafe {
+        let instance = &*(selection as *mut T::Instance);
+        let imp = instance.imp();
+
+        imp.add_selection(i).into_glib()
+    }
+}
+
+unsafe extern "C" fn selection_remove_selection<T: SelectionImpl>(
+    selection: *mut ffi::AtkSelection,
+    i: i32,
+) -> glib::ffi::gboolean {
+    unsafe {
+        let instance = &*(selection as *mut T::Instance);
+        let imp = instance.imp();
+
+        imp.remove_selection(i).into_glib()
+    }
+}
+
+unsafe extern "C" fn selection_clear_selection<T: SelectionImpl>(
+    selection: *mut ffi::AtkSelection,
+) -> glib::ffi::gboolean {
+    unsafe {
+        let instance = &*(selection as *mut T::Instance);
+        let imp = instance.imp();
+
+        imp.clear_selection().into_glib()
+    }
+}
+
+unsafe extern "C" fn selection_select_all_selection<T: SelectionImpl>(
+    selection: *mut ffi::AtkSelection,
+) -> glib::ffi::gboolean {
+    unsafe {
+        let instance = &*(selection as *mut T::Instance);
+        let imp = instance.imp();
+
+        imp.select_all_selection().into_glib()
+    }
+}
@@ -0,0 +1,526 @@
+// Take a look at the license at the top of the repository in the LICENSE file.
+
+use glib::GString;
+use glib::subclass::prelude::*;
+use glib::translate::*;
+
+use super::{store_object, store_object_at, store_str_at};
+use crate::{Object, Table, ffi, prelude::*};
+
+pub trait TableImpl: ObjectImpl + ObjectSubclass<Type: IsA<Table>> {
+    fn ref_at(&self, row: i32, column: i32) -> Option<Object>;
+
+    fn n_rows(&self) -> i32;
+
+    fn n_columns(&self) -> i32;
+
+    fn row_extent_at(&self, row: i32, column: i32) -> i32 {
+        self.parent_row_extent_at(row, column)
+    }
+
+    fn column_extent_at(&self, row: i32, column: i32) -> i32 {
+        self.parent_column_extent_at(row, column)
+    }
+
+    fn caption(&self) -> Option<Object> {
+        self.parent_caption()
+    }
+
+    fn summary(&self) -> Option<Object> {
+        self.parent_summary()
+    }
+
+    fn row_description(&self, row: i32) -> Option<GString> {
+        self.parent_row_description(row)
+    }
+
+    fn column_description(&self, column: i32) -> Option<GString> {
+        self.parent_column_description(column)
+    }
+
+    fn row_header(&self, row: i32) -> Option<Object> {
+        self.parent_row_header(row)
+    }
+
+    fn column_header(&self, column: i32) -> Option<Object> {
+        self.parent_column_header(column)
+    }
+
+    fn selected_rows(&self) -> Vec<i32> {
+        self.parent_selected_rows()
+    }
+
+    fn selected_columns(&self) -> Vec<i32> {
+        self.parent_selected_columns()
+    }
+
+    fn is_row_selected(&self, row: i32) -> bool {
+        self.parent_is_row_selected(row)
+    }
+
+    fn is_column_selected(&self, column: i32) -> bool {
+        self.parent_is_column_selected(column)
+    }
+
+    fn is_selected(&self, row: i32, column: i32) -> bool {
+        self.parent_is_selected(row, column)
+    }
+}
+
+pub trait TableImplExt: TableImpl {
+    fn parent_row_extent_at(&self, row: i32, column: i32) -> i32 {
+        unsafe {
+            let type_data = Self::type_data();
+            let parent_iface =
+                type_data.as_ref().parent_interface::<Table>() as *const ffi::AtkTableIface;
+
+            match (*parent_iface).get_row_extent_at {
+                Some(func) => func(
+                    self.obj().unsafe_cast_ref::<Table>().to_glib_none().0,
+                    row,
+                    column,
+                ),
+                None => 1,
+            }
+        }
+    }
+
+    fn parent_column_extent_at(&self, row: i32, column: i32) -> i32 {
+        unsafe {
+            let type_data = Self::type_data();
+            let parent_iface =
+                type_data.as_ref().parent_interface::<Table>() as *const ffi::AtkTableIface;
+
+            match (*parent_iface).get_column_extent_at {
+                Some(func) => func(
+                    self.obj().unsafe_cast_ref::<Table>().to_glib_none().0,
+                    row,
+                    column,
+                ),
+                None => 1,
+            }
+        }
+    }
+
+    fn parent_caption(&self) -> Option<Object> {
+        unsafe {
+            let type_data = Self::type_data();
+            let parent_iface =
+                type_data.as_ref().parent_interface::<Table>() as *const ffi::AtkTableIface;
+
+            (*parent_iface).get_caption.and_then(|func| {
+                from_glib_none(func(self.obj().unsafe_cast_ref::<Table>().to_glib_none().0))
+            })
+        }
+    }
+
+    fn parent_summary(&self) -> Option<Object> {
+        unsafe {
+            let type_data = Self::type_data();
+            let parent_iface =
+                type_data.as_ref().parent_interface::<Table>() as *const ffi::AtkTableIface;
+
+            (*parent_iface).get_summary.and_then(|func| {
+                from_glib_full(func(self.obj().unsafe_cast_ref::<Table>().to_glib_none().0))
+            })
+        }
+    }
+
+    fn parent_row_description(&self, row: i32) -> Option<GString> {
+        unsafe {
+            let type_data = Self::type_data();
+            let parent_iface =
+                type_data.as_ref().parent_interface::<Table>() as *const ffi::AtkTableIface;
+
+            (*parent_iface).get_row_description.and_then(|func| {
+                from_glib_none(func(
+                    self.obj().unsafe_cast_ref::<Table>().to_glib_none().0,
+                    row,
+                ))
+            })
+        }
+    }
+
+    fn parent_column_description(&self, column: i32) -> Option<GString> {
+        unsafe {
+            let type_data = Self::type_data();
+            let parent_iface =
+                type_data.as_ref().parent_interface::<Table>() as *const ffi::AtkTableIface;
+
+            (*parent_iface).get_column_description.and_then(|func| {
+                from_glib_none(func(
+                    self.obj().unsafe_cast_ref::<Table>().to_glib_none().0,
+                    column,
+                ))
+            })
+        }
+    }
+
+    fn parent_row_header(&self, row: i32) -> Option<Object> {
+        unsafe {
+            let type_data = Self::type_data();
+            let parent_iface =
+                type_data.as_ref().parent_interface::<Table>() as *const ffi::AtkTableIface;
+
+            (*parent_iface).get_row_header.and_then(|func| {
+                from_glib_none(func(
+                    self.obj().unsafe_cast_ref::<Table>().to_glib_none().0,
+                    row,
+                ))
+            })
+        }
+    }
+
+    fn parent_column_header(&self, column: i32) -> Option<Object> {
+        unsafe {
+            let type_data = Self::type_data();
+            let parent_iface =
+                type_data.as_ref().parent_interface::<Table>() as *const ffi::AtkTableIface;
+
+            (*parent_iface).get_column_header.and_then(|func| {
+                from_glib_none(func(
+                    self.obj().unsafe_cast_ref::<Table>().to_glib_none().0,
+                    column,
+                ))
+            })
+        }
+    }
+
+    fn parent_selected_rows(&self) -> Vec<i32> {
+        unsafe {
+            let type_data = Self::type_data();
+            let parent_iface =
+                type_data.as_ref().parent_interface::<Table>() as *const ffi::AtkTableIface;
+
+            let Some(func) = (*parent_iface).get_selected_rows else {
+                return Vec::new();
+            };
+            let mut selected = std::ptr::null_mut();
+            let count = func(
+                self.obj().unsafe_cast_ref::<Table>().to_glib_none().0,
+                &mut selected,
+            );
+            FromGlibContainer::from_glib_full_num(selected, count.max(0) as usize)
+        }
+    }
+
+    fn parent_selected_columns(&self) -> Vec<i32> {
+        unsafe {
+            let type_data = Self::type_data();
+            let parent_iface =
+                type_data.as_ref().parent_interface::<Table>() as *const ffi::AtkTableIface;
+
+            let Some(func) = (*parent_iface).get_selected_columns else {
+                return Vec::new();
+            };
+            let mut selected = std::ptr::null_mut();
+            let count = func(
+                self.obj().unsafe_cast_ref::<Table>().to_glib_none().0,
+                &mut selected,
+            );
+            FromGlibContainer::from_glib_full_num(selected, count.max(0) as usize)
+        }
+    }
+
+    fn parent_is_row_selected(&self, row: i32) -> bool {
+        unsafe {
+            let type_data = Self::type_data();
+            let parent_iface =
+                type_data.as_ref().parent_interface::<Table>() as *const ffi::AtkTableIface;
+
+            match (*parent_iface).is_row_selected {
+                Some(func) => from_glib(func(
+                    self.obj().unsafe_cast_ref::<Table>().to_glib_none().0,
+                    row,
+                )),
+                None => false,
+            }
+        }
+    }
+
+    fn parent_is_column_selected(&self, column: i32) -> bool {
+        unsafe {
+            let type_data = Self::type_data();
+            let parent_iface =
+                type_data.as_ref().parent_interface::<Table>() as *const ffi::AtkTableIface;
+
+            match (*parent_iface).is_column_selected {
+                Some(func) => from_glib(func(
+                    self.obj().unsafe_cast_ref::<Table>().to_glib_none().0,
+                    column,
+                )),
+                None => false,
+            }
+        }
+    }
+
+    fn parent_is_selected(&self, row: i32, column: i32) -> bool {
+        unsafe {
+            let type_data = Self::type_data();
+            let parent_iface =
+                type_data.as_ref().parent_interface::<Table>() as *const ffi::AtkTableIface;
+
+            match (*parent_iface).is_selected {
+                Some(func) => from_glib(func(
+                    self.obj().unsafe_cast_ref::<Table>().to_glib_none().0,
+                    row,
+                    column,
+                )),
+                None => false,
+            }
+        }
+    }
+}
+
+impl<T: TableImpl> TableImplExt for T {}
+
+unsafe impl<T: TableImpl> IsImplementable<T> for Table {
+    fn interface_init(iface: &mut glib::Interface<Self>) {
+        let iface = iface.as_mut();
+
+        iface.ref_at = Some(table_ref_at::<T>);
+        iface.get_n_rows = Some(table_get_n_rows::<T>);
+        iface.get_n_columns = Some(table_get_n_columns::<T>);
+        iface.get_row_extent_at = Some(table_get_row_extent_at::<T>);
+        iface.get_column_extent_at = Some(table_get_column_extent_at::<T>);
+        iface.get_caption = Some(table_get_caption::<T>);
+        iface.get_summary = Some(table_get_summary::<T>);
+        iface.get_row_description = Some(table_get_row_description::<T>);
+        iface.get_column_description = Some(table_get_column_description::<T>);
+        iface.get_row_header = Some(table_get_row_header::<T>);
+        iface.get_column_header = Some(table_get_column_header::<T>);
+        iface.get_selected_rows = Some(table_get_selected_rows::<T>);
+        iface.get_selected_columns = Some(table_get_selected_columns::<T>);
+        iface.is_row_selected = Some(table_is_row_selected::<T>);
+        iface.is_column_selected = Some(table_is_column_selected::<T>);
+        iface.is_selected = Some(table_is_selected::<T>);
+    }
+}
+
+unsafe extern "C" fn table_ref_at<T: TableImpl>(
+    table: *mut ffi::AtkTable,
+    row: i32,
+    column: i32,
+) -> *mut ffi::AtkObject {
+    unsafe {
+        let instance = &*(table as *mut T::Instance);
+        let imp = instance.imp();
+
+        imp.ref_at(row, column).to_glib_full()
+    }
+}
+
+unsafe extern "C" fn table_get_n_rows<T: TableImpl>(table: *mut ffi::AtkTable) -> i32 {
+    unsafe {
+        let instance = &*(table as *mut T::Instance);
+        let imp = instance.imp();
+
+        imp.n_rows()
+    }
+}
+
+unsafe extern "C" fn table_get_n_columns<T: TableImpl>(table: *mut ffi::AtkTable) -> i32 {
+    unsafe {
+        let instance = &*(table as *mut T::Instance);
+        let imp = instance.imp();
+
+        imp.n_columns()
+    }
+}
+
+unsafe extern "C" fn table_get_row_extent_at<T: TableImpl>(
+    table: *mut ffi::AtkTable,
+    row: i32,
+    column: i32,
+) -> i32 {
+    unsafe {
+        let instance = &*(table as *mut T::Instance);
+        let imp = instance.imp();
+
+        imp.row_extent_at(row, column)
+    }
+}
+
+unsafe extern "C" fn table_get_column_extent_at<T: TableImpl>(
+    table: *mut ffi::AtkTable,
+    row: i32,
+    column: i32,
+) -> i32 {
+    unsafe {
+        let instance = &*(table as *mut T::Instance);
+        let imp = instance.imp();
+
+        imp.column_extent_at(row, column)
+    }
+}
+
+unsafe extern "C" fn table_get_caption<T: TableImpl>(
+    table: *mut ffi::AtkTable,
+) -> *mut ffi::AtkObject {
+    unsafe {
+        let instance = &*(table as *mut T::Instance);
+        let imp = instance.imp();
+
+        store_object(
+            imp.obj().unsafe_cast_ref(),
+            "atk-rs-subclass-table-caption",
+            imp.caption(),
+        )
+    }
+}
+
+unsafe extern "C" fn table_get_summary<T: TableImpl>(
+    table: *mut ffi::AtkTable,
+) -> *mut ffi::AtkObject {
+    unsafe {
+        let instance = &*(table as *mut T::Instance);
+        let imp = instance.imp();
+
+        // Unlike the other accessors, this one is transfer full
+        imp.summary().to_glib_full()
+    }
+}
+
+unsafe extern "C" fn table_get_row_description<T: TableImpl>(
+    table: *mut ffi::AtkTable,
+    row: i32,
+) -> *const libc::c_char {
+    unsafe {
+        let instance = &*(table as *mut T::Instance);
+        let imp = instance.imp();
+
+        store_str_at(
+            imp.obj().unsafe_cast_ref(),
+            "atk-rs-subclass-table-row-description",
+            row,
+            imp.row_description(row),
+        )
+    }
+}
+
+unsafe extern "C" fn table_get_column_description<T: TableImpl>(
+    table: *mut ffi::AtkTable,
+    column: i32,
+) -> *const libc::c_char {
+    unsafe {
+        let instance = &*(table as *mut T::Instance);
+        let imp = instance.imp();
+
+        store_str_at(
+            imp.obj().unsafe_cast_ref(),
+            "atk-rs-subclass-table-column-description",
+            column,
+            imp.column_description(column),
+        )
+    }
+}
+
+unsafe extern "C" fn table_get_row_header<T: TableImpl>(
+    table: *mut ffi::AtkTable,
+    row: i32,
+) -> *mut ffi::AtkObject {
+    unsafe {
+        let instance = &*(table as *mut T::Instance);
+        let imp = instance.imp();
+
+        store_object_at(
+            imp.obj().unsafe_cast_ref(),
+            "atk-rs-subclass-table-row-header",
+            row,
+            imp.row_header(row),
+        )
+    }
+}
+
+unsafe extern "C" fn table_get_column_header<T: TableImpl>(
+    table: *mut ffi::AtkTable,
+    column: i32,
+) -> *mut ffi::AtkObject {
+    unsafe {
+        let instance = &*(table as *mut T::Instance);
+        let imp = instance.imp();
+
+        store_object_at(
+            imp.obj().unsafe_cast_ref(),
+            "atk-rs-subclass-table-column-header",
+            column,
+            imp.column_header(column),
+        )
+    }
+}
+
+// Returns the number of selected items and stores a newly allocated array of them in `selectedptr`
+unsafe fn selected_to_glib(selected: Vec<i32>, selectedptr: *mut *mut i32) -> i32 {
+    unsafe {
+        let count = selected.len() as i32;
+        if !selectedptr.is_null() {
+            *selectedptr = if selected.is_empty() {
+                std::ptr::null_mut()
+            } else {
+                ToGlibContainerFromSlice::to_glib_full_from_slice(&selected)
+            };
+        }
+        count
+    }
+}
+
+unsafe extern "C" fn table_get_selected_rows<T: TableImpl>(
+    table: *mut ffi::AtkTable,
+    selectedptr: *mut *mut i32,
+) -> i32 {
+    unsafe {
+        let instance = &*(table as *mut T::Instance);
+        let imp = instance.imp();
+
+        selected_to_glib(imp.selected_rows(), selectedptr)
+    }
+}
+
+unsafe extern "C" fn table_get_selected_columns<T: TableImpl>(
+    table: *mut ffi::AtkTable,
+    selectedptr: *mut *mut i32,
+) -> i32 {
+    unsafe {
+        let instance = &*(table as *mut T::Instance);
+        let imp = instance.imp();
+
+        selected_to_glib(imp.selected_columns(), selectedptr)
+    }
+}
+
+unsafe extern "C" fn table_is_row_selected<T: TableImpl>(
+    table: *mut ffi::AtkTable,
+    row: i32,
+) -> glib::ffi::gboolean {
+    unsafe {
+        let instance = &*(table as *mut T::Instance);
+        let imp = instance.imp();
+
+        imp.is_row_selected(row).into_glib()
+    }
+}
+
+unsafe extern "C" fn table_is_column_selected<T: TableImpl>(
+    table: *mut ffi::AtkTable,
+    column: i32,
+) -> glib::ffi::gboolean {
+    unsafe {
+        let instance = &*(table as *mut T::Instance);
+        let imp = instance.imp();
+
+        imp.is_column_selected(column).into_glib()
+    }
+}
+
+unsafe extern "C" fn table_is_selected<T: TableImpl>(
+    table: *mut ffi::AtkTable,
+    row: i32,
+    column: i32,
+) -> glib::ffi::gboolean {
+    unsafe {
+        let instance = &*(table as *mut T::Instance);
+        let imp = instance.imp();
+
+        imp.is_selected(row, column).into_glib()
+    }
+}
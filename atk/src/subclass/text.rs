@@ -0,0 +1,344 @@
+// Take a look at the license at the top of the repository in the LICENSE file.
+
+use glib::GString;
+use glib::subclass::prelude::*;
+use glib::translate::*;
+
+use crate::{CoordType, Text, TextGranularity, ffi, prelude::*};
+
+pub trait TextImpl: ObjectImpl + ObjectSubclass<Type: IsA<Text>> {
+    // Returns the characters from `start_offset` up to, but not including `end_offset`.
+    // An `end_offset` of -1 means the end of the text.
+    fn text(&self, start_offset: i32, end_offset: i32) -> GString;
+
+    fn character_at_offset(&self, offset: i32) -> char;
+
+    fn character_count(&self) -> i32;
+
+    fn caret_offset(&self) -> i32;
+
+    fn set_caret_offset(&self, offset: i32) -> bool {
+        self.parent_set_caret_offset(offset)
+    }
+
+    // Returns the text at `offset` and its start and end offsets
+    fn string_at_offset(
+        &self,
+        offset: i32,
+        granularity: TextGranularity,
+    ) -> Option<(GString, i32, i32)> {
+        self.parent_string_at_offset(offset, granularity)
+    }
+
+    // Returns `(x, y, width, height)` of the character at `offset`
+    fn character_extents(&self, offset: i32, coords: CoordType) -> (i32, i32, i32, i32) {
+        self.parent_character_extents(offset, coords)
+    }
+
+    fn offset_at_point(&self, x: i32, y: i32, coords: CoordType) -> i32 {
+        self.parent_offset_at_point(x, y, coords)
+    }
+
+    fn n_selections(&self) -> i32 {
+        self.parent_n_selections()
+    }
+
+    // Returns the selected text and its start and end offsets
+    fn selection(&self, selection_num: i32) -> Option<(GString, i32, i32)> {
+        self.parent_selection(selection_num)
+    }
+}
+
+pub trait TextImplExt: TextImpl {
+    fn parent_set_caret_offset(&self, offset: i32) -> bool {
+        unsafe {
+            let type_data = Self::type_data();
+            let parent_iface =
+                type_data.as_ref().parent_interface::<Text>() as *const ffi::AtkTextIface;
+
+            if let Some(func) = (*parent_iface).set_caret_offset {
+                from_glib(func(
+                    self.obj().unsafe_cast_ref::<Text>().to_glib_none().0,
+                    offset,
+                ))
+            } else {
+                false
+            }
+        }
+    }
+
+    fn parent_string_at_offset(
+        &self,
+        offset: i32,
+        granularity: TextGranularity,
+    ) -> Option<(GString, i32, i32)> {
+        unsafe {
+            let type_data = Self::type_data();
+            let parent_iface =
+                type_data.as_ref().parent_interface::<Text>() as *const ffi::AtkTextIface;
+
+            let func = (*parent_iface).get_string_at_offset?;
+            let mut start_offset = 0;
+            let mut end_offset = 0;
+            let text: Option<GString> = from_glib_full(func(
+                self.obj().unsafe_cast_ref::<Text>().to_glib_none().0,
+                offset,
+                granularity.into_glib(),
+                &mut start_offset,
+                &mut end_offset,
+            ));
+            text.map(|text| (text, start_offset, end_offset))
+        }
+    }
+
+    fn parent_character_extents(&self, offset: i32, coords: CoordType) -> (i32, i32, i32, i32) {
+        unsafe {
+            let type_data = Self::type_data();
+            let parent_iface =
+                type_data.as_ref().parent_interface::<Text>() as *const ffi::AtkTextIface;
+
+            let mut x = -1;
+            let mut y = -1;
+            let mut width = -1;
+            let mut height = -1;
+            if let Some(func) = (*parent_iface).get_character_extents {
+                func(
+                    self.obj().unsafe_cast_ref::<Text>().to_glib_none().0,
+                    offset,
+                    &mut x,
+                    &mut y,
+                    &mut width,
+                    &mut height,
+                    coords.into_glib(),
+                );
+            }
+            (x, y, width, height)
+        }
+    }
+
+    fn parent_offset_at_point(&self, x: i32, y: i32, coords: CoordType) -> i32 {
+        unsafe {
+            let type_data = Self::type_data();
+            let parent_iface =
+                type_data.as_ref().parent_interface::<Text>() as *const ffi::AtkTextIface;
+
+            if let Some(func) = (*parent_iface).get_offset_at_point {
+                func(
+                    self.obj().unsafe_cast_ref::<Text>().to_glib_none().0,
+                    x,
+                    y,
+                    coords.into_glib(),
+                )
+            } else {
+                -1
+            }
+        }
+    }
+
+    fn parent_n_selections(&self) -> i32 {
+        unsafe {
+            let type_data = Self::type_data();
+            let parent_iface =
+                type_data.as_ref().parent_interface::<Text>() as *const ffi::AtkTextIface;
+
+            if let Some(func) = (*parent_iface).get_n_selections {
+                func(self.obj().unsafe_cast_ref::<Text>().to_glib_none().0)
+            } else {
+                0
+            }
+        }
+    }
+
+    fn parent_selection(&self, selection_num: i32) -> Option<(GString, i32, i32)> {
+        unsafe {
+            let type_data = Self::type_data();
+            let parent_iface =
+                type_data.as_ref().parent_interface::<Text>() as *const ffi::AtkTextIface;
+
+            let func = (*parent_iface).get_selection?;
+            let mut start_offset = 0;
+            let mut end_offset = 0;
+            let text: Option<GString> = from_glib_full(func(
+                self.obj().unsafe_cast_ref::<Text>().to_glib_none().0,
+                selection_num,
+                &mut start_offset,
+                &mut end_offset,
+            ));
+            text.map(|text| (text, start_offset, end_offset))
+        }
+    }
+}
+
+impl<T: TextImpl> TextImplExt for T {}
+
+unsafe impl<T: TextImpl> IsImplementable<T> for Text {
+    fn interface_init(iface: &mut glib::Interface<Self>) {
+        let iface = iface.as_mut();
+
+        iface.get_text = Some(text_get_text::<T>);
+        iface.get_character_at_offset = Some(text_get_character_at_offset::<T>);
+        iface.get_character_count = Some(text_get_character_count::<T>);
+        iface.get_caret_offset = Some(text_get_caret_offset::<T>);
+        iface.set_caret_offset = Some(text_set_caret_offset::<T>);
+        iface.get_string_at_offset = Some(text_get_string_at_offset::<T>);
+        iface.get_character_extents = Some(text_get_character_extents::<T>);
+        iface.get_offset_at_point = Some(text_get_offset_at_point::<T>);
+        iface.get_n_selections = Some(text_get_n_selections::<T>);
+        iface.get_selection = Some(text_get_selection::<T>);
+    }
+}
+
+// Writes the offsets to the nullable out parameters and returns the text as `transfer full`
+unsafe fn text_with_offsets(
+    res: Option<(GString, i32, i32)>,
+    start_offsetptr: *mut i32,
+    end_offsetptr: *mut i32,
+) -> *mut libc::c_char {
+    unsafe {
+        let (text, start_offset, end_offset) = match res {
+            Some((text, start_offset, end_offset)) => (Some(text), start_offset, end_offset),
+            None => (None, -1, -1),
+        };
+        if !start_offsetptr.is_null() {
+            *start_offsetptr = start_offset;
+        }
+        if !end_offsetptr.is_null() {
+            *end_offsetptr = end_offset;
+        }
+        text.to_glib_full()
+    }
+}
+
+unsafe extern "C" fn text_get_text<T: TextImpl>(
+    text: *mut ffi::AtkText,
+    start_offset: i32,
+    end_offset: i32,
+) -> *mut libc::c_char {
+    unsafe {
+        let instance = &*(text as *mut T::Instance);
+        let imp = instance.imp();
+
+        imp.text(start_offset, end_offset).to_glib_full()
+    }
+}
+
+unsafe extern "C" fn text_get_character_at_offset<T: TextImpl>(
+    text: *mut ffi::AtkText,
+    offset: i32,
+) -> u32 {
+    unsafe {
+        let instance = &*(text as *mut T::Instance);
+        let imp = instance.imp();
+
+        imp.character_at_offset(offset) as u32
+    }
+}
+
+unsafe extern "C" fn text_get_character_count<T: TextImpl>(text: *mut ffi::AtkText) -> i32 {
+    unsafe {
+        let instance = &*(text as *mut T::Instance);
+        let imp = instance.imp();
+
+        imp.character_count()
+    }
+}
+
+unsafe extern "C" fn text_get_caret_offset<T: TextImpl>(text: *mut ffi::AtkText) -> i32 {
+    unsafe {
+        let instance = &*(text as *mut T::Instance);
+        let imp = instance.imp();
+
+        imp.caret_offset()
+    }
+}
+
+unsafe extern "C" fn text_set_caret_offset<T: TextImpl>(
+    text: *mut ffi::AtkText,
+    offset: i32,
+) -> glib::ffi::gboolean {
+    unsafe {
+        let instance = &*(text as *mut T::Instance);
+        let imp = instance.imp();
+
+        imp.set_caret_offset(offset).into_glib()
+    }
+}
+
+unsafe extern "C" fn text_get_string_at_offset<T: TextImpl>(
+    text: *mut ffi::AtkText,
+    offset: i32,
+    granularity: ffi::AtkTextGranularity,
+    start_offsetptr: *mut i32,
+    end_offsetptr: *mut i32,
+) -> *mut libc::c_char {
+    unsafe {
+        let instance = &*(text as *mut T::Instance);
+        let imp = instance.imp();
+
+        text_with_offsets(
+            imp.string_at_offset(offset, from_glib(granularity)),
+            start_offsetptr,
+            end_offsetptr,
+        )
+    }
+}
+
+unsafe extern "C" fn text_get_character_extents<T: TextImpl>(
+    text: *mut ffi::AtkText,
+    offset: i32,
+    xptr: *mut i32,
+    yptr: *mut i32,
+    widthptr: *mut i32,
+    heightptr: *mut i32,
+    coords: ffi::AtkCoordType,
+) {
+    unsafe {
+        let instance = &*(text as *mut T::Instance);
+        let imp = instance.imp();
+
+        let (x, y, width, height) = imp.character_extents(offset, from_glib(coords));
+        // All of the out parameters are nullable
+        for (ptr, value) in [(xptr, x), (yptr, y), (widthptr, width), (heightptr, height)] {
+            if !ptr.is_null() {
+                *ptr = value;
+            }
+        }
+    }
+}
+
+unsafe extern "C" fn text_get_offset_at_point<T: TextImpl>(
+    text: *mut ffi::AtkText,
+    x: i32,
+    y: i32,
+    coords: ffi::AtkCoordType,
+) -> i32 {
+    unsafe {
+        let instance = &*(text as *mut T::Instance);
+        let imp = instance.imp();
+
+        imp.offset_at_point(x, y, from_glib(coords))
+    }
+}
+
+unsafe extern "C" fn text_get_n_selections<T: TextImpl>(text: *mut ffi::AtkText) -> i32 {
+    unsafe {
+        let instance = &*(text as *mut T::Instance);
+        let imp = instance.imp();
+
+        imp.n_selections()
+    }
+}
+
+unsafe extern "C" fn text_get_selection<T: TextImpl>(
+    text: *mut ffi::AtkText,
+    selection_num: i32,
+    start_offsetptr: *mut i32,
+    end_offsetptr: *mut i32,
+) -> *mut libc::c_char {
+    unsafe {
+        let instance = &*(text as *mut T::Instance);
+        let imp = instance.imp();
+
+        text_with_offsets(imp.selection(selection_num), start_offsetptr, end_offsetptr)
+    }
+}
@@ -0,0 +1,165 @@
+// Take a look at the license at the top of the repository in the LICENSE file.
+
+use glib::GString;
+use glib::subclass::prelude::*;
+use glib::translate::*;
+
+use crate::{Range, Value, ffi, prelude::*};
+
+pub trait ValueImpl: ObjectImpl + ObjectSubclass<Type: IsA<Value>> {
+    // Returns the current value and an optional textual description of it
+    fn value_and_text(&self) -> (f64, Option<GString>);
+
+    fn range(&self) -> Option<Range>;
+
+    fn increment(&self) -> f64 {
+        self.parent_increment()
+    }
+
+    fn set_value(&self, new_value: f64);
+}
+
+pub trait ValueImplExt: ValueImpl {
+    fn parent_increment(&self) -> f64 {
+        unsafe {
+            let type_data = Self::type_data();
+            let parent_iface =
+                type_data.as_ref().parent_interface::<Value>() as *const ffi::AtkValueIface;
+
+            if let Some(func) = (*parent_iface).get_increment {
+                func(self.obj().unsafe_cast_ref::<Value>().to_glib_none().0)
+            } else {
+                0.0
+            }
+        }
+    }
+}
+
+impl<T: ValueImpl> ValueImplExt for T {}
+
+unsafe impl<T: ValueImpl> IsImplementable<T> for Value {
+    fn interface_init(iface: &mut glib::Interface<Self>) {
+        let iface = iface.as_mut();
+
+        iface.get_value_and_text = Some(value_get_value_and_text::<T>);
+        iface.get_range = Some(value_get_range::<T>);
+        iface.get_increment = Some(value_get_increment::<T>);
+        iface.set_value = Some(value_set_value::<T>);
+
+        // The deprecated accessors are still used by some assistive technologies, so
+        // implement them in terms of the new ones
+        iface.get_current_value = Some(value_get_current_value::<T>);
+        iface.get_minimum_value = Some(value_get_minimum_value::<T>);
+        iface.get_maximum_value = Some(value_get_maximum_value::<T>);
+        iface.set_current_value = Some(value_set_current_value::<T>);
+    }
+}
+
+unsafe extern "C" fn value_get_value_and_text<T: ValueImpl>(
+    value: *mut ffi::AtkValue,
+    valueptr: *mut f64,
+    textptr: *mut *mut libc::c_char,
+) {
+    unsafe {
+        let instance = &*(value as *mut T::Instance);
+        let imp = instance.imp();
+
+        let (v, text) = imp.value_and_text();
+        if !valueptr.is_null() {
+            *valueptr = v;
+        }
+        if !textptr.is_null() {
+            *textptr = text.to_glib_full();
+        }
+    }
+}
+
+unsafe extern "C" fn value_get_range<T: ValueImpl>(
+    value: *mut ffi::AtkValue,
+) -> *mut ffi::AtkRange {
+    unsafe {
+        let instance = &*(value as *mut T::Instance);
+        let imp = instance.imp();
+
+        imp.range().to_glib_full()
+    }
+}
+
+unsafe extern "C" fn value_get_increment<T: ValueImpl>(value: *mut ffi::AtkValue) -> f64 {
+    unsafe {
+        let instance = &*(value as *mut T::Instance);
+        let imp = instance.imp();
+
+        imp.increment()
+    }
+}
+
+unsafe extern "C" fn value_set_value<T: ValueImpl>(value: *mut ffi::AtkValue, new_value: f64) {
+    unsafe {
+        let instance = &*(value as *mut T::Instance);
+        let imp = instance.imp();
+
+        imp.set_value(new_value)
+    }
+}
+
+unsafe extern "C" fn value_get_current_value<T: ValueImpl>(
+    value: *mut ffi::AtkValue,
+    valueptr: *mut glib::gobject_ffi::GValue,
+) {
+    unsafe {
+        let instance = &*(value as *mut T::Instance);
+        let imp = instance.imp();
+
+        let (v, _) = imp.value_and_text();
+        // `valueptr` has not been initialized, so no need to unset first.
+        std::ptr::write(valueptr, v.to_value().into_raw());
+    }
+}
+
+unsafe extern "C" fn value_get_minimum_value<T: ValueImpl>(
+    value: *mut ffi::AtkValue,
+    valueptr: *mut glib::gobject_ffi::GValue,
+) {
+    unsafe {
+        let instance = &*(value as *mut T::Instance);
+        let imp = instance.imp();
+
+        if let Some(mut range) = imp.range() {
+            std::ptr::write(valueptr, range.lower_limit().to_value().into_raw());
+        }
+    }
+}
+
+unsafe extern "C" fn value_get_maximum_value<T: ValueImpl>(
+    value: *mut ffi::AtkValue,
+    valueptr: *mut glib::gobject_ffi::GValue,
+) {
+    unsafe {
+        let instance = &*(value as *mut T::Instance);
+        let imp = instance.imp();
+
+        if let Some(mut range) = imp.range() {
+            std::ptr::write(valueptr, range.upper_limit().to_value().into_raw());
+        }
+    }
+}
+
+unsafe extern "C" fn value_set_current_value<T: ValueImpl>(
+    value: *mut ffi::AtkValue,
+    valueptr: *const glib::gobject_ffi::GValue,
+) -> glib::ffi::gboolean {
+    unsafe {
+        let instance = &*(value as *mut T::Instance);
+        let imp = instance.imp();
+
+        let v = &*(valueptr as *const glib::Value);
+        match v.transform::<f64>().ok().and_then(|v| v.get::<f64>().ok()) {
+            Some(new_value) => {
+                imp.set_value(new_value);
+                glib::ffi::GTRUE
+            }
+            None => glib::ffi::GFALSE,
+        }
+    }
+}
@@ -0,0 +1,63 @@
+// Take a look at the license at the top of the repository in the LICENSE file.
+
+use atk::glib;
+use atk::prelude::*;
+use atk::subclass::prelude::*;
+
+mod imp {
+    use super::*;
+
+    pub struct Chart {
+        pub bars: Vec<atk::Object>,
+    }
+
+    #[glib::object_subclass]
+    impl ObjectSubclass for Chart {
+        const NAME: &'static str = "AtkTestChart";
+        type Type = super::Chart;
+        type ParentType = atk::Object;
+
+        fn new() -> Self {
+            Self {
+                bars: (0..3).map(|_| glib::Object::new()).collect(),
+            }
+        }
+    }
+
+    impl ObjectImpl for Chart {}
+
+    impl AtkObjectImpl for Chart {
+        fn name(&self) -> Option<glib::GString> {
+            Some("Sales".into())
+        }
+
+        fn role(&self) -> atk::Role {
+            atk::Role::Chart
+        }
+
+        fn n_children(&self) -> i32 {
+            self.bars.len() as i32
+        }
+
+        fn ref_child(&self, i: i32) -> Option<atk::Object> {
+            self.bars.get(usize::try_from(i).ok()?).cloned()
+        }
+    }
+}
+
+glib::wrapper! {
+    pub struct Chart(ObjectSubclass<imp::Chart>) @extends atk::Object;
+}
+
+#[test]
+fn object_impl() {
+    let chart = glib::Object::new::<Chart>();
+    assert_eq!(chart.name().as_deref(), Some("Sales"));
+    assert_eq!(chart.role(), atk::Role::Chart);
+    assert_eq!(chart.n_accessible_children(), 3);
+    assert_eq!(
+        chart.ref_accessible_child(1).as_ref(),
+        Some(&chart.imp().bars[1])
+    );
+    assert_eq!(chart.ref_accessible_child(3), None);
+}
@@ -449,11 +449,6 @@ pub trait TreeModelExt: IsA<TreeModel> + 'static {
             )
         }
     }
-
-    //#[doc(alias = "rows-reordered")]
-    //fn connect_rows_reordered<Unsupported or ignored types>(&self, f: F) -> SignalHandlerId {
-    //    Unimplemented new_order: *.Pointer
-    //}
 }
 
 impl<O: IsA<TreeModel>> TreeModelExt for O {}
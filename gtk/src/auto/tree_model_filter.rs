@@ -90,11 +90,6 @@ pub trait TreeModelFilterExt: IsA<TreeModelFilter> + 'static {
         }
     }
 
-    //#[doc(alias = "gtk_tree_model_filter_set_modify_func")]
-    //fn set_modify_func<P: Fn(&TreeModel, &TreeIter, &glib::Value, i32) + 'static>(&self, types: /*Unimplemented*/&CArray TypeId { ns_id: 0, id: 30 }, func: P) {
-    //    unsafe { TODO: call ffi:gtk_tree_model_filter_set_modify_func() }
-    //}
-
     #[doc(alias = "gtk_tree_model_filter_set_visible_column")]
     fn set_visible_column(&self, column: i32) {
         unsafe {
@@ -0,0 +1,301 @@
+// Take a look at the license at the top of the repository in the LICENSE file.
+
+use std::fmt;
+use std::rc::Rc;
+
+use glib::SignalHandlerId;
+
+use crate::prelude::*;
+use crate::{
+    CellRenderer, CellRendererText, CellRendererToggle, ListStore, TreeIter, TreeModel,
+    TreeModelFilter, TreeModelSort, TreePath, TreeStore,
+};
+
+type ParseFn<T> = dyn Fn(&str) -> Result<T, String>;
+type RejectedFn = dyn Fn(&TreePath, &str, &str);
+
+// rustdoc-stripper-ignore-next
+/// Writes the edits of a cell renderer back to a column of a [`ListStore`] or [`TreeStore`].
+///
+/// The model given to [`new()`](Self::new) is the one the view shows. It can be the store
+/// itself, or any stack of [`TreeModelSort`]s and [`TreeModelFilter`]s on top of it: the
+/// edited row is converted to the row of the store underneath, and `column` is a column of
+/// that store.
+///
+/// Text entered in a [`CellRendererText`], or one of its subclasses
+/// [`CellRendererSpin`](crate::CellRendererSpin) and
+/// [`CellRendererCombo`](crate::CellRendererCombo), is converted by the
+/// [`parse()`](Self::parse) closure, and stored as is if none is set. When the closure returns
+/// an error, the model is left unchanged and the [`on_rejected()`](Self::on_rejected) callback
+/// is called instead. A [`CellRendererToggle`] flips the boolean in `column`.
+///
+/// ```no_run
+/// # use gtk::prelude::*;
+/// # use gtk::glib;
+/// const COL_NAME: i32 = 0;
+/// const COL_AGE: i32 = 1;
+///
+/// let store = gtk::ListStore::new(&[glib::Type::STRING, glib::Type::U32]);
+/// let sorted = gtk::TreeModelSort::new(&store);
+/// let tree_view = gtk::TreeView::with_model(&sorted);
+///
+/// let renderer = gtk::CellRendererText::new();
+/// tree_view.append_column(&gtk::TreeViewColumn::with_attributes(
+///     "Age",
+///     &renderer,
+///     &[("text", COL_AGE)],
+/// ));
+/// gtk::CellEditBinding::new(&sorted, COL_AGE)
+///     .parse(|text| text.trim().parse::<u32>().map_err(|err| err.to_string()))
+///     .on_rejected(|_path, text, error| eprintln!("Invalid age {text:?}: {error}"))
+///     .bind(&renderer);
+///
+/// // Or, with the column builder:
+/// tree_view
+///     .column_builder()
+///     .text("Name", COL_NAME)
+///     .bind_edits(gtk::CellEditBinding::new(&sorted, COL_NAME))
+///     .build();
+/// ```
+pub struct CellEditBinding<T> {
+    model: TreeModel,
+    column: i32,
+    parse: Rc<ParseFn<T>>,
+    on_rejected: Option<Rc<RejectedFn>>,
+}
+
+impl CellEditBinding<String> {
+    // rustdoc-stripper-ignore-next
+    /// Creates a binding writing edits to `column` of the store underneath `model`.
+    ///
+    /// Until [`parse()`](Self::parse) is called, the edited text is stored as is, converted
+    /// to the type of the column if GLib can transform a string to it.
+    pub fn new(model: &impl IsA<TreeModel>, column: impl Into<i32>) -> Self {
+        skip_assert_initialized!();
+        Self {
+            model: model.clone().upcast(),
+            column: column.into(),
+            parse: Rc::new(|text| Ok(text.to_owned())),
+            on_rejected: None,
+        }
+    }
+}
+
+impl<T: ToValue + 'static> CellEditBinding<T> {
+    // rustdoc-stripper-ignore-next
+    /// Sets the closure converting and validating the edited text. The model is only changed
+    /// if it returns `Ok`.
+    pub fn parse<U, F: Fn(&str) -> Result<U, String> + 'static>(
+        self,
+        parse: F,
+    ) -> CellEditBinding<U> {
+        CellEditBinding {
+            model: self.model,
+            column: self.column,
+            parse: Rc::new(parse),
+            on_rejected: self.on_rejected,
+        }
+    }
+
+    // rustdoc-stripper-ignore-next
+    /// Sets the callback called with the path of the row in the model of the view, the edited
+    /// text and the error when an edit can't be written to the model: when the
+    /// [`parse()`](Self::parse) closure rejects it, when the value can't be converted to the
+    /// type of the column, or when the row is not part of the store. The text is empty for
+    /// toggles. Without this callback, rejected edits are logged as warnings.
+    pub fn on_rejected<F: Fn(&TreePath, &str, &str) + 'static>(self, on_rejected: F) -> Self {
+        Self {
+            on_rejected: Some(Rc::new(on_rejected)),
+            ..self
+        }
+    }
+
+    // rustdoc-stripper-ignore-next
+    /// Makes `renderer` editable and writes its edits to the model.
+    ///
+    /// Returns the handler of the `edited` or `toggled` signal of `renderer`, to stop writing
+    /// edits back by disconnecting it.
+    ///
+    /// # Panics
+    ///
+    /// If `renderer` is neither a [`CellRendererText`] nor a [`CellRendererToggle`].
+    pub fn bind(self, renderer: &impl IsA<CellRenderer>) -> SignalHandlerId {
+        let renderer = renderer.as_ref();
+        if let Some(renderer) = renderer.downcast_ref::<CellRendererText>() {
+            renderer.set_editable(true);
+            renderer.connect_edited(move |_, path, text| self.text_edited(&path, text))
+        } else if let Some(renderer) = renderer.downcast_ref::<CellRendererToggle>() {
+            renderer.set_activatable(true);
+            renderer.connect_toggled(move |_, path| self.toggled(&path))
+        } else {
+            panic!(
+                "can't bind the edits of a {}, only text and toggle renderers are supported",
+                renderer.type_()
+            )
+        }
+    }
+
+    fn text_edited(&self, path: &TreePath, text: &str) {
+        let res = (self.parse)(text).and_then(|value| {
+            let (store, iter) = self.store_row(path)?;
+            set_store_value(&store, &iter, self.column, &value.to_value())
+        });
+        self.handle_result(path, text, res);
+    }
+
+    fn toggled(&self, path: &TreePath) {
+        let res = self.store_row(path).and_then(|(store, iter)| {
+            let active = store
+                .value(&iter, self.column)
+                .get::<bool>()
+                .map_err(|_| format!("column {} is not a boolean column", self.column))?;
+            set_store_value(&store, &iter, self.column, &(!active).to_value())
+        });
+        self.handle_result(path, "", res);
+    }
+
+    // Errors are reported instead of panicking, as this runs in signal handlers
+    fn handle_result(&self, path: &TreePath, text: &str, res: Result<(), String>) {
+        let Err(error) = res else {
+            return;
+        };
+        match &self.on_rejected {
+            Some(on_rejected) => on_rejected(path, text, &error),
+            None => glib::g_warning!(
+                "Gtk",
+                "Rejected the edit {:?} of row {} in column {}: {}",
+                text,
+                path,
+                self.column,
+                error
+            ),
+        }
+    }
+
+    // Converts `path` in the model of the view to the store at the bottom of the model stack
+    fn store_row(&self, path: &TreePath) -> Result<(TreeModel, TreeIter), String> {
+        let mut model = self.model.clone();
+        let mut path = path.clone();
+        loop {
+            if let Some(sort) = model.downcast_ref::<TreeModelSort>() {
+                path = sort
+                    .convert_path_to_child_path(&path)
+                    .ok_or_else(|| format!("row {path} is not part of the sorted model"))?;
+                model = sort.model();
+            } else if let Some(filter) = model.downcast_ref::<TreeModelFilter>() {
+                path = filter
+                    .convert_path_to_child_path(&path)
+                    .ok_or_else(|| format!("row {path} is not part of the filtered model"))?;
+                model = filter
+                    .model()
+                    .ok_or_else(|| "the filtered model has no child model".to_owned())?;
+            } else {
+                break;
+            }
+        }
+        let iter = model
+            .iter(&path)
+            .ok_or_else(|| format!("row {path} is not part of the model"))?;
+        Ok((model, iter))
+    }
+}
+
+impl<T> fmt::Debug for CellEditBinding<T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("CellEditBinding")
+            .field("model", &self.model)
+            .field("column", &self.column)
+            .finish()
+    }
+}
+
+fn set_store_value(
+    store: &TreeModel,
+    iter: &TreeIter,
+    column: i32,
+    value: &glib::Value,
+) -> Result<(), String> {
+    if column < 0 || column >= store.n_columns() {
+        return Err(format!("the model has no column {column}"));
+    }
+    let type_ = store.column_type(column);
+    let value = value.transform_with_type(type_).map_err(|_| {
+        format!(
+            "can't store a {} in column {column} of type {type_}",
+            value.type_()
+        )
+    })?;
+    if let Some(store) = store.downcast_ref::<ListStore>() {
+        store.set_value(iter, column as u32, &value);
+    } else if let Some(store) = store.downcast_ref::<TreeStore>() {
+        store.set_value(iter, column as u32, &value);
+    } else {
+        return Err(format!(
+            "can't write edits to a {}, only ListStore and TreeStore are supported",
+            store.type_()
+        ));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use std::cell::RefCell;
+
+    use super::*;
+    use crate::{SortColumn, SortType, TEST_THREAD_WORKER};
+
+    fn names(store: &ListStore) -> Vec<String> {
+        store
+            .rows()
+            .map(|iter| store.value(&iter, 0).get::<String>().unwrap())
+            .collect()
+    }
+
+    #[test]
+    fn edit_through_sort_and_filter() {
+        TEST_THREAD_WORKER
+            .push(|| {
+                let store = ListStore::new(&[glib::Type::STRING, glib::Type::U32]);
+                for name in ["c", "a", "b"] {
+                    store.insert_with_values(None, &[(0, &name), (1, &0u32)]);
+                }
+                let sort = TreeModelSort::new(&store);
+                sort.set_sort_column_id(SortColumn::Index(0), SortType::Ascending);
+                let filter = TreeModelFilter::new(&sort, None);
+                filter.set_visible_func(|model, iter| {
+                    model.value(iter, 0).get::<String>().unwrap() != "a"
+                });
+
+                // The first row of the filter is "b", the last row of the store
+                let path = TreePath::from_indicesv(&[0]);
+                CellEditBinding::new(&filter, 0).text_edited(&path, "B");
+                assert_eq!(names(&store), ["c", "a", "B"]);
+
+                let rejected = Rc::new(RefCell::new(Vec::new()));
+                let on_rejected = {
+                    let rejected = rejected.clone();
+                    move |path: &TreePath, text: &str, _error: &str| {
+                        rejected.borrow_mut().push(format!("{path} {text}"));
+                    }
+                };
+
+                // The default parse stores strings, which can't be converted to `u32`
+                CellEditBinding::new(&filter, 1)
+                    .on_rejected(on_rejected.clone())
+                    .text_edited(&path, "1");
+                let binding = CellEditBinding::new(&filter, 1)
+                    .parse(|text| text.parse::<u32>().map_err(|err| err.to_string()))
+                    .on_rejected(on_rejected);
+                binding.text_edited(&path, "x");
+                binding.text_edited(&TreePath::from_indicesv(&[5]), "2");
+                binding.text_edited(&path, "3");
+
+                let last = store.iter_nth_child(None, 2).unwrap();
+                assert_eq!(store.value(&last, 1).get::<u32>().unwrap(), 3);
+                assert_eq!(*rejected.borrow(), ["0 1", "0 x", "5 2"]);
+            })
+            .expect("Failed to schedule a test call");
+        while TEST_THREAD_WORKER.unprocessed() > 0 {}
+    }
+}
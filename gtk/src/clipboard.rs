@@ -2,11 +2,15 @@
 
 use crate::SelectionData;
 use crate::TargetEntry;
+use crate::TargetList;
 use crate::{Clipboard, ffi};
 use glib::ffi::gpointer;
+use glib::prelude::*;
 use glib::translate::*;
-use libc::{c_char, c_uint};
+use libc::{c_char, c_int, c_uint};
 use std::boxed::Box as Box_;
+use std::future::Future;
+use std::pin::Pin;
 
 impl Clipboard {
     #[doc(alias = "gtk_clipboard_set_with_data")]
@@ -96,4 +100,333 @@ impl Clipboard {
             );
         }
     }
+
+    // rustdoc-stripper-ignore-next
+    /// Requests the list of targets the clipboard contents are available in.
+    ///
+    /// The callback is called with `None` if there was no clipboard owner.
+    #[doc(alias = "gtk_clipboard_request_targets")]
+    pub fn request_targets<P: FnOnce(&Clipboard, Option<&[gdk::Atom]>) + 'static>(
+        &self,
+        callback: P,
+    ) {
+        let callback_data: Box_<P> = Box_::new(callback);
+        unsafe extern "C" fn callback_func<
+            P: FnOnce(&Clipboard, Option<&[gdk::Atom]>) + 'static,
+        >(
+            clipboard: *mut ffi::GtkClipboard,
+            atoms: *mut gdk::ffi::GdkAtom,
+            n_atoms: c_int,
+            data: glib::ffi::gpointer,
+        ) {
+            unsafe {
+                let clipboard = from_glib_borrow(clipboard);
+                let targets: Option<Vec<gdk::Atom>> = (!atoms.is_null())
+                    .then(|| FromGlibContainer::from_glib_none_num(atoms, n_atoms as usize));
+                let callback: Box_<P> = Box_::from_raw(data as *mut _);
+                (*callback)(&clipboard, targets.as_deref());
+            }
+        }
+        let callback = Some(callback_func::<P> as _);
+        let super_callback0: Box_<P> = callback_data;
+        unsafe {
+            ffi::gtk_clipboard_request_targets(
+                self.to_glib_none().0,
+                callback,
+                Box_::into_raw(super_callback0) as *mut _,
+            );
+        }
+    }
+
+    // rustdoc-stripper-ignore-next
+    /// Makes `owner` the owner of the clipboard and offers the formats of `provider`.
+    ///
+    /// The closures of `provider` are called whenever another application requests the
+    /// contents in one of the formats, until the contents are replaced, the clipboard is
+    /// cleared or `owner` is finalized. Returns `false` if setting the contents failed.
+    ///
+    /// ```no_run
+    /// let clipboard = gtk::Clipboard::get(&gtk::gdk::SELECTION_CLIPBOARD);
+    /// let window = gtk::Window::new(gtk::WindowType::Toplevel);
+    ///
+    /// let provider = gtk::ClipboardProvider::new()
+    ///     .text(|| "Name\tAge\nAlice\t42".to_owned())
+    ///     .mime("text/html", || {
+    ///         b"<table><tr><td>Alice</td><td>42</td></tr></table>".to_vec()
+    ///     })
+    ///     .on_clear(|_| println!("Clipboard contents replaced"));
+    /// clipboard.set_with_owner(&window, provider);
+    /// ```
+    #[doc(alias = "gtk_clipboard_set_with_owner")]
+    pub fn set_with_owner(
+        &self,
+        owner: &impl IsA<glib::Object>,
+        provider: ClipboardProvider,
+    ) -> bool {
+        unsafe extern "C" fn get_func(
+            clipboard: *mut ffi::GtkClipboard,
+            selection_data: *mut ffi::GtkSelectionData,
+            info: c_uint,
+            _owner: gpointer,
+        ) {
+            unsafe {
+                let clipboard: Borrowed<Clipboard> = from_glib_borrow(clipboard);
+                let Some(provider) = clipboard.qdata::<ClipboardProvider>(provider_quark()) else {
+                    return;
+                };
+                if let Some(handler) = provider.as_ref().handlers.get(info as usize) {
+                    handler(&clipboard, &from_glib_borrow(selection_data));
+                }
+            }
+        }
+        unsafe extern "C" fn clear_func(clipboard: *mut ffi::GtkClipboard, _owner: gpointer) {
+            unsafe {
+                let clipboard: Borrowed<Clipboard> = from_glib_borrow(clipboard);
+                if let Some(provider) = clipboard.steal_qdata::<ClipboardProvider>(provider_quark())
+                {
+                    provider.clear(&clipboard);
+                }
+            }
+        }
+
+        unsafe {
+            let mut n_targets = 0;
+            let targets = ffi::gtk_target_table_new_from_list(
+                provider.targets.to_glib_none().0,
+                &mut n_targets,
+            );
+            let success: bool = from_glib(ffi::gtk_clipboard_set_with_owner(
+                self.to_glib_none().0,
+                targets,
+                n_targets as c_uint,
+                Some(get_func),
+                Some(clear_func),
+                owner.as_ref().to_glib_none().0,
+            ));
+            ffi::gtk_target_table_free(targets, n_targets);
+
+            if success {
+                // When the owner stays the same, GTK replaces the contents without calling
+                // `clear_func`, so clear the previous provider here.
+                if let Some(previous) = self.steal_qdata::<ClipboardProvider>(provider_quark()) {
+                    previous.clear(self);
+                }
+                self.set_qdata(provider_quark(), provider);
+            }
+            success
+        }
+    }
+
+    // rustdoc-stripper-ignore-next
+    /// Requests the contents of the clipboard as text.
+    ///
+    /// Unlike [`wait_for_text()`](Self::wait_for_text), this does not run a nested main
+    /// loop. The request is started when the future is first polled, so it has to be spawned
+    /// on the main context, for example with [`glib::MainContext::spawn_local()`].
+    ///
+    /// ```no_run
+    /// # async fn run() {
+    /// let clipboard = gtk::Clipboard::get(&gtk::gdk::SELECTION_CLIPBOARD);
+    ///
+    /// if let Some(text) = clipboard.read_text_future().await {
+    ///     println!("Clipboard: {text}");
+    /// }
+    /// # }
+    /// ```
+    #[doc(alias = "gtk_clipboard_request_text")]
+    pub fn read_text_future(
+        &self,
+    ) -> Pin<Box_<dyn Future<Output = Option<glib::GString>> + 'static>> {
+        let clipboard = self.clone();
+        Box_::pin(async move {
+            let (sender, receiver) = futures_channel::oneshot::channel();
+
+            clipboard.request_text(move |_, text| {
+                let _ = sender.send(text.map(glib::GString::from));
+            });
+
+            receiver.await.unwrap_or_default()
+        })
+    }
+
+    // rustdoc-stripper-ignore-next
+    /// Requests the contents of the clipboard as image.
+    ///
+    /// See [`read_text_future()`](Self::read_text_future) for how to use the future.
+    #[doc(alias = "gtk_clipboard_request_image")]
+    pub fn read_image_future(
+        &self,
+    ) -> Pin<Box_<dyn Future<Output = Option<gdk_pixbuf::Pixbuf>> + 'static>> {
+        let clipboard = self.clone();
+        Box_::pin(async move {
+            let (sender, receiver) = futures_channel::oneshot::channel();
+
+            clipboard.request_image(move |_, pixbuf| {
+                let _ = sender.send(pixbuf.cloned());
+            });
+
+            receiver.await.unwrap_or_default()
+        })
+    }
+
+    // rustdoc-stripper-ignore-next
+    /// Requests the contents of the clipboard as URIs.
+    ///
+    /// See [`read_text_future()`](Self::read_text_future) for how to use the future.
+    #[doc(alias = "gtk_clipboard_request_uris")]
+    pub fn read_uris_future(&self) -> Pin<Box_<dyn Future<Output = Vec<glib::GString>> + 'static>> {
+        let clipboard = self.clone();
+        Box_::pin(async move {
+            let (sender, receiver) = futures_channel::oneshot::channel();
+
+            clipboard.request_uris(move |_, uris| {
+                let _ = sender.send(uris.to_vec());
+            });
+
+            receiver.await.unwrap_or_default()
+        })
+    }
+
+    // rustdoc-stripper-ignore-next
+    /// Requests the list of targets the clipboard contents are available in, or `None` if
+    /// there was no clipboard owner.
+    ///
+    /// See [`read_text_future()`](Self::read_text_future) for how to use the future.
+    #[doc(alias = "gtk_clipboard_request_targets")]
+    pub fn read_targets_future(
+        &self,
+    ) -> Pin<Box_<dyn Future<Output = Option<Vec<gdk::Atom>>> + 'static>> {
+        let clipboard = self.clone();
+        Box_::pin(async move {
+            let (sender, receiver) = futures_channel::oneshot::channel();
+
+            clipboard.request_targets(move |_, targets| {
+                let _ = sender.send(targets.map(|targets| targets.to_vec()));
+            });
+
+            receiver.await.unwrap_or_default()
+        })
+    }
+
+    // rustdoc-stripper-ignore-next
+    /// Requests the contents of the clipboard in the format given by `target`, or `None` if
+    /// the contents could not be retrieved in that format.
+    ///
+    /// See [`read_text_future()`](Self::read_text_future) for how to use the future.
+    #[doc(alias = "gtk_clipboard_request_contents")]
+    pub fn read_contents_future(
+        &self,
+        target: &gdk::Atom,
+    ) -> Pin<Box_<dyn Future<Output = Option<SelectionData>> + 'static>> {
+        let clipboard = self.clone();
+        let target = *target;
+        Box_::pin(async move {
+            let (sender, receiver) = futures_channel::oneshot::channel();
+
+            clipboard.request_contents(&target, move |_, selection_data| {
+                // A negative length signals that the contents could not be retrieved
+                let contents = (selection_data.length() >= 0).then(|| selection_data.clone());
+                let _ = sender.send(contents);
+            });
+
+            receiver.await.unwrap_or_default()
+        })
+    }
+}
+
+fn provider_quark() -> glib::Quark {
+    glib::Quark::from_str("gtk-rs-clipboard-provider")
+}
+
+type ProviderHandler = Box_<dyn Fn(&Clipboard, &SelectionData) + 'static>;
+
+// rustdoc-stripper-ignore-next
+/// The formats offered by [`Clipboard::set_with_owner()`].
+///
+/// Each format comes with a closure that is called to produce the data when another
+/// application requests it, so the data is only converted into the formats that are used.
+pub struct ClipboardProvider {
+    targets: TargetList,
+    handlers: Vec<ProviderHandler>,
+    clear: Option<Box_<dyn FnOnce(&Clipboard) + 'static>>,
+}
+
+impl Default for ClipboardProvider {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl ClipboardProvider {
+    pub fn new() -> Self {
+        assert_initialized_main_thread!();
+        Self {
+            targets: TargetList::new(&[]),
+            handlers: Vec::new(),
+            clear: None,
+        }
+    }
+
+    fn next_info(&self) -> u32 {
+        self.handlers.len() as u32
+    }
+
+    // rustdoc-stripper-ignore-next
+    /// Offers the contents as text, in all of the text formats known to GTK.
+    pub fn text<F: Fn() -> String + 'static>(mut self, f: F) -> Self {
+        self.targets.add_text_targets(self.next_info());
+        self.handlers.push(Box_::new(move |_, selection_data| {
+            selection_data.set_text(&f());
+        }));
+        self
+    }
+
+    // rustdoc-stripper-ignore-next
+    /// Offers the contents as a list of URIs, for example as `text/uri-list`.
+    pub fn uris<F: Fn() -> Vec<String> + 'static>(mut self, f: F) -> Self {
+        self.targets.add_uri_targets(self.next_info());
+        self.handlers.push(Box_::new(move |_, selection_data| {
+            let uris = f();
+            let uris = uris.iter().map(String::as_str).collect::<Vec<_>>();
+            selection_data.set_uris(&uris);
+        }));
+        self
+    }
+
+    // rustdoc-stripper-ignore-next
+    /// Offers the contents as an image, in all of the image formats gdk-pixbuf can write,
+    /// for example `image/png`.
+    pub fn image<F: Fn() -> gdk_pixbuf::Pixbuf + 'static>(mut self, f: F) -> Self {
+        self.targets.add_image_targets(self.next_info(), true);
+        self.handlers.push(Box_::new(move |_, selection_data| {
+            selection_data.set_pixbuf(&f());
+        }));
+        self
+    }
+
+    // rustdoc-stripper-ignore-next
+    /// Offers the contents in the format `mime_type`, for example `text/html`, with the
+    /// bytes returned by `f`.
+    pub fn mime<F: Fn() -> Vec<u8> + 'static>(mut self, mime_type: &str, f: F) -> Self {
+        self.targets
+            .add(&gdk::Atom::intern(mime_type), 0, self.next_info());
+        self.handlers.push(Box_::new(move |_, selection_data| {
+            selection_data.set(&selection_data.target(), 8, &f());
+        }));
+        self
+    }
+
+    // rustdoc-stripper-ignore-next
+    /// Sets a closure that is called once the contents are replaced or the clipboard is
+    /// cleared.
+    pub fn on_clear<F: FnOnce(&Clipboard) + 'static>(mut self, f: F) -> Self {
+        self.clear = Some(Box_::new(f));
+        self
+    }
+
+    fn clear(self, clipboard: &Clipboard) {
+        if let Some(clear) = self.clear {
+            clear(clipboard);
+        }
+    }
 }
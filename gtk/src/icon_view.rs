@@ -0,0 +1,76 @@
+// Take a look at the license at the top of the repository in the LICENSE file.
+
+use gdk::{DragAction, ModifierType};
+use glib::translate::*;
+use std::ptr;
+
+use crate::prelude::*;
+use crate::{IconView, TargetEntry, ffi};
+
+mod sealed {
+    pub trait Sealed {}
+    impl<T: glib::object::IsA<crate::IconView>> Sealed for T {}
+}
+
+pub trait IconViewExtManual: IsA<IconView> + sealed::Sealed + 'static {
+    // rustdoc-stripper-ignore-next
+    /// Turns the view into a drag source for automatic DnD of its rows.
+    ///
+    /// Rows can be moved within the view, and to other views, with the
+    /// [`TargetEntry::tree_model_row()`] target. The model has to implement
+    /// [`TreeDragSource`](crate::TreeDragSource), like [`ListStore`](crate::ListStore) and
+    /// [`TreeStore`](crate::TreeStore) do, or custom models through
+    /// [`TreeDragSourceImpl`](crate::subclass::tree_drag_source::TreeDragSourceImpl).
+    #[doc(alias = "gtk_icon_view_enable_model_drag_source")]
+    fn enable_model_drag_source(
+        &self,
+        start_button_mask: ModifierType,
+        targets: &[TargetEntry],
+        actions: DragAction,
+    ) {
+        let stashes: Vec<_> = targets.iter().map(|e| e.to_glib_none()).collect();
+        let t: Vec<_> = stashes.iter().map(|stash| unsafe { *stash.0 }).collect();
+        let t_ptr: *const ffi::GtkTargetEntry = if !t.is_empty() {
+            t.as_ptr()
+        } else {
+            ptr::null()
+        };
+        unsafe {
+            ffi::gtk_icon_view_enable_model_drag_source(
+                self.as_ref().to_glib_none().0,
+                start_button_mask.into_glib(),
+                t_ptr,
+                t.len() as i32,
+                actions.into_glib(),
+            )
+        };
+    }
+
+    // rustdoc-stripper-ignore-next
+    /// Turns the view into a drop destination for automatic DnD of rows.
+    ///
+    /// The model has to implement [`TreeDragDest`](crate::TreeDragDest), like
+    /// [`ListStore`](crate::ListStore) and [`TreeStore`](crate::TreeStore) do, or custom
+    /// models through
+    /// [`TreeDragDestImpl`](crate::subclass::tree_drag_dest::TreeDragDestImpl).
+    #[doc(alias = "gtk_icon_view_enable_model_drag_dest")]
+    fn enable_model_drag_dest(&self, targets: &[TargetEntry], actions: DragAction) {
+        let stashes: Vec<_> = targets.iter().map(|e| e.to_glib_none()).collect();
+        let t: Vec<_> = stashes.iter().map(|stash| unsafe { *stash.0 }).collect();
+        let t_ptr: *const ffi::GtkTargetEntry = if !t.is_empty() {
+            t.as_ptr()
+        } else {
+            ptr::null()
+        };
+        unsafe {
+            ffi::gtk_icon_view_enable_model_drag_dest(
+                self.as_ref().to_glib_none().0,
+                t_ptr,
+                t.len() as i32,
+                actions.into_glib(),
+            )
+        };
+    }
+}
+
+impl<O: IsA<IconView>> IconViewExtManual for O {}
@@ -68,6 +68,7 @@ mod application_window;
 mod border;
 mod buildable;
 mod builder;
+mod cell_edit_binding;
 mod cell_renderer_pixbuf;
 mod clipboard;
 mod color_button;
@@ -88,10 +89,12 @@ mod flow_box;
 mod functions;
 #[cfg(feature = "v3_24")]
 mod gesture_stylus;
+mod icon_view;
 mod im_context_simple;
 mod image;
 mod invisible;
 mod list_box;
+mod list_model_tree_adapter;
 mod list_store;
 mod menu;
 mod message_dialog;
@@ -111,26 +114,39 @@ mod requisition;
 mod response_type;
 mod selection_data;
 mod signal;
+mod signal_stream;
 mod stack_switcher;
+#[cfg(feature = "serde")]
+mod store_serde;
 mod style_context;
 mod switch;
 mod target_entry;
 mod target_list;
 mod text_buffer;
 mod text_iter;
+mod tree_iter;
+mod tree_model;
 mod tree_model_filter;
 mod tree_path;
+mod tree_row;
 mod tree_row_reference;
 mod tree_sortable;
 mod tree_store;
+mod tree_view;
 mod tree_view_column;
+mod virtual_list_model;
 mod widget;
+mod widget_query;
+mod widget_tree;
 
 #[macro_use]
 pub mod subclass;
 
 pub mod builders;
 pub mod prelude;
+#[cfg(feature = "test-utils")]
+#[cfg_attr(docsrs, doc(cfg(feature = "test-utils")))]
+pub mod test;
 
 pub use crate::auto::functions::*;
 pub use crate::auto::*;
@@ -142,15 +158,27 @@ pub use gdk::Rectangle;
 
 pub use crate::app_chooser::AppChooser;
 pub use crate::border::Border;
+pub use crate::cell_edit_binding::CellEditBinding;
+pub use crate::clipboard::ClipboardProvider;
 pub use crate::entry_buffer::EntryBuffer;
 pub use crate::file_filter_info::FileFilterInfo;
+pub use crate::list_model_tree_adapter::ListModelTreeAdapter;
 pub use crate::message_dialog::MessageDialogExt;
 pub use crate::page_range::PageRange;
 pub use crate::recent_data::RecentData;
 pub use crate::requisition::Requisition;
 pub use crate::response_type::ResponseType;
+pub use crate::signal_stream::{SignalArgs, SignalStream};
+#[cfg(feature = "serde")]
+pub use crate::store_serde::{StoreContents, StoreRow, StoreValue};
 pub use crate::target_entry::TargetEntry;
+pub use crate::tree_model::{TreeModelDescendant, TreeModelDescendants, TreeModelRows};
+pub use crate::tree_row::{TreeColumn, TreeRow};
 pub use crate::tree_sortable::SortColumn;
+pub use crate::tree_view::TreeViewColumnsBuilder;
+pub use crate::virtual_list_model::{VirtualListModel, VirtualListSource};
 pub use crate::widget::TickCallbackId;
+pub use crate::widget_query::{WidgetAncestors, WidgetDescendants, WidgetSelector};
+pub use crate::widget_tree::{DEFAULT_DUMP_PROPERTIES, WidgetAllocation, WidgetNode};
 pub use functions::*;
 pub use pad_action_entry::PadActionEntry;
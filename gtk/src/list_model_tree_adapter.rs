@@ -0,0 +1,402 @@
+// Take a look at the license at the top of the repository in the LICENSE file.
+
+use glib::subclass::prelude::*;
+
+use crate::{TreeIter, TreeModel, prelude::*};
+
+mod imp {
+    use std::cell::{Cell, OnceCell, RefCell};
+    use std::rc::Rc;
+
+    use glib::SignalHandlerId;
+
+    use super::*;
+    use crate::{TreeModelFlags, TreePath, subclass::prelude::*};
+
+    #[derive(Debug)]
+    pub(super) struct Column {
+        pub(super) property: String,
+        pub(super) type_: glib::Type,
+    }
+
+    #[derive(Debug)]
+    pub(super) struct Row {
+        pub(super) item: glib::Object,
+        // Position of the row, shared with the notify handler of the item
+        pub(super) index: Rc<Cell<usize>>,
+        pub(super) notify_handler: SignalHandlerId,
+    }
+
+    #[derive(Debug, Default)]
+    pub struct ListModelTreeAdapter {
+        pub(super) model: OnceCell<gio::ListModel>,
+        pub(super) columns: OnceCell<Vec<Column>>,
+        pub(super) items_changed_handler: RefCell<Option<SignalHandlerId>>,
+        // Mirror of the items of `model`, so that `row-deleted` and `row-inserted` can be
+        // emitted one row at a time with the model in a consistent state for each of them.
+        pub(super) rows: RefCell<Vec<Row>>,
+        pub(super) stamp: Cell<i32>,
+    }
+
+    #[glib::object_subclass]
+    impl ObjectSubclass for ListModelTreeAdapter {
+        const NAME: &'static str = "GtkRsListModelTreeAdapter";
+        type Type = super::ListModelTreeAdapter;
+        type ParentType = glib::Object;
+        type Interfaces = (TreeModel,);
+    }
+
+    impl ObjectImpl for ListModelTreeAdapter {
+        fn dispose(&self) {
+            if let (Some(model), Some(handler)) =
+                (self.model.get(), self.items_changed_handler.take())
+            {
+                model.disconnect(handler);
+            }
+            for row in self.rows.take() {
+                row.item.disconnect(row.notify_handler);
+            }
+        }
+    }
+
+    impl TreeModelImpl for ListModelTreeAdapter {
+        fn flags(&self) -> TreeModelFlags {
+            TreeModelFlags::LIST_ONLY
+        }
+
+        fn n_columns(&self) -> i32 {
+            self.columns().len() as i32
+        }
+
+        fn column_type(&self, index: i32) -> glib::Type {
+            self.columns()
+                .get(index as usize)
+                .map_or(glib::Type::INVALID, |column| column.type_)
+        }
+
+        fn iter(&self, path: &TreePath) -> Option<TreeIter> {
+            match path.indices()[..] {
+                [index] => self.iter_nth_child(None, index),
+                _ => None,
+            }
+        }
+
+        fn path(&self, iter: &TreeIter) -> TreePath {
+            match self.index(iter) {
+                Some(index) => TreePath::from_indicesv(&[index as i32]),
+                None => TreePath::new(),
+            }
+        }
+
+        fn value(&self, iter: &TreeIter, column: i32) -> glib::Value {
+            let Some(column) = self.columns().get(column as usize) else {
+                glib::g_warning!(
+                    "Gtk",
+                    "Invalid column {} of a ListModelTreeAdapter with {} columns",
+                    column,
+                    self.columns().len()
+                );
+                // A value of some type has to be returned, which the caller can't expect
+                return None::<&str>.to_value();
+            };
+            match self.index(iter) {
+                Some(index) => self.rows.borrow()[index]
+                    .item
+                    .property_value(&column.property),
+                None => glib::Value::from_type(column.type_),
+            }
+        }
+
+        fn iter_next(&self, iter: &TreeIter) -> Option<TreeIter> {
+            self.iter_nth_child(None, self.index(iter)? as i32 + 1)
+        }
+
+        fn iter_previous(&self, iter: &TreeIter) -> Option<TreeIter> {
+            self.index(iter)?
+                .checked_sub(1)
+                .map(|index| self.iter_at(index))
+        }
+
+        fn iter_has_child(&self, _iter: &TreeIter) -> bool {
+            false
+        }
+
+        fn iter_n_children(&self, iter: Option<&TreeIter>) -> i32 {
+            match iter {
+                Some(_) => 0,
+                None => self.rows.borrow().len() as i32,
+            }
+        }
+
+        fn iter_nth_child(&self, parent: Option<&TreeIter>, n: i32) -> Option<TreeIter> {
+            if parent.is_some() || n < 0 || n as usize >= self.rows.borrow().len() {
+                return None;
+            }
+            Some(self.iter_at(n as usize))
+        }
+
+        fn iter_parent(&self, _child: &TreeIter) -> Option<TreeIter> {
+            None
+        }
+    }
+
+    impl ListModelTreeAdapter {
+        pub(super) fn columns(&self) -> &[Column] {
+            self.columns.get().expect("adapter not initialized")
+        }
+
+        pub(super) fn iter_at(&self, index: usize) -> TreeIter {
+            TreeIter::from_handle(self.stamp.get(), index)
+        }
+
+        // Returns `None` for iterators from before the last insertion or removal, which
+        // GTK's own stores reject as well
+        pub(super) fn index(&self, iter: &TreeIter) -> Option<usize> {
+            let index = iter.handle();
+            (iter.stamp() == self.stamp.get() && index < self.rows.borrow().len()).then_some(index)
+        }
+
+        // Iterators are row indices, so any insertion or removal invalidates them
+        fn invalidate_iters(&self) {
+            self.stamp.set(self.stamp.get().wrapping_add(1));
+        }
+
+        fn connect_row(&self, item: glib::Object, index: usize) -> Row {
+            let index = Rc::new(Cell::new(index));
+            let row_index = index.clone();
+            let adapter = self.obj().downgrade();
+            let notify_handler = item.connect_notify_local(None, move |_, pspec| {
+                let Some(adapter) = adapter.upgrade() else {
+                    return;
+                };
+                let imp = adapter.imp();
+                if !imp
+                    .columns()
+                    .iter()
+                    .any(|column| column.property == pspec.name())
+                {
+                    return;
+                }
+                imp.emit_row_changed(&imp.iter_at(row_index.get()));
+            });
+            Row {
+                item,
+                index,
+                notify_handler,
+            }
+        }
+
+        // Updates the positions of the rows from `start` on after an insertion or removal
+        fn update_row_indices(&self, start: usize) {
+            for (index, row) in self.rows.borrow().iter().enumerate().skip(start) {
+                row.index.set(index);
+            }
+        }
+
+        pub(super) fn items_changed(
+            &self,
+            model: &gio::ListModel,
+            position: u32,
+            removed: u32,
+            added: u32,
+        ) {
+            let position = position as usize;
+            for _ in 0..removed {
+                let row = self.rows.borrow_mut().remove(position);
+                row.item.disconnect(row.notify_handler);
+                self.update_row_indices(position);
+                self.invalidate_iters();
+                self.emit_row_deleted(&TreePath::from_indicesv(&[position as i32]));
+            }
+            for index in position..position + added as usize {
+                let item = model
+                    .item(index as u32)
+                    .expect("list model reported more items than it has");
+                let row = self.connect_row(item, index);
+                self.rows.borrow_mut().insert(index, row);
+                self.update_row_indices(index + 1);
+                self.invalidate_iters();
+                self.emit_row_inserted(&self.iter_at(index));
+            }
+        }
+    }
+}
+
+glib::wrapper! {
+    // rustdoc-stripper-ignore-next
+    /// A flat [`TreeModel`] exposing the items of a [`gio::ListModel`].
+    ///
+    /// Every column of the tree model is bound to a property of the items, so that a
+    /// [`TreeView`](crate::TreeView), [`IconView`](crate::IconView) or
+    /// [`ComboBox`](crate::ComboBox) can show the same collection a
+    /// [`ListBox`](crate::ListBox) or [`FlowBox`](crate::FlowBox) is bound to, without keeping
+    /// a second copy of it in a [`ListStore`](crate::ListStore).
+    ///
+    /// Rows are inserted and deleted when the list model emits `items-changed`, and a row is
+    /// changed whenever one of the bound properties of its item is notified.
+    ///
+    /// ```no_run
+    /// # use gtk::prelude::*;
+    /// # fn build(model: &gtk::gio::ListModel) {
+    /// // `model` holds items with a "name" and a "count" property.
+    /// let adapter = gtk::ListModelTreeAdapter::new(model, &["name", "count"]);
+    /// let tree_view = gtk::TreeView::with_model(&adapter);
+    /// let cell = gtk::CellRendererText::new();
+    /// tree_view.append_column(&gtk::TreeViewColumn::with_attributes("Name", &cell, &[("text", 0)]));
+    /// # }
+    /// ```
+    pub struct ListModelTreeAdapter(ObjectSubclass<imp::ListModelTreeAdapter>)
+        @implements TreeModel;
+}
+
+impl ListModelTreeAdapter {
+    // rustdoc-stripper-ignore-next
+    /// Creates an adapter for `model` with one column for each property in `properties`,
+    /// in order. The type of each column is the type of the property.
+    ///
+    /// # Panics
+    ///
+    /// If the item type of `model` is not a class, or doesn't have a readable property for
+    /// each of `properties`.
+    pub fn new(model: &impl IsA<gio::ListModel>, properties: &[&str]) -> Self {
+        assert_initialized_main_thread!();
+        let model = model.as_ref();
+        let item_type = model.item_type();
+        let class = glib::Class::<glib::Object>::from_type(item_type).unwrap_or_else(|| {
+            panic!("item type {item_type} of the list model is not an object class")
+        });
+        let columns = properties
+            .iter()
+            .map(|&property| {
+                let pspec = class.find_property(property).unwrap_or_else(|| {
+                    panic!("item type {item_type} has no property '{property}'")
+                });
+                assert!(
+                    pspec.flags().contains(glib::ParamFlags::READABLE),
+                    "property '{property}' of {item_type} is not readable",
+                );
+                imp::Column {
+                    property: property.to_owned(),
+                    type_: pspec.value_type(),
+                }
+            })
+            .collect();
+
+        let adapter: Self = glib::Object::new();
+        let imp = adapter.imp();
+        imp.model.set(model.clone()).unwrap();
+        imp.columns.set(columns).unwrap();
+
+        let weak = adapter.downgrade();
+        let handler = model.connect_items_changed(move |model, position, removed, added| {
+            if let Some(adapter) = weak.upgrade() {
+                adapter.imp().items_changed(model, position, removed, added);
+            }
+        });
+        imp.items_changed_handler.replace(Some(handler));
+        imp.items_changed(model, 0, 0, model.n_items());
+
+        adapter
+    }
+
+    // rustdoc-stripper-ignore-next
+    /// Returns the list model the adapter exposes.
+    pub fn model(&self) -> gio::ListModel {
+        self.imp().model.get().unwrap().clone()
+    }
+
+    // rustdoc-stripper-ignore-next
+    /// Returns the item of the list model that the row pointed to by `iter` shows, or `None`
+    /// if `iter` is no longer valid.
+    pub fn item(&self, iter: &TreeIter) -> Option<glib::Object> {
+        let imp = self.imp();
+        let index = imp.index(iter)?;
+        Some(imp.rows.borrow()[index].item.clone())
+    }
+
+    // rustdoc-stripper-ignore-next
+    /// Returns an iterator pointing to the row showing the item at `position` of the list
+    /// model.
+    pub fn iter_for_position(&self, position: u32) -> Option<TreeIter> {
+        self.iter_nth_child(None, position as i32)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::cell::RefCell;
+    use std::rc::Rc;
+
+    use super::*;
+    use crate::TEST_THREAD_WORKER;
+
+    fn names(adapter: &ListModelTreeAdapter) -> Vec<String> {
+        adapter
+            .rows()
+            .map(|iter| adapter.value(&iter, 0).get::<String>().unwrap())
+            .collect()
+    }
+
+    #[test]
+    fn items_changed() {
+        TEST_THREAD_WORKER
+            .push(|| {
+                let c = gio::SimpleAction::new("c", None);
+                let store = gio::ListStore::new::<gio::SimpleAction>();
+                store.append(&gio::SimpleAction::new("a", None));
+                store.append(&gio::SimpleAction::new("b", None));
+                store.append(&c);
+                let adapter = ListModelTreeAdapter::new(&store, &["name", "enabled"]);
+                assert_eq!(names(&adapter), ["a", "b", "c"]);
+
+                let signals = Rc::new(RefCell::new(Vec::new()));
+                let s = signals.clone();
+                adapter.connect_row_changed(move |_, path, _| {
+                    s.borrow_mut().push(format!("changed {path}"));
+                });
+                let s = signals.clone();
+                adapter.connect_row_deleted(move |_, path| {
+                    s.borrow_mut().push(format!("deleted {path}"));
+                });
+                let s = signals.clone();
+                adapter.connect_row_inserted(move |_, path, _| {
+                    s.borrow_mut().push(format!("inserted {path}"));
+                });
+
+                store.remove(0);
+                store.insert(1, &gio::SimpleAction::new("d", None));
+                assert_eq!(names(&adapter), ["b", "d", "c"]);
+
+                // The row of an item follows it when rows before it are inserted or removed
+                c.set_enabled(false);
+                let last = adapter.iter_for_position(2).unwrap();
+                assert!(!adapter.value(&last, 1).get::<bool>().unwrap());
+                assert_eq!(adapter.item(&last).unwrap(), c.upcast::<glib::Object>());
+                assert_eq!(*signals.borrow(), ["deleted 0", "inserted 1", "changed 2"]);
+            })
+            .expect("Failed to schedule a test call");
+        while TEST_THREAD_WORKER.unprocessed() > 0 {}
+    }
+
+    #[test]
+    fn stale_iter() {
+        TEST_THREAD_WORKER
+            .push(|| {
+                let store = gio::ListStore::new::<gio::SimpleAction>();
+                store.append(&gio::SimpleAction::new("a", None));
+                let adapter = ListModelTreeAdapter::new(&store, &["name"]);
+                let iter = adapter.iter_first().unwrap();
+
+                store.append(&gio::SimpleAction::new("b", None));
+                assert!(adapter.item(&iter).is_none());
+                assert_eq!(
+                    adapter.value(&iter, 0).get::<Option<String>>().unwrap(),
+                    None
+                );
+                assert_eq!(adapter.path(&iter).unwrap().depth(), 0);
+                assert!(!adapter.iter_next(&iter));
+            })
+            .expect("Failed to schedule a test call");
+        while TEST_THREAD_WORKER.unprocessed() > 0 {}
+    }
+}
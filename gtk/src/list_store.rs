@@ -2,7 +2,9 @@
 
 use crate::TreeIter;
 use crate::TreeModel;
+use crate::tree_row::row_columns_and_values;
 use crate::{ListStore, ffi};
+use crate::{TreeColumn, TreeRow};
 use glib::object::{Cast, IsA};
 use glib::translate::*;
 use glib::{Type, Value, value::ToValue};
@@ -203,6 +205,27 @@ pub trait GtkListStoreExtManual: IsA<ListStore> + sealed::Sealed + 'static {
             );
         }
     }
+
+    // rustdoc-stripper-ignore-next
+    /// Inserts a new row holding the fields of `row`, at `position` or at the end if
+    /// `position` is `None`.
+    fn insert_row<R: TreeRow>(&self, position: Option<u32>, row: &R) -> TreeIter {
+        let values = row.to_values();
+        self.insert_with_values(position, &row_columns_and_values(&values))
+    }
+
+    // rustdoc-stripper-ignore-next
+    /// Replaces all columns of the row pointed to by `iter` with the fields of `row`.
+    fn set_row<R: TreeRow>(&self, iter: &TreeIter, row: &R) {
+        let values = row.to_values();
+        self.set(iter, &row_columns_and_values(&values));
+    }
+
+    // rustdoc-stripper-ignore-next
+    /// Sets a single typed column of the row pointed to by `iter`.
+    fn set_field<R, T: ToValue>(&self, iter: &TreeIter, column: TreeColumn<R, T>, value: &T) {
+        self.set_value(iter, column.into(), &value.to_value());
+    }
 }
 
 impl<O: IsA<ListStore>> GtkListStoreExtManual for O {}
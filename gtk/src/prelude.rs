@@ -27,6 +27,7 @@ pub use crate::fixed::FixedExtManual;
 pub use crate::flow_box::FlowBoxExtManual;
 #[cfg(feature = "v3_24")]
 pub use crate::gesture_stylus::GestureStylusExtManual;
+pub use crate::icon_view::IconViewExtManual;
 pub use crate::im_context_simple::IMContextSimpleExtManual;
 pub use crate::image::ImageExtManual;
 pub use crate::invisible::InvisibleExtManual;
@@ -39,8 +40,13 @@ pub use crate::stack_switcher::StackSwitcherExtManual;
 pub use crate::style_context::StyleContextExtManual;
 pub use crate::switch::SwitchExtManual;
 pub use crate::text_buffer::TextBufferExtManual;
+pub use crate::tree_model::TreeModelExtManual;
+pub use crate::tree_model_filter::TreeModelFilterExtManual;
+pub use crate::tree_row::TreeRow;
 pub use crate::tree_sortable::TreeSortableExtManual;
 pub use crate::tree_store::TreeStoreExtManual;
+pub use crate::tree_view::TreeViewExtManual;
 pub use crate::widget::{InitializingWidgetExt, WidgetExtManual};
 
 pub use crate::signal::*;
+pub use crate::signal_stream::{ButtonStreamExt, EditableStreamExt, SignalStreamExt};
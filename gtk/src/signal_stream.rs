@@ -0,0 +1,307 @@
+// Take a look at the license at the top of the repository in the LICENSE file.
+
+use std::fmt;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+
+use futures_channel::mpsc;
+use futures_core::stream::{FusedStream, Stream};
+use glib::prelude::*;
+use glib::signal::SignalHandlerId;
+use glib::subclass::SignalId;
+use glib::value::FromValue;
+
+use crate::{Button, Editable};
+
+// rustdoc-stripper-ignore-next
+/// The arguments of a signal emission, as yielded by a [`SignalStream`].
+///
+/// This is implemented for tuples of up to eight elements, where each element corresponds to
+/// one parameter of the signal, not including the emitting object itself.
+pub trait SignalArgs: Sized + 'static {
+    // rustdoc-stripper-ignore-next
+    /// The types of the parameters, in order.
+    fn static_types() -> Vec<glib::Type>;
+
+    // rustdoc-stripper-ignore-next
+    /// Whether each element accepts `None`, that is whether it is an `Option`.
+    fn accepts_none() -> Vec<bool>;
+
+    // rustdoc-stripper-ignore-next
+    /// Converts the parameter values of one emission.
+    ///
+    /// `values` does not include the emitting object. Returns `None` if a value is missing or
+    /// can't be converted to its element.
+    fn from_values(values: &[glib::Value]) -> Option<Self>;
+}
+
+macro_rules! signal_args_tuple {
+    ($($name:ident),*) => {
+        impl<$($name),*> SignalArgs for ($($name,)*)
+        where
+            $($name: for<'a> FromValue<'a> + StaticType + 'static,)*
+        {
+            fn static_types() -> Vec<glib::Type> {
+                vec![$($name::static_type()),*]
+            }
+
+            fn accepts_none() -> Vec<bool> {
+                vec![$(glib::Value::from_type($name::static_type()).get::<$name>().is_ok()),*]
+            }
+
+            #[allow(unused_variables, unused_mut, clippy::unused_unit)]
+            fn from_values(values: &[glib::Value]) -> Option<Self> {
+                let mut values = values.iter();
+                Some(($(values.next()?.get::<$name>().ok()?,)*))
+            }
+        }
+    };
+}
+
+signal_args_tuple!();
+signal_args_tuple!(A);
+signal_args_tuple!(A, B);
+signal_args_tuple!(A, B, C);
+signal_args_tuple!(A, B, C, D);
+signal_args_tuple!(A, B, C, D, E);
+signal_args_tuple!(A, B, C, D, E, F);
+signal_args_tuple!(A, B, C, D, E, F, G);
+signal_args_tuple!(A, B, C, D, E, F, G, H);
+
+// rustdoc-stripper-ignore-next
+/// A [`Stream`] of the emissions of a signal.
+///
+/// The signal handler is disconnected when the stream is dropped. The stream ends once the
+/// object it is connected to is finalized.
+#[must_use = "streams do nothing unless polled, and dropping the stream disconnects the signal handler"]
+pub struct SignalStream<T> {
+    receiver: mpsc::UnboundedReceiver<T>,
+    object: glib::WeakRef<glib::Object>,
+    handler_id: Option<SignalHandlerId>,
+}
+
+impl<T: SignalArgs> SignalStream<T> {
+    fn connect(object: &glib::Object, signal_name: &str) -> Self {
+        let (signal_id, _) = SignalId::parse_name(signal_name, object.type_(), false)
+            .unwrap_or_else(|| {
+                panic!(
+                    "Signal '{signal_name}' of type '{}' not found",
+                    object.type_()
+                )
+            });
+        let query = signal_id.query();
+        assert_eq!(
+            query.return_type().type_(),
+            glib::Type::UNIT,
+            "Signal '{signal_name}' has a return value and can't be turned into a stream",
+        );
+        let param_types = query
+            .param_types()
+            .iter()
+            .map(|t| t.type_())
+            .collect::<Vec<_>>();
+        let arg_types = T::static_types();
+        assert!(
+            param_types.len() == arg_types.len()
+                && param_types
+                    .iter()
+                    .zip(arg_types.iter())
+                    .all(|(param, arg)| param.is_a(*arg)),
+            "Signal '{signal_name}' has parameters {param_types:?} but the stream expects {arg_types:?}",
+        );
+        // Objects may be passed as `NULL`, which would otherwise only fail once emitted
+        let non_optional_object =
+            param_types
+                .iter()
+                .zip(T::accepts_none())
+                .find(|(param, accepts_none)| {
+                    !accepts_none
+                        && (param.is_a(glib::Type::OBJECT) || param.is_a(glib::Type::INTERFACE))
+                });
+        if let Some((param, _)) = non_optional_object {
+            panic!(
+                "Signal '{signal_name}' has a parameter of type '{param}' that can be `None`, \
+                 the stream has to use an `Option` for it",
+            );
+        }
+
+        let (sender, receiver) = mpsc::unbounded();
+        let name = signal_name.to_owned();
+        let handler_id = object.connect_local(signal_name, false, move |values| {
+            match T::from_values(&values[1..]) {
+                Some(args) => {
+                    let _ = sender.unbounded_send(args);
+                }
+                None => glib::g_critical!(
+                    "Gtk",
+                    "Unexpected arguments of signal '{}', the emission is not streamed",
+                    name
+                ),
+            }
+            None
+        });
+
+        Self {
+            receiver,
+            object: object.downgrade(),
+            handler_id: Some(handler_id),
+        }
+    }
+}
+
+impl<T> Stream for SignalStream<T> {
+    type Item = T;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<T>> {
+        Pin::new(&mut self.receiver).poll_next(cx)
+    }
+}
+
+impl<T> FusedStream for SignalStream<T> {
+    fn is_terminated(&self) -> bool {
+        self.receiver.is_terminated()
+    }
+}
+
+impl<T> Drop for SignalStream<T> {
+    fn drop(&mut self) {
+        if let (Some(object), Some(handler_id)) = (self.object.upgrade(), self.handler_id.take()) {
+            object.disconnect(handler_id);
+        }
+    }
+}
+
+impl<T> fmt::Debug for SignalStream<T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("SignalStream")
+            .field("object", &self.object.upgrade())
+            .field("handler_id", &self.handler_id)
+            .finish()
+    }
+}
+
+pub trait SignalStreamExt: IsA<glib::Object> {
+    // rustdoc-stripper-ignore-next
+    /// Returns a [`Stream`] yielding the arguments of every emission of the signal
+    /// `signal_name`.
+    ///
+    /// `signal_name` may contain a detail, as in `notify::label`. `T` is a tuple with one
+    /// element per signal parameter, for example `(i32, i32)` for `GtkEditable::delete-text`,
+    /// or `()` for a signal without parameters. Object parameters have to be `Option`s.
+    ///
+    /// # Panics
+    ///
+    /// If the signal doesn't exist, has a return value, or its parameter types don't match `T`.
+    ///
+    /// ```no_run
+    /// use futures_util::StreamExt;
+    /// use gtk::prelude::*;
+    ///
+    /// # async fn run() {
+    /// let notebook = gtk::Notebook::new();
+    /// let mut pages = notebook.signal_stream::<(Option<gtk::Widget>, u32)>("switch-page");
+    /// while let Some((_page, page_num)) = pages.next().await {
+    ///     println!("Switched to page {page_num}");
+    /// }
+    /// # }
+    /// ```
+    fn signal_stream<T: SignalArgs>(&self, signal_name: &str) -> SignalStream<T> {
+        SignalStream::connect(self.upcast_ref(), signal_name)
+    }
+}
+
+impl<O: IsA<glib::Object>> SignalStreamExt for O {}
+
+pub trait ButtonStreamExt: IsA<Button> {
+    // rustdoc-stripper-ignore-next
+    /// Returns a [`Stream`] yielding once for every emission of
+    /// [`clicked`](crate::prelude::ButtonExt::connect_clicked).
+    fn clicked_stream(&self) -> SignalStream<()> {
+        self.signal_stream("clicked")
+    }
+}
+
+impl<O: IsA<Button>> ButtonStreamExt for O {}
+
+pub trait EditableStreamExt: IsA<Editable> {
+    // rustdoc-stripper-ignore-next
+    /// Returns a [`Stream`] yielding once for every emission of
+    /// [`changed`](crate::prelude::EditableSignals::connect_changed).
+    fn changed_stream(&self) -> SignalStream<()> {
+        self.signal_stream("changed")
+    }
+}
+
+impl<O: IsA<Editable>> EditableStreamExt for O {}
+
+#[cfg(test)]
+mod test {
+    use std::task::Waker;
+
+    use super::*;
+    use crate::TEST_THREAD_WORKER;
+    use crate::prelude::*;
+
+    fn poll<T>(stream: &mut SignalStream<T>) -> Poll<Option<T>> {
+        Pin::new(stream).poll_next(&mut Context::from_waker(Waker::noop()))
+    }
+
+    #[test]
+    fn clicked_stream() {
+        TEST_THREAD_WORKER
+            .push(|| {
+                let button = Button::new();
+                let mut clicks = button.clicked_stream();
+                assert_eq!(poll(&mut clicks), Poll::Pending);
+                button.clicked();
+                button.clicked();
+                assert_eq!(poll(&mut clicks), Poll::Ready(Some(())));
+                assert_eq!(poll(&mut clicks), Poll::Ready(Some(())));
+                assert_eq!(poll(&mut clicks), Poll::Pending);
+
+                let signal_id = SignalId::lookup("clicked", Button::static_type()).unwrap();
+                assert!(glib::signal::signal_has_handler_pending(
+                    &button, signal_id, None, false
+                ));
+                drop(clicks);
+                assert!(!glib::signal::signal_has_handler_pending(
+                    &button, signal_id, None, false
+                ));
+            })
+            .expect("Failed to schedule a test call");
+        while TEST_THREAD_WORKER.unprocessed() > 0 {}
+    }
+
+    #[test]
+    fn detailed_and_object_arguments() {
+        TEST_THREAD_WORKER
+            .push(|| {
+                let button = Button::new();
+                let mut notifies = button.signal_stream::<(glib::ParamSpec,)>("notify::label");
+                button.set_use_underline(true);
+                assert!(matches!(poll(&mut notifies), Poll::Pending));
+                button.set_label("Label");
+                match poll(&mut notifies) {
+                    Poll::Ready(Some((pspec,))) => assert_eq!(pspec.name(), "label"),
+                    _ => panic!("no notification for the label"),
+                }
+
+                let container = crate::Box::new(crate::Orientation::Horizontal, 0);
+                let mut added = container.signal_stream::<(Option<crate::Widget>,)>("add");
+                container.add(&button);
+                match poll(&mut added) {
+                    Poll::Ready(Some((widget,))) => {
+                        assert_eq!(widget.as_ref(), Some(button.upcast_ref()))
+                    }
+                    _ => panic!("no emission for the added child"),
+                }
+
+                let res = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+                    container.signal_stream::<(crate::Widget,)>("add")
+                }));
+                assert!(res.is_err());
+            })
+            .expect("Failed to schedule a test call");
+        while TEST_THREAD_WORKER.unprocessed() > 0 {}
+    }
+}
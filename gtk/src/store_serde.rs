@@ -0,0 +1,499 @@
+// Take a look at the license at the top of the repository in the LICENSE file.
+
+use gdk_pixbuf::Pixbuf;
+use serde::{Deserialize, Deserializer, Serialize, Serializer, de, ser};
+
+use crate::prelude::*;
+use crate::{ListStore, TreeIter, TreeModel, TreeStore};
+
+// rustdoc-stripper-ignore-next
+/// The value of a cell in [`StoreContents`].
+///
+/// Strings, booleans and numbers map to the corresponding column types and enums are stored
+/// by nick. With the `serde-pixbuf` feature, [`Pixbuf`]s are stored as PNG bytes. `Null`
+/// stands for a `None` string or object.
+///
+/// The values are tagged with their variant, so they can be read back by formats that are
+/// not self-describing as well.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum StoreValue {
+    Null,
+    Bool(bool),
+    Int(i64),
+    UInt(u64),
+    Float(f64),
+    String(String),
+    Bytes(Vec<u8>),
+}
+
+// rustdoc-stripper-ignore-next
+/// A row of [`StoreContents`], with one value per column and its child rows.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct StoreRow {
+    pub values: Vec<StoreValue>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub children: Vec<StoreRow>,
+}
+
+// rustdoc-stripper-ignore-next
+/// The column types and rows of a [`ListStore`] or [`TreeStore`], in a form that can be
+/// serialized with serde.
+///
+/// [`ListStore`] and [`TreeStore`] implement [`Serialize`] and [`Deserialize`] through this
+/// type, so they can be saved and restored with any serde format:
+///
+/// ```no_run
+/// # fn save(store: &gtk::TreeStore) -> Result<(), Box<dyn std::error::Error>> {
+/// let json = serde_json::to_string(store)?;
+/// let restored: gtk::TreeStore = serde_json::from_str(&json)?;
+/// # Ok(())
+/// # }
+/// ```
+///
+/// With the column types written in the data, for example:
+///
+/// ```json
+/// {
+///   "columns": ["gchararray", "guint"],
+///   "rows": [
+///     {
+///       "values": [{ "String": "Documents" }, { "UInt": 3 }],
+///       "children": [{ "values": [{ "String": "notes.txt" }, { "UInt": 1 }] }]
+///     }
+///   ]
+/// }
+/// ```
+///
+/// Columns of other types than strings, booleans, numbers and enums are not supported, except
+/// for [`Pixbuf`] columns with the `serde-pixbuf` feature.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct StoreContents {
+    // rustdoc-stripper-ignore-next
+    /// The names of the column types, like `gchararray` or `gint`.
+    pub columns: Vec<String>,
+    pub rows: Vec<StoreRow>,
+}
+
+impl StoreContents {
+    // rustdoc-stripper-ignore-next
+    /// Reads the column types and all rows of `model`.
+    pub fn from_model(model: &impl IsA<TreeModel>) -> Result<Self, glib::BoolError> {
+        let model = model.as_ref();
+        let types = (0..model.n_columns())
+            .map(|column| model.column_type(column))
+            .collect::<Vec<_>>();
+        for type_ in &types {
+            if !is_supported(*type_) {
+                return Err(glib::bool_error!("Unsupported column type {type_}"));
+            }
+        }
+        Ok(Self {
+            columns: types.iter().map(|type_| type_.name().to_owned()).collect(),
+            rows: read_rows(model, None)?,
+        })
+    }
+
+    // rustdoc-stripper-ignore-next
+    /// Returns the types of the columns.
+    pub fn column_types(&self) -> Result<Vec<glib::Type>, glib::BoolError> {
+        self.columns
+            .iter()
+            .map(|name| {
+                glib::Type::from_name(name)
+                    .filter(|type_| is_supported(*type_))
+                    .ok_or_else(|| glib::bool_error!("Unsupported column type {name}"))
+            })
+            .collect()
+    }
+
+    // rustdoc-stripper-ignore-next
+    /// Creates a [`ListStore`] holding the rows.
+    ///
+    /// Fails if a row has children, or a value doesn't fit the type of its column.
+    pub fn to_list_store(&self) -> Result<ListStore, glib::BoolError> {
+        let store = ListStore::new(&self.column_types()?);
+        self.append_to_list_store(&store)?;
+        Ok(store)
+    }
+
+    // rustdoc-stripper-ignore-next
+    /// Creates a [`TreeStore`] holding the rows.
+    ///
+    /// Fails if a value doesn't fit the type of its column.
+    pub fn to_tree_store(&self) -> Result<TreeStore, glib::BoolError> {
+        let store = TreeStore::new(&self.column_types()?);
+        self.append_to_tree_store(&store, None)?;
+        Ok(store)
+    }
+
+    // rustdoc-stripper-ignore-next
+    /// Appends the rows to `store`, whose columns must have the same types.
+    pub fn append_to_list_store(&self, store: &ListStore) -> Result<(), glib::BoolError> {
+        let types = self.checked_column_types(store.upcast_ref())?;
+        for row in &self.rows {
+            if !row.children.is_empty() {
+                return Err(glib::bool_error!("A ListStore can't hold child rows"));
+            }
+            let values = row_values(&types, row)?;
+            let iter = store.append();
+            set_values(&values, |column, value| {
+                store.set_value(&iter, column, value)
+            });
+        }
+        Ok(())
+    }
+
+    // rustdoc-stripper-ignore-next
+    /// Appends the rows and their children to `store`, whose columns must have the same
+    /// types, as children of `parent`, or as top-level rows if `parent` is `None`.
+    pub fn append_to_tree_store(
+        &self,
+        store: &TreeStore,
+        parent: Option<&TreeIter>,
+    ) -> Result<(), glib::BoolError> {
+        let types = self.checked_column_types(store.upcast_ref())?;
+        append_tree_rows(store, &types, parent, &self.rows)
+    }
+
+    fn checked_column_types(&self, model: &TreeModel) -> Result<Vec<glib::Type>, glib::BoolError> {
+        let types = self.column_types()?;
+        let model_types = (0..model.n_columns())
+            .map(|column| model.column_type(column))
+            .collect::<Vec<_>>();
+        if types != model_types {
+            return Err(glib::bool_error!(
+                "Store has columns {model_types:?} but the contents have {types:?}"
+            ));
+        }
+        Ok(types)
+    }
+}
+
+fn is_supported(type_: glib::Type) -> bool {
+    [
+        glib::Type::BOOL,
+        glib::Type::I8,
+        glib::Type::U8,
+        glib::Type::I32,
+        glib::Type::U32,
+        glib::Type::I_LONG,
+        glib::Type::U_LONG,
+        glib::Type::I64,
+        glib::Type::U64,
+        glib::Type::F32,
+        glib::Type::F64,
+        glib::Type::STRING,
+    ]
+    .contains(&type_)
+        || type_.is_a(glib::Type::ENUM)
+        || is_pixbuf(type_)
+}
+
+// Embedding every image as PNG can make the data much larger, so it is opt-in
+fn is_pixbuf(type_: glib::Type) -> bool {
+    cfg!(feature = "serde-pixbuf") && type_.is_a(Pixbuf::static_type())
+}
+
+fn read_rows(
+    model: &TreeModel,
+    parent: Option<&TreeIter>,
+) -> Result<Vec<StoreRow>, glib::BoolError> {
+    let rows = match parent {
+        Some(parent) => model.children(parent),
+        None => model.rows(),
+    };
+    rows.map(|iter| -> Result<StoreRow, glib::BoolError> {
+        let values = (0..model.n_columns())
+            .map(|column| to_store_value(&model.value(&iter, column)))
+            .collect::<Result<_, _>>()?;
+        Ok(StoreRow {
+            values,
+            children: read_rows(model, Some(&iter))?,
+        })
+    })
+    .collect()
+}
+
+fn to_store_value(value: &glib::Value) -> Result<StoreValue, glib::BoolError> {
+    let type_ = value.type_();
+    let store_value = if type_ == glib::Type::BOOL {
+        StoreValue::Bool(value.get().unwrap())
+    } else if type_ == glib::Type::I8 {
+        StoreValue::Int(value.get::<i8>().unwrap().into())
+    } else if type_ == glib::Type::U8 {
+        StoreValue::UInt(value.get::<u8>().unwrap().into())
+    } else if type_ == glib::Type::I32 {
+        StoreValue::Int(value.get::<i32>().unwrap().into())
+    } else if type_ == glib::Type::U32 {
+        StoreValue::UInt(value.get::<u32>().unwrap().into())
+    } else if type_ == glib::Type::I_LONG {
+        StoreValue::Int(value.get::<glib::ILong>().unwrap().0.into())
+    } else if type_ == glib::Type::U_LONG {
+        StoreValue::UInt(value.get::<glib::ULong>().unwrap().0.into())
+    } else if type_ == glib::Type::I64 {
+        StoreValue::Int(value.get().unwrap())
+    } else if type_ == glib::Type::U64 {
+        StoreValue::UInt(value.get().unwrap())
+    } else if type_ == glib::Type::F32 {
+        StoreValue::Float(value.get::<f32>().unwrap().into())
+    } else if type_ == glib::Type::F64 {
+        StoreValue::Float(value.get().unwrap())
+    } else if type_ == glib::Type::STRING {
+        value
+            .get::<Option<String>>()
+            .unwrap()
+            .map_or(StoreValue::Null, StoreValue::String)
+    } else if type_.is_a(glib::Type::ENUM) {
+        let (_, enum_value) = glib::EnumValue::from_value(value).unwrap();
+        StoreValue::String(enum_value.nick().to_owned())
+    } else if is_pixbuf(type_) {
+        match value.get::<Option<Pixbuf>>().unwrap() {
+            Some(pixbuf) => StoreValue::Bytes(
+                pixbuf
+                    .save_to_bufferv("png", &[])
+                    .map_err(|err| glib::bool_error!("Failed to encode pixbuf: {err}"))?,
+            ),
+            None => StoreValue::Null,
+        }
+    } else {
+        return Err(glib::bool_error!("Unsupported column type {type_}"));
+    };
+    Ok(store_value)
+}
+
+fn from_store_value(type_: glib::Type, value: &StoreValue) -> Result<glib::Value, glib::BoolError> {
+    let mismatch = || glib::bool_error!("Value {value:?} doesn't fit a column of type {type_}");
+
+    fn int<T: TryFrom<i64> + TryFrom<u64>>(value: &StoreValue) -> Option<T> {
+        match *value {
+            StoreValue::Int(i) => T::try_from(i).ok(),
+            StoreValue::UInt(u) => T::try_from(u).ok(),
+            _ => None,
+        }
+    }
+    fn float(value: &StoreValue) -> Option<f64> {
+        match *value {
+            StoreValue::Float(f) => Some(f),
+            StoreValue::Int(i) => Some(i as f64),
+            StoreValue::UInt(u) => Some(u as f64),
+            _ => None,
+        }
+    }
+
+    let converted = if type_ == glib::Type::BOOL {
+        match value {
+            StoreValue::Bool(b) => Some(b.to_value()),
+            _ => None,
+        }
+    } else if type_ == glib::Type::I8 {
+        int::<i8>(value).map(|i| i.to_value())
+    } else if type_ == glib::Type::U8 {
+        int::<u8>(value).map(|u| u.to_value())
+    } else if type_ == glib::Type::I32 {
+        int::<i32>(value).map(|i| i.to_value())
+    } else if type_ == glib::Type::U32 {
+        int::<u32>(value).map(|u| u.to_value())
+    } else if type_ == glib::Type::I_LONG {
+        int::<libc::c_long>(value).map(|i| glib::ILong(i).to_value())
+    } else if type_ == glib::Type::U_LONG {
+        int::<libc::c_ulong>(value).map(|u| glib::ULong(u).to_value())
+    } else if type_ == glib::Type::I64 {
+        int::<i64>(value).map(|i| i.to_value())
+    } else if type_ == glib::Type::U64 {
+        int::<u64>(value).map(|u| u.to_value())
+    } else if type_ == glib::Type::F32 {
+        float(value).map(|f| (f as f32).to_value())
+    } else if type_ == glib::Type::F64 {
+        float(value).map(|f| f.to_value())
+    } else if type_ == glib::Type::STRING {
+        match value {
+            StoreValue::String(s) => Some(s.to_value()),
+            StoreValue::Null => Some(None::<&str>.to_value()),
+            _ => None,
+        }
+    } else if type_.is_a(glib::Type::ENUM) {
+        match value {
+            StoreValue::String(nick) => {
+                glib::EnumClass::with_type(type_).and_then(|class| class.to_value_by_nick(nick))
+            }
+            _ => None,
+        }
+    } else if is_pixbuf(type_) {
+        match value {
+            StoreValue::Bytes(bytes) => {
+                let stream =
+                    gio::MemoryInputStream::from_bytes(&glib::Bytes::from(bytes.as_slice()));
+                let pixbuf = Pixbuf::from_stream(&stream, gio::Cancellable::NONE)
+                    .map_err(|err| glib::bool_error!("Failed to decode pixbuf: {err}"))?;
+                Some(pixbuf.to_value())
+            }
+            StoreValue::Null => Some(glib::Value::from_type(type_)),
+            _ => None,
+        }
+    } else {
+        return Err(glib::bool_error!("Unsupported column type {type_}"));
+    };
+    converted.ok_or_else(mismatch)
+}
+
+fn row_values(types: &[glib::Type], row: &StoreRow) -> Result<Vec<glib::Value>, glib::BoolError> {
+    if row.values.len() != types.len() {
+        return Err(glib::bool_error!(
+            "Row has {} values but there are {} columns",
+            row.values.len(),
+            types.len()
+        ));
+    }
+    types
+        .iter()
+        .zip(&row.values)
+        .map(|(type_, value)| from_store_value(*type_, value))
+        .collect()
+}
+
+fn set_values(values: &[glib::Value], mut set_value: impl FnMut(u32, &glib::Value)) {
+    for (column, value) in values.iter().enumerate() {
+        set_value(column as u32, value);
+    }
+}
+
+fn append_tree_rows(
+    store: &TreeStore,
+    types: &[glib::Type],
+    parent: Option<&TreeIter>,
+    rows: &[StoreRow],
+) -> Result<(), glib::BoolError> {
+    for row in rows {
+        let values = row_values(types, row)?;
+        let iter = store.append(parent);
+        set_values(&values, |column, value| {
+            store.set_value(&iter, column, value)
+        });
+        append_tree_rows(store, types, Some(&iter), &row.children)?;
+    }
+    Ok(())
+}
+
+impl Serialize for ListStore {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        StoreContents::from_model(self)
+            .map_err(ser::Error::custom)?
+            .serialize(serializer)
+    }
+}
+
+impl Serialize for TreeStore {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        StoreContents::from_model(self)
+            .map_err(ser::Error::custom)?
+            .serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for ListStore {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        StoreContents::deserialize(deserializer)?
+            .to_list_store()
+            .map_err(de::Error::custom)
+    }
+}
+
+impl<'de> Deserialize<'de> for TreeStore {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        StoreContents::deserialize(deserializer)?
+            .to_tree_store()
+            .map_err(de::Error::custom)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::TEST_THREAD_WORKER;
+
+    #[test]
+    fn round_trip() {
+        TEST_THREAD_WORKER
+            .push(|| {
+                let store =
+                    TreeStore::new(&[glib::Type::STRING, glib::Type::U32, glib::Type::BOOL]);
+                let parent = store.insert_with_values(None, None, &[(0, &"Documents"), (1, &3u32)]);
+                store.insert_with_values(Some(&parent), None, &[(0, &"notes.txt"), (2, &true)]);
+
+                let contents = StoreContents::from_model(&store).unwrap();
+                assert_eq!(contents.columns, ["gchararray", "guint", "gboolean"]);
+                assert_eq!(
+                    contents.rows,
+                    [StoreRow {
+                        values: vec![
+                            StoreValue::String("Documents".to_owned()),
+                            StoreValue::UInt(3),
+                            StoreValue::Bool(false),
+                        ],
+                        children: vec![StoreRow {
+                            values: vec![
+                                StoreValue::String("notes.txt".to_owned()),
+                                StoreValue::UInt(0),
+                                StoreValue::Bool(true),
+                            ],
+                            children: vec![],
+                        }],
+                    }]
+                );
+
+                let restored = contents.to_tree_store().unwrap();
+                assert_eq!(StoreContents::from_model(&restored).unwrap(), contents);
+                assert!(contents.to_list_store().is_err());
+
+                let json = serde_json::to_string(&store).unwrap();
+                let restored: TreeStore = serde_json::from_str(&json).unwrap();
+                assert_eq!(StoreContents::from_model(&restored).unwrap(), contents);
+                assert!(serde_json::from_str::<ListStore>(&json).is_err());
+
+                let list = ListStore::new(&[glib::Type::STRING, glib::Type::I64]);
+                list.insert_with_values(None, &[(0, &"a"), (1, &-1i64)]);
+                list.insert_with_values(None, &[(1, &2i64)]);
+                let json = serde_json::to_string(&list).unwrap();
+                let restored: ListStore = serde_json::from_str(&json).unwrap();
+                assert_eq!(
+                    StoreContents::from_model(&restored).unwrap(),
+                    StoreContents::from_model(&list).unwrap()
+                );
+            })
+            .expect("Failed to schedule a test call");
+        while TEST_THREAD_WORKER.unprocessed() > 0 {}
+    }
+
+    #[test]
+    fn pixbuf() {
+        TEST_THREAD_WORKER
+            .push(|| {
+                let store = ListStore::new(&[Pixbuf::static_type()]);
+                let pixbuf = Pixbuf::new(gdk_pixbuf::Colorspace::Rgb, true, 8, 3, 2).unwrap();
+                pixbuf.fill(0x336699ff);
+                store.insert_with_values(None, &[(0, &pixbuf)]);
+                store.insert_with_values(None, &[]);
+
+                if !cfg!(feature = "serde-pixbuf") {
+                    assert!(StoreContents::from_model(&store).is_err());
+                    return;
+                }
+                let contents = StoreContents::from_model(&store).unwrap();
+                assert!(matches!(contents.rows[0].values[0], StoreValue::Bytes(_)));
+                assert_eq!(contents.rows[1].values[0], StoreValue::Null);
+
+                let restored = contents.to_list_store().unwrap();
+                let iter = restored.iter_first().unwrap();
+                let restored_pixbuf = restored.get::<Pixbuf>(&iter, 0);
+                assert_eq!((restored_pixbuf.width(), restored_pixbuf.height()), (3, 2));
+                assert_eq!(
+                    restored_pixbuf.read_pixel_bytes(),
+                    pixbuf.read_pixel_bytes()
+                );
+                assert!(restored.iter_next(&iter));
+                assert_eq!(restored.get::<Option<Pixbuf>>(&iter, 0), None);
+            })
+            .expect("Failed to schedule a test call");
+        while TEST_THREAD_WORKER.unprocessed() > 0 {}
+    }
+}
@@ -0,0 +1,535 @@
+// Take a look at the license at the top of the repository in the LICENSE file.
+
+use std::any::Any;
+use std::ffi::CStr;
+
+use glib::GString;
+use glib::translate::*;
+
+use crate::{Buildable, Builder, ffi, prelude::*, subclass::prelude::*};
+
+// rustdoc-stripper-ignore-next
+/// Parser for the content of a custom tag, see [`BuildableImpl::custom_tag_start`].
+///
+/// The methods are called for every element nested in the custom tag, including the
+/// custom tag itself. Returning an error aborts the parsing of the whole UI definition.
+pub trait BuildableParser: Any {
+    fn start_element(
+        &mut self,
+        element_name: &str,
+        attributes: &[(&str, &str)],
+    ) -> Result<(), glib::Error> {
+        let _ = (element_name, attributes);
+        Ok(())
+    }
+
+    fn end_element(&mut self, element_name: &str) -> Result<(), glib::Error> {
+        let _ = element_name;
+        Ok(())
+    }
+
+    // Called with the text between elements, including whitespace
+    fn text(&mut self, text: &str) -> Result<(), glib::Error> {
+        let _ = text;
+        Ok(())
+    }
+}
+
+impl dyn BuildableParser {
+    pub fn downcast<T: BuildableParser>(self: Box<Self>) -> Result<Box<T>, Box<Self>> {
+        if (*self).type_id() == std::any::TypeId::of::<T>() {
+            let any: Box<dyn Any> = self;
+            Ok(any.downcast().unwrap())
+        } else {
+            Err(self)
+        }
+    }
+
+    pub fn downcast_mut<T: BuildableParser>(&mut self) -> Option<&mut T> {
+        let any: &mut dyn Any = self;
+        any.downcast_mut()
+    }
+}
+
+pub trait BuildableImpl: ObjectImpl + ObjectSubclass<Type: IsA<Buildable>> {
+    fn add_child(&self, builder: &Builder, child: &glib::Object, type_: Option<&str>) {
+        self.parent_add_child(builder, child, type_)
+    }
+
+    fn set_buildable_property(&self, builder: &Builder, name: &str, value: &glib::Value) {
+        self.parent_set_buildable_property(builder, name, value)
+    }
+
+    fn construct_child(&self, builder: &Builder, name: &str) -> Option<glib::Object> {
+        self.parent_construct_child(builder, name)
+    }
+
+    // Returns a parser for the content of the custom tag `tagname`, found inside of the
+    // `<object>` of this buildable or inside of the `<child>` element for `child`.
+    // Tags for which `None` is returned are handled by the parent implementation.
+    fn custom_tag_start(
+        &self,
+        builder: &Builder,
+        child: Option<&glib::Object>,
+        tagname: &str,
+    ) -> Option<Box<dyn BuildableParser>> {
+        let _ = (builder, child, tagname);
+        None
+    }
+
+    // Called at the end of a custom tag with the parser returned by `custom_tag_start`
+    fn custom_tag_end(
+        &self,
+        builder: &Builder,
+        child: Option<&glib::Object>,
+        tagname: &str,
+        parser: &mut dyn BuildableParser,
+    ) {
+        let _ = (builder, child, tagname, parser);
+    }
+
+    // Called once the whole UI definition was parsed, for every custom tag that was handled
+    // by `custom_tag_start`. If parsing fails, the parser is dropped along with the builder.
+    fn custom_finished(
+        &self,
+        builder: &Builder,
+        child: Option<&glib::Object>,
+        tagname: &str,
+        parser: Box<dyn BuildableParser>,
+    ) {
+        let _ = (builder, child, tagname, parser);
+    }
+
+    fn parser_finished(&self, builder: &Builder) {
+        self.parent_parser_finished(builder)
+    }
+
+    fn internal_child(&self, builder: &Builder, childname: &str) -> Option<glib::Object> {
+        self.parent_internal_child(builder, childname)
+    }
+}
+
+pub trait BuildableImplExt: BuildableImpl {
+    fn parent_add_child(&self, builder: &Builder, child: &glib::Object, type_: Option<&str>) {
+        unsafe {
+            let type_data = Self::type_data();
+            let parent_iface =
+                type_data.as_ref().parent_interface::<Buildable>() as *const ffi::GtkBuildableIface;
+
+            if let Some(func) = (*parent_iface).add_child {
+                func(
+                    self.obj().unsafe_cast_ref::<Buildable>().to_glib_none().0,
+                    builder.to_glib_none().0,
+                    child.to_glib_none().0,
+                    type_.to_glib_none().0,
+                )
+            }
+        }
+    }
+
+    fn parent_set_buildable_property(&self, builder: &Builder, name: &str, value: &glib::Value) {
+        unsafe {
+            let type_data = Self::type_data();
+            let parent_iface =
+                type_data.as_ref().parent_interface::<Buildable>() as *const ffi::GtkBuildableIface;
+
+            if let Some(func) = (*parent_iface).set_buildable_property {
+                func(
+                    self.obj().unsafe_cast_ref::<Buildable>().to_glib_none().0,
+                    builder.to_glib_none().0,
+                    name.to_glib_none().0,
+                    value.to_glib_none().0,
+                )
+            } else {
+                // Same fallback as gtk_buildable_set_buildable_property(). This runs inside
+                // a vfunc, so errors are only warned about, like GObject does.
+                let obj = self.obj();
+                let obj = obj.upcast_ref::<glib::Object>();
+                match obj.find_property(name) {
+                    None => {
+                        glib::g_warning!("Gtk", "{} has no property named '{}'", obj.type_(), name)
+                    }
+                    Some(pspec) if !pspec.flags().contains(glib::ParamFlags::WRITABLE) => {
+                        glib::g_warning!(
+                            "Gtk",
+                            "Property '{}' of {} is not writable",
+                            name,
+                            obj.type_()
+                        )
+                    }
+                    Some(pspec)
+                        if !glib::Value::type_transformable(value.type_(), pspec.value_type()) =>
+                    {
+                        glib::g_warning!(
+                            "Gtk",
+                            "Can't set property '{}' of type {} of {} from a value of type {}",
+                            name,
+                            pspec.value_type(),
+                            obj.type_(),
+                            value.type_()
+                        )
+                    }
+                    Some(_) => glib::gobject_ffi::g_object_set_property(
+                        obj.to_glib_none().0,
+                        name.to_glib_none().0,
+                        value.to_glib_none().0,
+                    ),
+                }
+            }
+        }
+    }
+
+    fn parent_construct_child(&self, builder: &Builder, name: &str) -> Option<glib::Object> {
+        unsafe {
+            let type_data = Self::type_data();
+            let parent_iface =
+                type_data.as_ref().parent_interface::<Buildable>() as *const ffi::GtkBuildableIface;
+
+            (*parent_iface).construct_child.and_then(|func| {
+                from_glib_full(func(
+                    self.obj().unsafe_cast_ref::<Buildable>().to_glib_none().0,
+                    builder.to_glib_none().0,
+                    name.to_glib_none().0,
+                ))
+            })
+        }
+    }
+
+    fn parent_parser_finished(&self, builder: &Builder) {
+        unsafe {
+            let type_data = Self::type_data();
+            let parent_iface =
+                type_data.as_ref().parent_interface::<Buildable>() as *const ffi::GtkBuildableIface;
+
+            if let Some(func) = (*parent_iface).parser_finished {
+                func(
+                    self.obj().unsafe_cast_ref::<Buildable>().to_glib_none().0,
+                    builder.to_glib_none().0,
+                )
+            }
+        }
+    }
+
+    fn parent_internal_child(&self, builder: &Builder, childname: &str) -> Option<glib::Object> {
+        unsafe {
+            let type_data = Self::type_data();
+            let parent_iface =
+                type_data.as_ref().parent_interface::<Buildable>() as *const ffi::GtkBuildableIface;
+
+            (*parent_iface).get_internal_child.and_then(|func| {
+                from_glib_none(func(
+                    self.obj().unsafe_cast_ref::<Buildable>().to_glib_none().0,
+                    builder.to_glib_none().0,
+                    childname.to_glib_none().0,
+                ))
+            })
+        }
+    }
+}
+
+impl<T: BuildableImpl> BuildableImplExt for T {}
+
+unsafe impl<T: BuildableImpl> IsImplementable<T> for Buildable {
+    fn interface_init(iface: &mut glib::Interface<Self>) {
+        let iface = iface.as_mut();
+
+        if !crate::rt::is_initialized() {
+            panic!("GTK has to be initialized first");
+        }
+
+        iface.add_child = Some(buildable_add_child::<T>);
+        iface.set_buildable_property = Some(buildable_set_buildable_property::<T>);
+        iface.construct_child = Some(buildable_construct_child::<T>);
+        iface.custom_tag_start = Some(buildable_custom_tag_start::<T>);
+        iface.custom_tag_end = Some(buildable_custom_tag_end::<T>);
+        iface.custom_finished = Some(buildable_custom_finished::<T>);
+        iface.parser_finished = Some(buildable_parser_finished::<T>);
+        iface.get_internal_child = Some(buildable_get_internal_child::<T>);
+    }
+}
+
+type ParserData = Box<dyn BuildableParser>;
+
+// A parser handed out by `buildable_custom_tag_start`, stored on the builder until
+// `buildable_custom_finished` takes it back. GTK only calls that one if the whole UI
+// definition could be parsed, otherwise the parser is freed along with the builder.
+struct OwnedParser(*mut ParserData);
+
+impl Drop for OwnedParser {
+    fn drop(&mut self) {
+        unsafe {
+            drop(Box::from_raw(self.0));
+        }
+    }
+}
+
+fn parser_key(data: glib::ffi::gpointer) -> String {
+    format!("gtk-rs-buildable-parser-{data:p}")
+}
+
+// Any other data pointer passed to the end/finished functions belongs to the parent
+// implementation.
+unsafe fn is_rust_parser(builder: &Builder, data: glib::ffi::gpointer) -> bool {
+    unsafe { builder.data::<OwnedParser>(&parser_key(data)).is_some() }
+}
+
+unsafe fn set_error(error: *mut *mut glib::ffi::GError, res: Result<(), glib::Error>) {
+    unsafe {
+        if let Err(err) = res
+            && !error.is_null()
+        {
+            *error = err.into_glib_ptr();
+        }
+    }
+}
+
+unsafe extern "C" fn parser_start_element(
+    _context: *mut glib::ffi::GMarkupParseContext,
+    element_name: *const libc::c_char,
+    attribute_names: *mut *const libc::c_char,
+    attribute_values: *mut *const libc::c_char,
+    user_data: glib::ffi::gpointer,
+    error: *mut *mut glib::ffi::GError,
+) {
+    unsafe {
+        let parser = &mut *(user_data as *mut ParserData);
+        let element_name = CStr::from_ptr(element_name).to_string_lossy();
+
+        let mut attributes = Vec::new();
+        let mut i = 0;
+        while !(*attribute_names.add(i)).is_null() {
+            attributes.push((
+                CStr::from_ptr(*attribute_names.add(i)).to_string_lossy(),
+                CStr::from_ptr(*attribute_values.add(i)).to_string_lossy(),
+            ));
+            i += 1;
+        }
+        let attributes = attributes
+            .iter()
+            .map(|(name, value)| (name.as_ref(), value.as_ref()))
+            .collect::<Vec<_>>();
+
+        set_error(error, parser.start_element(&element_name, &attributes));
+    }
+}
+
+unsafe extern "C" fn parser_end_element(
+    _context: *mut glib::ffi::GMarkupParseContext,
+    element_name: *const libc::c_char,
+    user_data: glib::ffi::gpointer,
+    error: *mut *mut glib::ffi::GError,
+) {
+    unsafe {
+        let parser = &mut *(user_data as *mut ParserData);
+        let element_name = CStr::from_ptr(element_name).to_string_lossy();
+
+        set_error(error, parser.end_element(&element_name));
+    }
+}
+
+unsafe extern "C" fn parser_text(
+    _context: *mut glib::ffi::GMarkupParseContext,
+    text: *const libc::c_char,
+    text_len: usize,
+    user_data: glib::ffi::gpointer,
+    error: *mut *mut glib::ffi::GError,
+) {
+    unsafe {
+        let parser = &mut *(user_data as *mut ParserData);
+        let text = std::slice::from_raw_parts(text as *const u8, text_len);
+
+        set_error(error, parser.text(&String::from_utf8_lossy(text)));
+    }
+}
+
+unsafe extern "C" fn buildable_add_child<T: BuildableImpl>(
+    buildable: *mut ffi::GtkBuildable,
+    builderptr: *mut ffi::GtkBuilder,
+    childptr: *mut glib::gobject_ffi::GObject,
+    typeptr: *const libc::c_char,
+) {
+    unsafe {
+        let instance = &*(buildable as *mut T::Instance);
+        let imp = instance.imp();
+        let type_ = (!typeptr.is_null()).then(|| GString::from_glib_borrow(typeptr));
+
+        imp.add_child(
+            &from_glib_borrow(builderptr),
+            &from_glib_borrow(childptr),
+            type_.as_deref().map(|s| s.as_str()),
+        )
+    }
+}
+
+unsafe extern "C" fn buildable_set_buildable_property<T: BuildableImpl>(
+    buildable: *mut ffi::GtkBuildable,
+    builderptr: *mut ffi::GtkBuilder,
+    nameptr: *const libc::c_char,
+    valueptr: *const glib::gobject_ffi::GValue,
+) {
+    unsafe {
+        let instance = &*(buildable as *mut T::Instance);
+        let imp = instance.imp();
+        let name = GString::from_glib_borrow(nameptr);
+
+        imp.set_buildable_property(
+            &from_glib_borrow(builderptr),
+            &name,
+            &*(valueptr as *const glib::Value),
+        )
+    }
+}
+
+unsafe extern "C" fn buildable_construct_child<T: BuildableImpl>(
+    buildable: *mut ffi::GtkBuildable,
+    builderptr: *mut ffi::GtkBuilder,
+    nameptr: *const libc::c_char,
+) -> *mut glib::gobject_ffi::GObject {
+    unsafe {
+        let instance = &*(buildable as *mut T::Instance);
+        let imp = instance.imp();
+        let name = GString::from_glib_borrow(nameptr);
+
+        imp.construct_child(&from_glib_borrow(builderptr), &name)
+            .to_glib_full()
+    }
+}
+
+unsafe extern "C" fn buildable_custom_tag_start<T: BuildableImpl>(
+    buildable: *mut ffi::GtkBuildable,
+    builderptr: *mut ffi::GtkBuilder,
+    childptr: *mut glib::gobject_ffi::GObject,
+    tagnameptr: *const libc::c_char,
+    parserptr: *mut glib::ffi::GMarkupParser,
+    dataptr: *mut glib::ffi::gpointer,
+) -> glib::ffi::gboolean {
+    unsafe {
+        let instance = &*(buildable as *mut T::Instance);
+        let imp = instance.imp();
+        let builder: Borrowed<Builder> = from_glib_borrow(builderptr);
+        let child: Option<Borrowed<glib::Object>> =
+            (!childptr.is_null()).then(|| from_glib_borrow(childptr));
+        let tagname = GString::from_glib_borrow(tagnameptr);
+
+        match imp.custom_tag_start(&builder, child.as_deref(), &tagname) {
+            Some(parser) => {
+                *parserptr = glib::ffi::GMarkupParser {
+                    start_element: Some(parser_start_element),
+                    end_element: Some(parser_end_element),
+                    text: Some(parser_text),
+                    passthrough: None,
+                    error: None,
+                };
+                let parser = Box::into_raw(Box::new(parser));
+                let data = parser as glib::ffi::gpointer;
+                builder.set_data(&parser_key(data), OwnedParser(parser));
+                *dataptr = data;
+                glib::ffi::GTRUE
+            }
+            None => {
+                let type_data = T::type_data();
+                let parent_iface = type_data.as_ref().parent_interface::<Buildable>()
+                    as *const ffi::GtkBuildableIface;
+
+                match (*parent_iface).custom_tag_start {
+                    Some(func) => func(
+                        buildable, builderptr, childptr, tagnameptr, parserptr, dataptr,
+                    ),
+                    None => glib::ffi::GFALSE,
+                }
+            }
+        }
+    }
+}
+
+unsafe extern "C" fn buildable_custom_tag_end<T: BuildableImpl>(
+    buildable: *mut ffi::GtkBuildable,
+    builderptr: *mut ffi::GtkBuilder,
+    childptr: *mut glib::gobject_ffi::GObject,
+    tagnameptr: *const libc::c_char,
+    data: glib::ffi::gpointer,
+) {
+    unsafe {
+        let builder: Borrowed<Builder> = from_glib_borrow(builderptr);
+        if !is_rust_parser(&builder, data) {
+            let type_data = T::type_data();
+            let parent_iface =
+                type_data.as_ref().parent_interface::<Buildable>() as *const ffi::GtkBuildableIface;
+
+            if let Some(func) = (*parent_iface).custom_tag_end {
+                func(buildable, builderptr, childptr, tagnameptr, data);
+            }
+            return;
+        }
+
+        let instance = &*(buildable as *mut T::Instance);
+        let imp = instance.imp();
+        let child: Option<Borrowed<glib::Object>> =
+            (!childptr.is_null()).then(|| from_glib_borrow(childptr));
+        let tagname = GString::from_glib_borrow(tagnameptr);
+        let parser = &mut *(data as *mut ParserData);
+
+        imp.custom_tag_end(&builder, child.as_deref(), &tagname, parser.as_mut())
+    }
+}
+
+unsafe extern "C" fn buildable_custom_finished<T: BuildableImpl>(
+    buildable: *mut ffi::GtkBuildable,
+    builderptr: *mut ffi::GtkBuilder,
+    childptr: *mut glib::gobject_ffi::GObject,
+    tagnameptr: *const libc::c_char,
+    data: glib::ffi::gpointer,
+) {
+    unsafe {
+        let builder: Borrowed<Builder> = from_glib_borrow(builderptr);
+        let Some(owned) = builder.steal_data::<OwnedParser>(&parser_key(data)) else {
+            let type_data = T::type_data();
+            let parent_iface =
+                type_data.as_ref().parent_interface::<Buildable>() as *const ffi::GtkBuildableIface;
+
+            if let Some(func) = (*parent_iface).custom_finished {
+                func(buildable, builderptr, childptr, tagnameptr, data);
+            }
+            return;
+        };
+
+        let instance = &*(buildable as *mut T::Instance);
+        let imp = instance.imp();
+        let child: Option<Borrowed<glib::Object>> =
+            (!childptr.is_null()).then(|| from_glib_borrow(childptr));
+        let tagname = GString::from_glib_borrow(tagnameptr);
+        let parser = *Box::from_raw(owned.0);
+        std::mem::forget(owned);
+
+        imp.custom_finished(&builder, child.as_deref(), &tagname, parser)
+    }
+}
+
+unsafe extern "C" fn buildable_parser_finished<T: BuildableImpl>(
+    buildable: *mut ffi::GtkBuildable,
+    builderptr: *mut ffi::GtkBuilder,
+) {
+    unsafe {
+        let instance = &*(buildable as *mut T::Instance);
+        let imp = instance.imp();
+
+        imp.parser_finished(&from_glib_borrow(builderptr))
+    }
+}
+
+unsafe extern "C" fn buildable_get_internal_child<T: BuildableImpl>(
+    buildable: *mut ffi::GtkBuildable,
+    builderptr: *mut ffi::GtkBuilder,
+    childnameptr: *const libc::c_char,
+) -> *mut glib::gobject_ffi::GObject {
+    unsafe {
+        let instance = &*(buildable as *mut T::Instance);
+        let imp = instance.imp();
+        let childname = GString::from_glib_borrow(childnameptr);
+
+        // transfer none: the internal child is owned by the buildable
+        imp.internal_child(&from_glib_borrow(builderptr), &childname)
+            .to_glib_none()
+            .0
+    }
+}
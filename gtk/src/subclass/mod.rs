@@ -4,6 +4,7 @@ pub mod application;
 pub mod application_window;
 pub mod bin;
 pub mod box_;
+pub mod buildable;
 pub mod button;
 pub mod cell_editable;
 pub mod cell_layout;
@@ -41,6 +42,7 @@ pub mod stack;
 pub mod toggle_button;
 pub mod tree_drag_dest;
 pub mod tree_drag_source;
+pub mod tree_model;
 pub mod tree_model_filter;
 pub mod tree_model_sort;
 pub mod tree_store;
@@ -56,6 +58,7 @@ pub mod prelude {
     pub use super::application_window::ApplicationWindowImpl;
     pub use super::bin::BinImpl;
     pub use super::box_::BoxImpl;
+    pub use super::buildable::{BuildableImpl, BuildableImplExt, BuildableParser};
     pub use super::button::ButtonImpl;
     pub use super::cell_editable::{CellEditableImpl, CellEditableImplExt};
     pub use super::cell_layout::{CellDataFunc, CellLayoutImpl, CellLayoutImplExt};
@@ -93,12 +96,14 @@ pub mod prelude {
     pub use super::toggle_button::ToggleButtonImpl;
     pub use super::tree_drag_dest::{TreeDragDestImpl, TreeDragDestImplExt};
     pub use super::tree_drag_source::{TreeDragSourceImpl, TreeDragSourceImplExt};
+    pub use super::tree_model::{TreeModelImpl, TreeModelImplExt};
     pub use super::tree_model_filter::{TreeModelFilterImpl, TreeModelFilterImplExt};
     pub use super::tree_model_sort::TreeModelSortImpl;
     pub use super::tree_store::TreeStoreImpl;
     pub use super::tree_view::{TreeViewImpl, TreeViewImplExt};
     pub use super::widget::{
-        CompositeTemplate, TemplateChild, WidgetClassSubclassExt, WidgetImpl, WidgetImplExt,
+        CompositeTemplate, CompositeTemplateCallbacks, TemplateCallbacks, TemplateChild,
+        WidgetClassSubclassExt, WidgetImpl, WidgetImplExt,
     };
     pub use super::window::{WindowImpl, WindowImplExt};
 }
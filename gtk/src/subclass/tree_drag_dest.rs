@@ -4,6 +4,27 @@ use glib::translate::*;
 
 use crate::{SelectionData, TreeDragDest, TreePath, ffi, prelude::*, subclass::prelude::*};
 
+// rustdoc-stripper-ignore-next
+/// Accepts rows dropped on a custom [`TreeModel`](crate::TreeModel).
+///
+/// With the DnD reordering set up by
+/// [`TreeViewExtManual::enable_model_drag_dest()`](crate::prelude::TreeViewExtManual::enable_model_drag_dest),
+/// `drag_data_received` reads the dragged row with
+/// [`tree_get_row_drag_data()`](crate::tree_get_row_drag_data) and inserts a copy of it
+/// before `dest`:
+///
+/// ```ignore
+/// impl TreeDragDestImpl for MyModel {
+///     fn drag_data_received(&self, dest: &TreePath, selection_data: &SelectionData) -> bool {
+///         match gtk::tree_get_row_drag_data(selection_data) {
+///             Some((Some(model), Some(path))) if model == *self.obj().upcast_ref() => {
+///                 self.copy_row(&path, dest)
+///             }
+///             _ => false,
+///         }
+///     }
+/// }
+/// ```
 pub trait TreeDragDestImpl: ObjectImpl + ObjectSubclass<Type: IsA<TreeDragDest>> {
     fn drag_data_received(&self, dest: &TreePath, selection_data: &SelectionData) -> bool;
 
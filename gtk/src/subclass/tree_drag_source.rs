@@ -4,6 +4,27 @@ use glib::translate::*;
 
 use crate::{SelectionData, TreeDragSource, TreePath, ffi, prelude::*, subclass::prelude::*};
 
+// rustdoc-stripper-ignore-next
+/// Makes the rows of a custom [`TreeModel`](crate::TreeModel) draggable.
+///
+/// To take part in the DnD reordering set up by
+/// [`TreeViewExtManual::enable_model_drag_source()`](crate::prelude::TreeViewExtManual::enable_model_drag_source)
+/// with [`TargetEntry::tree_model_row()`](crate::TargetEntry::tree_model_row),
+/// `drag_data_get` stores the row with
+/// [`tree_set_row_drag_data()`](crate::tree_set_row_drag_data), and `drag_data_delete`
+/// removes the row once it was moved:
+///
+/// ```ignore
+/// impl TreeDragSourceImpl for MyModel {
+///     fn drag_data_get(&self, path: &TreePath, selection_data: &SelectionData) -> bool {
+///         gtk::tree_set_row_drag_data(selection_data, &*self.obj(), &mut path.clone())
+///     }
+///
+///     fn drag_data_delete(&self, path: &TreePath) -> bool {
+///         self.remove_row(path)
+///     }
+/// }
+/// ```
 pub trait TreeDragSourceImpl: ObjectImpl + ObjectSubclass<Type: IsA<TreeDragSource>> {
     fn row_draggable(&self, path: &TreePath) -> bool {
         self.parent_row_draggable(path)
@@ -0,0 +1,513 @@
+// Take a look at the license at the top of the repository in the LICENSE file.
+
+use glib::translate::*;
+
+use crate::{TreeIter, TreeModel, TreeModelFlags, TreePath, ffi, prelude::*, subclass::prelude::*};
+
+pub trait TreeModelImpl: ObjectImpl + ObjectSubclass<Type: IsA<TreeModel>> {
+    fn flags(&self) -> TreeModelFlags {
+        self.parent_flags()
+    }
+
+    fn n_columns(&self) -> i32;
+
+    fn column_type(&self, index: i32) -> glib::Type;
+
+    fn iter(&self, path: &TreePath) -> Option<TreeIter>;
+
+    fn path(&self, iter: &TreeIter) -> TreePath;
+
+    fn value(&self, iter: &TreeIter, column: i32) -> glib::Value;
+
+    fn iter_next(&self, iter: &TreeIter) -> Option<TreeIter>;
+
+    fn iter_previous(&self, iter: &TreeIter) -> Option<TreeIter> {
+        self.parent_iter_previous(iter)
+    }
+
+    // Returns the first child of `parent`, or the first top-level row if `parent` is `None`
+    fn iter_children(&self, parent: Option<&TreeIter>) -> Option<TreeIter> {
+        self.iter_nth_child(parent, 0)
+    }
+
+    fn iter_has_child(&self, iter: &TreeIter) -> bool {
+        self.iter_n_children(Some(iter)) > 0
+    }
+
+    // Returns the number of top-level rows if `iter` is `None`
+    fn iter_n_children(&self, iter: Option<&TreeIter>) -> i32;
+
+    fn iter_nth_child(&self, parent: Option<&TreeIter>, n: i32) -> Option<TreeIter>;
+
+    fn iter_parent(&self, child: &TreeIter) -> Option<TreeIter>;
+
+    fn ref_node(&self, iter: &TreeIter) {
+        self.parent_ref_node(iter)
+    }
+
+    fn unref_node(&self, iter: &TreeIter) {
+        self.parent_unref_node(iter)
+    }
+}
+
+pub trait TreeModelImplExt: TreeModelImpl {
+    fn parent_flags(&self) -> TreeModelFlags {
+        unsafe {
+            let type_data = Self::type_data();
+            let parent_iface =
+                type_data.as_ref().parent_interface::<TreeModel>() as *const ffi::GtkTreeModelIface;
+
+            if let Some(func) = (*parent_iface).get_flags {
+                from_glib(func(
+                    self.obj().unsafe_cast_ref::<TreeModel>().to_glib_none().0,
+                ))
+            } else {
+                TreeModelFlags::empty()
+            }
+        }
+    }
+
+    fn parent_iter_previous(&self, iter: &TreeIter) -> Option<TreeIter> {
+        unsafe {
+            let type_data = Self::type_data();
+            let parent_iface =
+                type_data.as_ref().parent_interface::<TreeModel>() as *const ffi::GtkTreeModelIface;
+
+            if let Some(func) = (*parent_iface).iter_previous {
+                let mut iter = iter.clone();
+                let ret: bool = from_glib(func(
+                    self.obj().unsafe_cast_ref::<TreeModel>().to_glib_none().0,
+                    iter.to_glib_none_mut().0,
+                ));
+                ret.then_some(iter)
+            } else {
+                // Same fallback as gtk_tree_model_iter_previous(): go through the path
+                let mut path = self.path(iter);
+                if path.prev() { self.iter(&path) } else { None }
+            }
+        }
+    }
+
+    fn parent_ref_node(&self, iter: &TreeIter) {
+        unsafe {
+            let type_data = Self::type_data();
+            let parent_iface =
+                type_data.as_ref().parent_interface::<TreeModel>() as *const ffi::GtkTreeModelIface;
+
+            if let Some(func) = (*parent_iface).ref_node {
+                func(
+                    self.obj().unsafe_cast_ref::<TreeModel>().to_glib_none().0,
+                    mut_override(iter.to_glib_none().0),
+                )
+            }
+        }
+    }
+
+    fn parent_unref_node(&self, iter: &TreeIter) {
+        unsafe {
+            let type_data = Self::type_data();
+            let parent_iface =
+                type_data.as_ref().parent_interface::<TreeModel>() as *const ffi::GtkTreeModelIface;
+
+            if let Some(func) = (*parent_iface).unref_node {
+                func(
+                    self.obj().unsafe_cast_ref::<TreeModel>().to_glib_none().0,
+                    mut_override(iter.to_glib_none().0),
+                )
+            }
+        }
+    }
+
+    // Emits `row-inserted` for the row at `iter`, which must already be part of the model
+    fn emit_row_inserted(&self, iter: &TreeIter) {
+        let path = self.path(iter);
+        self.obj().row_inserted(&path, iter);
+    }
+
+    fn emit_row_changed(&self, iter: &TreeIter) {
+        let path = self.path(iter);
+        self.obj().row_changed(&path, iter);
+    }
+
+    fn emit_row_has_child_toggled(&self, iter: &TreeIter) {
+        let path = self.path(iter);
+        self.obj().row_has_child_toggled(&path, iter);
+    }
+
+    // Emits `row-deleted` for the row that was at `path`, which must already be removed
+    fn emit_row_deleted(&self, path: &TreePath) {
+        self.obj().row_deleted(path);
+    }
+
+    // `new_order[new_position] = old_position` for the children of `parent`, or of the
+    // top-level rows if `parent` is `None`
+    fn emit_rows_reordered(&self, parent: Option<&TreeIter>, new_order: &[i32]) {
+        let path = match parent {
+            Some(parent) => self.path(parent),
+            None => TreePath::new(),
+        };
+        self.obj()
+            .rows_reordered_with_length(&path, parent, new_order);
+    }
+}
+
+impl<T: TreeModelImpl> TreeModelImplExt for T {}
+
+unsafe impl<T: TreeModelImpl> IsImplementable<T> for TreeModel {
+    fn interface_init(iface: &mut glib::Interface<Self>) {
+        let iface = iface.as_mut();
+
+        if !crate::rt::is_initialized() {
+            panic!("GTK has to be initialized first");
+        }
+
+        iface.get_flags = Some(tree_model_get_flags::<T>);
+        iface.get_n_columns = Some(tree_model_get_n_columns::<T>);
+        iface.get_column_type = Some(tree_model_get_column_type::<T>);
+        iface.get_iter = Some(tree_model_get_iter::<T>);
+        iface.get_path = Some(tree_model_get_path::<T>);
+        iface.get_value = Some(tree_model_get_value::<T>);
+        iface.iter_next = Some(tree_model_iter_next::<T>);
+        iface.iter_previous = Some(tree_model_iter_previous::<T>);
+        iface.iter_children = Some(tree_model_iter_children::<T>);
+        iface.iter_has_child = Some(tree_model_iter_has_child::<T>);
+        iface.iter_n_children = Some(tree_model_iter_n_children::<T>);
+        iface.iter_nth_child = Some(tree_model_iter_nth_child::<T>);
+        iface.iter_parent = Some(tree_model_iter_parent::<T>);
+        iface.ref_node = Some(tree_model_ref_node::<T>);
+        iface.unref_node = Some(tree_model_unref_node::<T>);
+    }
+}
+
+// Copies `iter` into the caller-provided `iterptr` and returns whether there was one
+unsafe fn set_iter(iterptr: *mut ffi::GtkTreeIter, iter: Option<TreeIter>) -> glib::ffi::gboolean {
+    unsafe {
+        match iter {
+            Some(iter) => {
+                *iterptr = *iter.to_glib_none().0;
+                glib::ffi::GTRUE
+            }
+            None => glib::ffi::GFALSE,
+        }
+    }
+}
+
+unsafe extern "C" fn tree_model_get_flags<T: TreeModelImpl>(
+    tree_model: *mut ffi::GtkTreeModel,
+) -> ffi::GtkTreeModelFlags {
+    unsafe {
+        let instance = &*(tree_model as *mut T::Instance);
+        let imp = instance.imp();
+
+        imp.flags().into_glib()
+    }
+}
+
+unsafe extern "C" fn tree_model_get_n_columns<T: TreeModelImpl>(
+    tree_model: *mut ffi::GtkTreeModel,
+) -> i32 {
+    unsafe {
+        let instance = &*(tree_model as *mut T::Instance);
+        let imp = instance.imp();
+
+        imp.n_columns()
+    }
+}
+
+unsafe extern "C" fn tree_model_get_column_type<T: TreeModelImpl>(
+    tree_model: *mut ffi::GtkTreeModel,
+    index: i32,
+) -> glib::ffi::GType {
+    unsafe {
+        let instance = &*(tree_model as *mut T::Instance);
+        let imp = instance.imp();
+
+        imp.column_type(index).into_glib()
+    }
+}
+
+unsafe extern "C" fn tree_model_get_iter<T: TreeModelImpl>(
+    tree_model: *mut ffi::GtkTreeModel,
+    iterptr: *mut ffi::GtkTreeIter,
+    pathptr: *mut ffi::GtkTreePath,
+) -> glib::ffi::gboolean {
+    unsafe {
+        let instance = &*(tree_model as *mut T::Instance);
+        let imp = instance.imp();
+        let path: Borrowed<TreePath> = from_glib_borrow(pathptr);
+
+        set_iter(iterptr, imp.iter(&path))
+    }
+}
+
+unsafe extern "C" fn tree_model_get_path<T: TreeModelImpl>(
+    tree_model: *mut ffi::GtkTreeModel,
+    iterptr: *mut ffi::GtkTreeIter,
+) -> *mut ffi::GtkTreePath {
+    unsafe {
+        let instance = &*(tree_model as *mut T::Instance);
+        let imp = instance.imp();
+        let iter: Borrowed<TreeIter> = from_glib_borrow(iterptr);
+
+        imp.path(&iter).to_glib_full()
+    }
+}
+
+unsafe extern "C" fn tree_model_get_value<T: TreeModelImpl>(
+    tree_model: *mut ffi::GtkTreeModel,
+    iterptr: *mut ffi::GtkTreeIter,
+    column: i32,
+    valueptr: *mut glib::gobject_ffi::GValue,
+) {
+    unsafe {
+        let instance = &*(tree_model as *mut T::Instance);
+        let imp = instance.imp();
+        let iter: Borrowed<TreeIter> = from_glib_borrow(iterptr);
+
+        let v = imp.value(&iter, column);
+
+        // `valueptr` has not been initialized, so no need to unset first.  Then consume `v` and
+        // transfer ownership of its bits to `valueptr`.
+        std::ptr::write(valueptr, v.into_raw());
+    }
+}
+
+unsafe extern "C" fn tree_model_iter_next<T: TreeModelImpl>(
+    tree_model: *mut ffi::GtkTreeModel,
+    iterptr: *mut ffi::GtkTreeIter,
+) -> glib::ffi::gboolean {
+    unsafe {
+        let instance = &*(tree_model as *mut T::Instance);
+        let imp = instance.imp();
+        let iter: Borrowed<TreeIter> = from_glib_borrow(iterptr);
+
+        let next = imp.iter_next(&iter);
+        set_iter(iterptr, next)
+    }
+}
+
+unsafe extern "C" fn tree_model_iter_previous<T: TreeModelImpl>(
+    tree_model: *mut ffi::GtkTreeModel,
+    iterptr: *mut ffi::GtkTreeIter,
+) -> glib::ffi::gboolean {
+    unsafe {
+        let instance = &*(tree_model as *mut T::Instance);
+        let imp = instance.imp();
+        let iter: Borrowed<TreeIter> = from_glib_borrow(iterptr);
+
+        let previous = imp.iter_previous(&iter);
+        set_iter(iterptr, previous)
+    }
+}
+
+unsafe extern "C" fn tree_model_iter_children<T: TreeModelImpl>(
+    tree_model: *mut ffi::GtkTreeModel,
+    iterptr: *mut ffi::GtkTreeIter,
+    parentptr: *mut ffi::GtkTreeIter,
+) -> glib::ffi::gboolean {
+    unsafe {
+        let instance = &*(tree_model as *mut T::Instance);
+        let imp = instance.imp();
+        let parent: Option<Borrowed<TreeIter>> =
+            (!parentptr.is_null()).then(|| from_glib_borrow(parentptr));
+
+        set_iter(iterptr, imp.iter_children(parent.as_deref()))
+    }
+}
+
+unsafe extern "C" fn tree_model_iter_has_child<T: TreeModelImpl>(
+    tree_model: *mut ffi::GtkTreeModel,
+    iterptr: *mut ffi::GtkTreeIter,
+) -> glib::ffi::gboolean {
+    unsafe {
+        let instance = &*(tree_model as *mut T::Instance);
+        let imp = instance.imp();
+        let iter: Borrowed<TreeIter> = from_glib_borrow(iterptr);
+
+        imp.iter_has_child(&iter).into_glib()
+    }
+}
+
+unsafe extern "C" fn tree_model_iter_n_children<T: TreeModelImpl>(
+    tree_model: *mut ffi::GtkTreeModel,
+    iterptr: *mut ffi::GtkTreeIter,
+) -> i32 {
+    unsafe {
+        let instance = &*(tree_model as *mut T::Instance);
+        let imp = instance.imp();
+        let iter: Option<Borrowed<TreeIter>> =
+            (!iterptr.is_null()).then(|| from_glib_borrow(iterptr));
+
+        imp.iter_n_children(iter.as_deref())
+    }
+}
+
+unsafe extern "C" fn tree_model_iter_nth_child<T: TreeModelImpl>(
+    tree_model: *mut ffi::GtkTreeModel,
+    iterptr: *mut ffi::GtkTreeIter,
+    parentptr: *mut ffi::GtkTreeIter,
+    n: i32,
+) -> glib::ffi::gboolean {
+    unsafe {
+        let instance = &*(tree_model as *mut T::Instance);
+        let imp = instance.imp();
+        let parent: Option<Borrowed<TreeIter>> =
+            (!parentptr.is_null()).then(|| from_glib_borrow(parentptr));
+
+        set_iter(iterptr, imp.iter_nth_child(parent.as_deref(), n))
+    }
+}
+
+unsafe extern "C" fn tree_model_iter_parent<T: TreeModelImpl>(
+    tree_model: *mut ffi::GtkTreeModel,
+    iterptr: *mut ffi::GtkTreeIter,
+    childptr: *mut ffi::GtkTreeIter,
+) -> glib::ffi::gboolean {
+    unsafe {
+        let instance = &*(tree_model as *mut T::Instance);
+        let imp = instance.imp();
+        let child: Borrowed<TreeIter> = from_glib_borrow(childptr);
+
+        set_iter(iterptr, imp.iter_parent(&child))
+    }
+}
+
+unsafe extern "C" fn tree_model_ref_node<T: TreeModelImpl>(
+    tree_model: *mut ffi::GtkTreeModel,
+    iterptr: *mut ffi::GtkTreeIter,
+) {
+    unsafe {
+        let instance = &*(tree_model as *mut T::Instance);
+        let imp = instance.imp();
+        let iter: Borrowed<TreeIter> = from_glib_borrow(iterptr);
+
+        imp.ref_node(&iter)
+    }
+}
+
+unsafe extern "C" fn tree_model_unref_node<T: TreeModelImpl>(
+    tree_model: *mut ffi::GtkTreeModel,
+    iterptr: *mut ffi::GtkTreeIter,
+) {
+    unsafe {
+        let instance = &*(tree_model as *mut T::Instance);
+        let imp = instance.imp();
+        let iter: Borrowed<TreeIter> = from_glib_borrow(iterptr);
+
+        imp.unref_node(&iter)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::cell::RefCell;
+    use std::rc::Rc;
+
+    use super::*;
+    use crate::TEST_THREAD_WORKER;
+
+    mod imp {
+        use super::*;
+
+        #[derive(Default)]
+        pub struct Names(pub RefCell<Vec<String>>);
+
+        #[glib::object_subclass]
+        impl ObjectSubclass for Names {
+            const NAME: &'static str = "GtkRsTestNames";
+            type Type = super::Names;
+            type Interfaces = (TreeModel,);
+        }
+
+        impl ObjectImpl for Names {}
+
+        impl TreeModelImpl for Names {
+            fn flags(&self) -> TreeModelFlags {
+                TreeModelFlags::LIST_ONLY
+            }
+
+            fn n_columns(&self) -> i32 {
+                1
+            }
+
+            fn column_type(&self, _index: i32) -> glib::Type {
+                glib::Type::STRING
+            }
+
+            fn iter(&self, path: &TreePath) -> Option<TreeIter> {
+                self.iter_nth_child(None, *path.indices().first()?)
+            }
+
+            fn path(&self, iter: &TreeIter) -> TreePath {
+                TreePath::from_indicesv(&[iter.handle() as i32])
+            }
+
+            fn value(&self, iter: &TreeIter, _column: i32) -> glib::Value {
+                self.0.borrow()[iter.handle()].to_value()
+            }
+
+            fn iter_next(&self, iter: &TreeIter) -> Option<TreeIter> {
+                self.iter_nth_child(None, iter.handle() as i32 + 1)
+            }
+
+            fn iter_n_children(&self, iter: Option<&TreeIter>) -> i32 {
+                match iter {
+                    Some(_) => 0,
+                    None => self.0.borrow().len() as i32,
+                }
+            }
+
+            fn iter_nth_child(&self, parent: Option<&TreeIter>, n: i32) -> Option<TreeIter> {
+                (parent.is_none() && (0..self.0.borrow().len() as i32).contains(&n))
+                    .then(|| TreeIter::from_handle(0, n as usize))
+            }
+
+            fn iter_parent(&self, _child: &TreeIter) -> Option<TreeIter> {
+                None
+            }
+        }
+    }
+
+    glib::wrapper! {
+        pub struct Names(ObjectSubclass<imp::Names>) @implements TreeModel;
+    }
+
+    impl Names {
+        fn insert(&self, position: usize, name: &str) {
+            self.imp().0.borrow_mut().insert(position, name.to_owned());
+            self.imp()
+                .emit_row_inserted(&TreeIter::from_handle(0, position));
+        }
+    }
+
+    #[test]
+    fn emit_row_inserted() {
+        TEST_THREAD_WORKER
+            .push(|| {
+                let names = glib::Object::new::<Names>();
+                let inserted = Rc::new(RefCell::new(Vec::new()));
+                names.connect_row_inserted({
+                    let inserted = inserted.clone();
+                    move |model, path, iter| {
+                        inserted
+                            .borrow_mut()
+                            .push((path.indices(), model.get::<String>(iter, 0)));
+                    }
+                });
+
+                names.insert(0, "b");
+                names.insert(0, "a");
+                names.insert(2, "c");
+                assert_eq!(
+                    *inserted.borrow(),
+                    [
+                        (vec![0], "b".to_owned()),
+                        (vec![0], "a".to_owned()),
+                        (vec![2], "c".to_owned()),
+                    ]
+                );
+            })
+            .expect("Failed to schedule a test call");
+        while TEST_THREAD_WORKER.unprocessed() > 0 {}
+    }
+}
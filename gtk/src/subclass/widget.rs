@@ -2,6 +2,8 @@
 
 use glib::subclass::SignalId;
 use libc::c_int;
+use std::collections::HashSet;
+use std::ffi::CStr;
 use std::mem;
 use std::num::NonZeroU32;
 
@@ -10,6 +12,7 @@ use glib::prelude::*;
 use glib::subclass::prelude::*;
 use glib::translate::*;
 
+use crate::Builder;
 use crate::DirectionType;
 use crate::DragResult;
 use crate::Orientation;
@@ -2746,6 +2749,7 @@ pub unsafe trait WidgetClassSubclassExt: ClassStruct<Type: WidgetImpl> {
         unsafe {
             let widget_class = self as *mut _ as *mut ffi::GtkWidgetClass;
             ffi::gtk_widget_class_set_template(widget_class, template.to_glib_none().0);
+            set_template_object_ids::<Self::Type>(template);
         }
     }
 
@@ -2791,6 +2795,10 @@ pub unsafe trait WidgetClassSubclassExt: ClassStruct<Type: WidgetImpl> {
                 widget_class,
                 resource_name.to_glib_none().0,
             );
+            let template =
+                gio::resources_lookup_data(resource_name, gio::ResourceLookupFlags::NONE)
+                    .unwrap_or_else(|_| glib::Bytes::from_static(b""));
+            set_template_object_ids::<Self::Type>(&template);
         }
     }
 
@@ -2813,18 +2821,50 @@ pub unsafe trait WidgetClassSubclassExt: ClassStruct<Type: WidgetImpl> {
         offset: field_offset::FieldOffset<Self::Type, TemplateChild<T>>,
     ) where
         T: ObjectType + FromGlibPtrNone<*mut <T as ObjectType>::GlibType>,
+    {
+        unsafe { bind_template_child_full(self, name, false, offset) }
+    }
+
+    // rustdoc-stripper-ignore-next
+    /// Binds the template object `name` to a [`TemplateChild`] field, like
+    /// [`bind_template_child_with_offset`](Self::bind_template_child_with_offset), and lets
+    /// the UI definitions that use this widget refer to it as an `internal-child`.
+    #[allow(clippy::missing_safety_doc)]
+    unsafe fn bind_internal_template_child_with_offset<T>(
+        &mut self,
+        name: &str,
+        offset: field_offset::FieldOffset<Self::Type, TemplateChild<T>>,
+    ) where
+        T: ObjectType + FromGlibPtrNone<*mut <T as ObjectType>::GlibType>,
+    {
+        unsafe { bind_template_child_full(self, name, true, offset) }
+    }
+
+    // rustdoc-stripper-ignore-next
+    /// Binds the template widget `name` to a [`TemplateChild`] field, if the template
+    /// declares it.
+    ///
+    /// The template must have been set before. If it has no object with the id `name`,
+    /// the field stays unbound, see [`TemplateChild::try_get`]. Otherwise, this is the
+    /// same as [`bind_template_child_with_offset`](Self::bind_template_child_with_offset).
+    #[allow(clippy::missing_safety_doc)]
+    unsafe fn bind_optional_template_child_with_offset<T>(
+        &mut self,
+        name: &str,
+        offset: field_offset::FieldOffset<Self::Type, TemplateChild<T>>,
+    ) where
+        T: ObjectType + FromGlibPtrNone<*mut <T as ObjectType>::GlibType>,
     {
         unsafe {
-            let widget_class = self as *mut _ as *mut ffi::GtkWidgetClass;
-            let private_offset = <Self::Type as ObjectSubclassType>::type_data()
+            let declared = <Self::Type as ObjectSubclassType>::type_data()
                 .as_ref()
-                .impl_offset();
-            ffi::gtk_widget_class_bind_template_child_full(
-                widget_class,
-                name.to_glib_none().0,
-                false as glib::ffi::gboolean,
-                private_offset + (offset.get_byte_offset() as isize),
-            )
+                .class_data::<TemplateObjectIds>(<Self::Type as ObjectSubclassType>::type_())
+                .expect("The template must be set before binding template children")
+                .0
+                .contains(name);
+            if declared {
+                self.bind_template_child_with_offset(name, offset);
+            }
         }
     }
 
@@ -2847,6 +2887,98 @@ pub unsafe trait WidgetClassSubclassExt: ClassStruct<Type: WidgetImpl> {
 
 unsafe impl<T: ClassStruct> WidgetClassSubclassExt for T where T::Type: WidgetImpl {}
 
+unsafe fn bind_template_child_full<C, T>(
+    klass: &mut C,
+    name: &str,
+    internal: bool,
+    offset: field_offset::FieldOffset<C::Type, TemplateChild<T>>,
+) where
+    C: ClassStruct,
+    T: ObjectType + FromGlibPtrNone<*mut <T as ObjectType>::GlibType>,
+{
+    unsafe {
+        let widget_class = klass as *mut _ as *mut ffi::GtkWidgetClass;
+        let private_offset = <C::Type as ObjectSubclassType>::type_data()
+            .as_ref()
+            .impl_offset();
+        ffi::gtk_widget_class_bind_template_child_full(
+            widget_class,
+            name.to_glib_none().0,
+            internal.into_glib(),
+            private_offset + (offset.get_byte_offset() as isize),
+        )
+    }
+}
+
+// The ids of the objects declared in the template of a class, to bind its optional
+// template children
+struct TemplateObjectIds(HashSet<String>);
+
+unsafe fn set_template_object_ids<T: ObjectSubclassType>(template: &[u8]) {
+    unsafe {
+        let ids = TemplateObjectIds(template_object_ids(template));
+        let mut type_data = T::type_data();
+        let type_data = type_data.as_mut();
+        if let Some(data) = type_data.class_data_mut::<TemplateObjectIds>(T::type_()) {
+            *data = ids;
+            return;
+        }
+        type_data.set_class_data(T::type_(), ids);
+    }
+}
+
+fn template_object_ids(template: &[u8]) -> HashSet<String> {
+    unsafe extern "C" fn start_element(
+        _context: *mut glib::ffi::GMarkupParseContext,
+        element_name: *const libc::c_char,
+        attribute_names: *mut *const libc::c_char,
+        attribute_values: *mut *const libc::c_char,
+        user_data: glib::ffi::gpointer,
+        _error: *mut *mut glib::ffi::GError,
+    ) {
+        unsafe {
+            if CStr::from_ptr(element_name).to_bytes() != b"object" {
+                return;
+            }
+            let ids = &mut *(user_data as *mut HashSet<String>);
+            let mut i = 0;
+            while !(*attribute_names.add(i)).is_null() {
+                if CStr::from_ptr(*attribute_names.add(i)).to_bytes() == b"id" {
+                    let id = CStr::from_ptr(*attribute_values.add(i));
+                    ids.insert(id.to_string_lossy().into_owned());
+                }
+                i += 1;
+            }
+        }
+    }
+
+    let parser = glib::ffi::GMarkupParser {
+        start_element: Some(start_element),
+        end_element: None,
+        text: None,
+        passthrough: None,
+        error: None,
+    };
+    let mut ids = HashSet::new();
+    unsafe {
+        let context = glib::ffi::g_markup_parse_context_new(
+            &parser,
+            0,
+            &mut ids as *mut HashSet<String> as glib::ffi::gpointer,
+            None,
+        );
+        // Invalid templates are reported by GTK when it builds them
+        glib::ffi::g_markup_parse_context_parse(
+            context,
+            template.as_ptr() as *const libc::c_char,
+            template.len() as isize,
+            std::ptr::null_mut(),
+        );
+        glib::ffi::g_markup_parse_context_free(context);
+    }
+    ids
+}
+
 #[derive(Debug, PartialEq, Eq)]
 #[repr(transparent)]
 pub struct TemplateChild<T>
@@ -2895,13 +3027,149 @@ where
 {
     #[track_caller]
     pub fn get(&self) -> T {
-        unsafe {
-            Option::<T>::from_glib_none(self.ptr)
-                .expect("Failed to retrieve template child. Please check that it has been bound.")
-        }
+        self.try_get()
+            .expect("Failed to retrieve template child. Please check that it has been bound.")
+    }
+
+    // rustdoc-stripper-ignore-next
+    /// Returns the template child, or `None` if it has not been bound.
+    ///
+    /// This is the way to access optional template children, which are only bound
+    /// if the template declares them.
+    pub fn try_get(&self) -> Option<T> {
+        unsafe { Option::<T>::from_glib_none(self.ptr) }
+    }
+
+    // rustdoc-stripper-ignore-next
+    /// Returns `true` if the template child has been bound.
+    pub fn is_bound(&self) -> bool {
+        !self.ptr.is_null()
     }
 }
 
 pub trait CompositeTemplate: WidgetImpl {
     fn bind_template(klass: &mut Self::Class);
 }
+
+// rustdoc-stripper-ignore-next
+/// A Rust function bound to a signal handler name of a composite template.
+///
+/// It receives the template instance and the signal handler arguments, in the order a C
+/// handler would see them: the emitting object, the signal arguments and the user data
+/// object last, or with the emitting object and the user data swapped for handlers
+/// declared with `swapped="yes"`. The user data object is the one named by the `object`
+/// attribute of the `<signal>` element, or the template instance itself.
+pub type TemplateCallback<T> = fn(&T, &[glib::Value]) -> Option<glib::Value>;
+
+// rustdoc-stripper-ignore-next
+/// The signal handlers a composite template can refer to by name.
+///
+/// This is usually implemented with the [`template_callbacks`](crate::template_callbacks)
+/// attribute macro.
+pub trait TemplateCallbacks: ObjectSubclass {
+    const CALLBACKS: &'static [(&'static str, TemplateCallback<Self::Type>)];
+}
+
+// rustdoc-stripper-ignore-next
+/// Connects the signal handlers declared in the template of a class to its
+/// [`TemplateCallbacks`].
+///
+/// This is implemented for every widget subclass implementing [`TemplateCallbacks`].
+pub trait CompositeTemplateCallbacks: WidgetImpl + TemplateCallbacks {
+    // rustdoc-stripper-ignore-next
+    /// Resolves the signal handlers of the class template to [`TemplateCallbacks::CALLBACKS`].
+    ///
+    /// This must be called in `class_init`, after the template has been set.
+    #[doc(alias = "gtk_widget_class_set_connect_func")]
+    fn bind_template_callbacks(klass: &mut Self::Class) {
+        unsafe {
+            let widget_class = klass as *mut _ as *mut ffi::GtkWidgetClass;
+            ffi::gtk_widget_class_set_connect_func(
+                widget_class,
+                Some(template_connect_func::<Self>),
+                std::ptr::null_mut(),
+                None,
+            );
+        }
+    }
+}
+
+impl<T: WidgetImpl + TemplateCallbacks> CompositeTemplateCallbacks for T {}
+
+unsafe extern "C" fn template_connect_func<T: WidgetImpl + TemplateCallbacks>(
+    builder: *mut ffi::GtkBuilder,
+    object: *mut glib::gobject_ffi::GObject,
+    signal_name: *const libc::c_char,
+    handler_name: *const libc::c_char,
+    connect_object: *mut glib::gobject_ffi::GObject,
+    flags: glib::gobject_ffi::GConnectFlags,
+    _user_data: glib::ffi::gpointer,
+) {
+    unsafe {
+        let builder: Borrowed<Builder> = from_glib_borrow(builder);
+        let object: Borrowed<glib::Object> = from_glib_borrow(object);
+        let signal_name: Borrowed<glib::GString> = from_glib_borrow(signal_name);
+        let handler_name: Borrowed<glib::GString> = from_glib_borrow(handler_name);
+        let connect_object: Borrowed<Option<glib::Object>> = from_glib_borrow(connect_object);
+
+        let Some(callback) = T::CALLBACKS
+            .iter()
+            .find(|(name, _)| *name == handler_name.as_str())
+            .map(|(_, callback)| *callback)
+        else {
+            glib::g_critical!(
+                "Gtk",
+                "No template callback named '{}' for the template of '{}'",
+                handler_name.as_str(),
+                T::NAME
+            );
+            return;
+        };
+
+        // The template instance is exposed to the builder under the name of its type.
+        let Some(instance) = builder.object::<T::Type>(T::type_().name()) else {
+            glib::g_critical!(
+                "Gtk",
+                "The instance of '{}' is not exposed to the builder of its template",
+                T::NAME
+            );
+            return;
+        };
+        let user_data = (*connect_object)
+            .clone()
+            .unwrap_or_else(|| instance.clone().upcast());
+
+        let instance = instance.downgrade();
+        let user_data = user_data.downgrade();
+        let swapped = flags & glib::gobject_ffi::G_CONNECT_SWAPPED != 0;
+        // Once the objects are gone, handlers of signals with a return value
+        // still have to return a value of the right type.
+        let return_type = SignalId::parse_name(signal_name.as_str(), object.type_(), false)
+            .map(|(signal_id, _)| signal_id.query().return_type().type_())
+            .unwrap_or(glib::Type::UNIT);
+
+        object.connect_unsafe(
+            signal_name.as_str(),
+            flags & glib::gobject_ffi::G_CONNECT_AFTER != 0,
+            move |values| {
+                let (Some(instance), Some(user_data)) = (instance.upgrade(), user_data.upgrade())
+                else {
+                    return (return_type != glib::Type::UNIT)
+                        .then(|| glib::Value::from_type(return_type));
+                };
+
+                let mut args = Vec::with_capacity(values.len() + 1);
+                if swapped {
+                    args.push(user_data.to_value());
+                    args.extend_from_slice(&values[1..]);
+                    args.push(values[0].clone());
+                } else {
+                    args.extend_from_slice(values);
+                    args.push(user_data.to_value());
+                }
+
+                callback(&instance, &args)
+            },
+        );
+    }
+}
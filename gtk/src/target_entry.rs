@@ -24,6 +24,20 @@ impl TargetEntry {
         }
     }
 
+    // rustdoc-stripper-ignore-next
+    /// Returns the target used by [`TreeView`](crate::TreeView) and
+    /// [`IconView`](crate::IconView) to move rows of their model with
+    /// [`enable_model_drag_source()`](crate::prelude::TreeViewExtManual::enable_model_drag_source)
+    /// and [`enable_model_drag_dest()`](crate::prelude::TreeViewExtManual::enable_model_drag_dest),
+    /// restricted to the same application.
+    ///
+    /// The row of such a drag is stored with [`tree_set_row_drag_data()`](crate::tree_set_row_drag_data)
+    /// and read with [`tree_get_row_drag_data()`](crate::tree_get_row_drag_data).
+    pub fn tree_model_row() -> TargetEntry {
+        assert_initialized_main_thread!();
+        Self::new("GTK_TREE_MODEL_ROW", TargetFlags::SAME_APP, 0)
+    }
+
     #[doc(alias = "get_target")]
     pub fn target(&self) -> &str {
         &self.target
@@ -0,0 +1,806 @@
+// Take a look at the license at the top of the repository in the LICENSE file.
+
+// rustdoc-stripper-ignore-next
+//! Helpers for writing UI tests with `cargo test`.
+//!
+//! This module is only available with the `test-utils` feature, which is usually enabled in
+//! the `[dev-dependencies]` of a crate.
+//!
+//! GTK may only be used from one thread, while the Rust test harness runs every test on a
+//! thread of its own. [`run()`] therefore runs the body of a test on a single thread shared by
+//! all tests of the test binary, initializing GTK with [`init_deterministic()`] the first time
+//! it is used, so widgets look the same on every machine.
+//!
+//! The tests need a display to run on. Either run them under a local X server like `Xvfb`, for
+//! example with `xvfb-run cargo test`, or start `broadwayd` and set `BROADWAY_DISPLAY`.
+//!
+//! ```no_run
+//! use gtk::prelude::*;
+//!
+//! #[test]
+//! fn click_counts() {
+//!     gtk::test::run(|| {
+//!         let window = gtk::Window::new(gtk::WindowType::Toplevel);
+//!         let button = gtk::Button::with_label("Count");
+//!         let clicks = std::rc::Rc::new(std::cell::Cell::new(0));
+//!         let counter = clicks.clone();
+//!         button.connect_clicked(move |_| counter.set(counter.get() + 1));
+//!         window.add(&button);
+//!         window.show_all();
+//!         gtk::test::run_until_idle();
+//!
+//!         gtk::test::click(&button);
+//!         assert!(gtk::test::run_until(
+//!             std::time::Duration::from_secs(1),
+//!             || clicks.get() == 1
+//!         ));
+//!         gtk::test::assert_sensitive(&button);
+//!
+//!         unsafe { window.destroy() };
+//!     });
+//! }
+//! ```
+
+use std::panic::{self, AssertUnwindSafe};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc;
+use std::sync::{LazyLock, Mutex};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use glib::translate::*;
+
+use crate::{Widget, ffi, prelude::*};
+
+// rustdoc-stripper-ignore-next
+/// The longest time [`run_until_idle()`] keeps iterating the main loop.
+pub const IDLE_TIMEOUT: Duration = Duration::from_secs(5);
+
+// Jobs are given the result of initializing GTK on the test thread.
+type Job = Box<dyn FnOnce(Result<(), &glib::BoolError>) + Send + 'static>;
+
+// rustdoc-stripper-ignore-next
+/// Initializes GTK for tests.
+///
+/// If `GDK_BACKEND` is not set, this selects the Broadway backend when `BROADWAY_DISPLAY` is
+/// set, and otherwise requires one of `DISPLAY` or `WAYLAND_DISPLAY`, so tests fail with a
+/// helpful message instead of trying to connect to a display that doesn't exist.
+///
+/// Tests that manage their own thread, for example with `harness = false`, can call this
+/// instead of [`init_deterministic()`] to run with the theme and settings of the user.
+pub fn init() -> Result<(), glib::BoolError> {
+    skip_assert_initialized!();
+    if crate::is_initialized_main_thread() {
+        return Ok(());
+    }
+
+    let is_set = |var| std::env::var_os(var).is_some_and(|value| !value.is_empty());
+    if !is_set("GDK_BACKEND") {
+        if is_set("BROADWAY_DISPLAY") {
+            gdk::set_allowed_backends("broadway");
+        } else if !is_set("DISPLAY") && !is_set("WAYLAND_DISPLAY") {
+            return Err(glib::bool_error!(
+                "No display to run the tests on. Run them under `xvfb-run`, or start `broadwayd` and set `BROADWAY_DISPLAY`"
+            ));
+        }
+    }
+
+    crate::init()
+}
+
+// rustdoc-stripper-ignore-next
+/// The font [`init_deterministic()`] sets as default font.
+pub const DETERMINISTIC_FONT: &str = "Sans 10";
+
+// rustdoc-stripper-ignore-next
+/// Initializes GTK for tests like [`init()`], but independent of the machine the tests run on.
+///
+/// This resets all [`Settings`](crate::Settings) to their defaults, so the `settings.ini`
+/// files and the settings of the desktop session are ignored, and then overrides those that
+/// affect rendering: the theme, icon theme and font ([`DETERMINISTIC_FONT`]), a resolution of
+/// 96 DPI with grayscale antialiasing and no hinting, and it disables animations and cursor
+/// blinking.
+///
+/// The configuration of the user that can't be overridden makes this fail instead: if
+/// `GTK_THEME` is set to another theme than `Adwaita`, if `GDK_SCALE` or `GDK_DPI_SCALE` is set
+/// to another value than 1, if `GTK_MODULES` is set, if the user has CSS in `gtk-3.0/gtk.css`,
+/// or if `GDK_SCALE` is not set and a monitor has a scale factor other than 1. Running the
+/// tests under `xvfb-run`, or calling [`set_deterministic_environment()`] first, avoids all of
+/// these.
+///
+/// Also fails if GTK was already initialized without this function.
+pub fn init_deterministic() -> Result<(), glib::BoolError> {
+    skip_assert_initialized!();
+    if crate::is_initialized() {
+        return if DETERMINISTIC.load(Ordering::Acquire) {
+            Ok(())
+        } else {
+            Err(glib::bool_error!(
+                "GTK was already initialized without a deterministic environment"
+            ))
+        };
+    }
+
+    let overriding = [
+        ("GTK_THEME", Some("Adwaita")),
+        ("GDK_SCALE", Some("1")),
+        ("GDK_DPI_SCALE", Some("1")),
+        ("GTK_MODULES", None),
+    ]
+    .into_iter()
+    .filter_map(|(var, expected)| {
+        let value = std::env::var_os(var).filter(|value| !value.is_empty())?;
+        (Some(value.as_os_str()) != expected.map(std::ffi::OsStr::new))
+            .then(|| format!("{var}={}", value.to_string_lossy()))
+    })
+    .collect::<Vec<_>>();
+    if !overriding.is_empty() {
+        return Err(glib::bool_error!(
+            "The environment overrides the deterministic settings of the tests: {}",
+            overriding.join(", ")
+        ));
+    }
+
+    let user_css = glib::user_config_dir().join("gtk-3.0").join("gtk.css");
+    if user_css.exists() {
+        return Err(glib::bool_error!(
+            "The CSS of the user in {} would be applied to the tests",
+            user_css.display()
+        ));
+    }
+
+    init()?;
+
+    if std::env::var_os("GDK_SCALE").is_none_or(|value| value.is_empty()) {
+        let display =
+            gdk::Display::default().ok_or_else(|| glib::bool_error!("No default display"))?;
+        let scaled = (0..display.n_monitors())
+            .filter_map(|index| display.monitor(index))
+            .any(|monitor| monitor.scale_factor() != 1);
+        if scaled {
+            return Err(glib::bool_error!(
+                "A monitor has a scale factor other than 1, set `GDK_SCALE=1` to run the tests"
+            ));
+        }
+    }
+
+    let settings =
+        crate::Settings::default().ok_or_else(|| glib::bool_error!("No default settings"))?;
+    // Values set by the application take precedence over `settings.ini` and the session
+    for pspec in settings.list_properties().iter() {
+        let flags = pspec.flags();
+        if flags.contains(glib::ParamFlags::WRITABLE)
+            && !flags.contains(glib::ParamFlags::CONSTRUCT_ONLY)
+        {
+            settings.set_property_from_value(pspec.name(), pspec.default_value());
+        }
+    }
+    settings.set_gtk_theme_name(Some("Adwaita"));
+    settings.set_gtk_application_prefer_dark_theme(false);
+    settings.set_gtk_icon_theme_name(Some("Adwaita"));
+    settings.set_gtk_font_name(Some(DETERMINISTIC_FONT));
+    settings.set_gtk_xft_dpi(96 * 1024);
+    settings.set_gtk_xft_antialias(1);
+    settings.set_gtk_xft_hinting(0);
+    settings.set_gtk_xft_hintstyle(Some("hintnone"));
+    settings.set_gtk_xft_rgba(Some("none"));
+    settings.set_gtk_enable_animations(false);
+    settings.set_gtk_cursor_blink(false);
+    settings.set_gtk_overlay_scrolling(false);
+
+    DETERMINISTIC.store(true, Ordering::Release);
+    Ok(())
+}
+
+// rustdoc-stripper-ignore-next
+/// Sets up the environment of the process so that GTK ignores the configuration of the user.
+///
+/// This sets `GDK_SCALE` and `GDK_DPI_SCALE` to 1 and `GTK_THEME` to the built-in Adwaita
+/// theme, points `XDG_CONFIG_HOME` and `XDG_CONFIG_DIRS` to a directory that doesn't exist, so
+/// no `settings.ini` and no user CSS in `gtk-3.0/gtk.css` is loaded, selects the in-memory
+/// GSettings backend and unsets `GTK_MODULES`.
+///
+/// The Rust test harness runs tests on several threads, so this can only be used by tests
+/// with a `main` function of their own, declared with `harness = false` in `Cargo.toml`:
+///
+/// ```no_run
+/// fn main() {
+///     // SAFETY: no other threads are running yet.
+///     unsafe { gtk::test::set_deterministic_environment() };
+///     gtk::test::init_deterministic().expect("Failed to initialize GTK");
+///     // run the tests
+/// }
+/// ```
+///
+/// # Safety
+///
+/// No other thread may read or write the environment at the same time, including C code
+/// calling `getenv()`. Call this before starting any threads and before initializing GTK.
+pub unsafe fn set_deterministic_environment() {
+    skip_assert_initialized!();
+    let no_config_dir =
+        std::env::temp_dir().join(format!("gtk-rs-test-no-config-{}", std::process::id()));
+    unsafe {
+        std::env::set_var("GDK_SCALE", "1");
+        std::env::set_var("GDK_DPI_SCALE", "1");
+        std::env::set_var("GTK_THEME", "Adwaita");
+        std::env::set_var("GSETTINGS_BACKEND", "memory");
+        std::env::set_var("XDG_CONFIG_HOME", &no_config_dir);
+        std::env::set_var("XDG_CONFIG_DIRS", &no_config_dir);
+        std::env::remove_var("GTK_MODULES");
+    }
+}
+
+static DETERMINISTIC: AtomicBool = AtomicBool::new(false);
+
+static TEST_THREAD: LazyLock<Mutex<mpsc::Sender<Job>>> = LazyLock::new(|| {
+    let (sender, receiver) = mpsc::channel::<Job>();
+    thread::Builder::new()
+        .name("gtk-test".into())
+        .spawn(move || {
+            let init_result = init_deterministic();
+            for job in receiver {
+                job(init_result.as_ref().map(|_| ()));
+            }
+        })
+        .expect("Failed to spawn the GTK test thread");
+    Mutex::new(sender)
+});
+
+// rustdoc-stripper-ignore-next
+/// Runs `f` on the thread used by all GTK tests of this binary and returns its result.
+///
+/// Panics in `f`, including failed assertions, are propagated to the calling test.
+///
+/// # Panics
+///
+/// If GTK could not be initialized, see [`init_deterministic()`].
+pub fn run<F, R>(f: F) -> R
+where
+    F: FnOnce() -> R + Send + 'static,
+    R: Send + 'static,
+{
+    skip_assert_initialized!();
+    let (sender, receiver) = mpsc::channel();
+    let job: Job = Box::new(move |init_result| {
+        let res = match init_result {
+            Ok(()) => Ok(panic::catch_unwind(AssertUnwindSafe(f))),
+            Err(err) => Err(err.to_string()),
+        };
+        let _ = sender.send(res);
+    });
+    TEST_THREAD
+        .lock()
+        .unwrap_or_else(|err| err.into_inner())
+        .send(job)
+        .expect("The GTK test thread has stopped");
+
+    match receiver.recv().expect("The GTK test thread has stopped") {
+        Ok(Ok(res)) => res,
+        Ok(Err(panic)) => panic::resume_unwind(panic),
+        Err(err) => panic!("Failed to initialize GTK for the test: {err}"),
+    }
+}
+
+// rustdoc-stripper-ignore-next
+/// Dispatches all pending events and sources of the default main context.
+///
+/// Gives up after [`IDLE_TIMEOUT`], so a widget that keeps scheduling work, like a spinner,
+/// doesn't hang the test.
+pub fn run_until_idle() {
+    assert_initialized_main_thread!();
+    let context = glib::MainContext::default();
+    let start = Instant::now();
+    while context.pending() && start.elapsed() < IDLE_TIMEOUT {
+        context.iteration(false);
+    }
+}
+
+// rustdoc-stripper-ignore-next
+/// Iterates the default main context until `condition` returns `true`.
+///
+/// Returns `false` if `condition` still didn't hold after `timeout`.
+pub fn run_until<F: FnMut() -> bool>(timeout: Duration, mut condition: F) -> bool {
+    assert_initialized_main_thread!();
+    let context = glib::MainContext::default();
+    let deadline = Instant::now() + timeout;
+    loop {
+        if condition() {
+            return true;
+        }
+        if Instant::now() >= deadline {
+            return false;
+        }
+        if !context.iteration(false) {
+            thread::sleep(Duration::from_millis(1));
+        }
+    }
+}
+
+// rustdoc-stripper-ignore-next
+/// Iterates the default main context for `duration`.
+pub fn run_for(duration: Duration) {
+    assert_initialized_main_thread!();
+    run_until(duration, || false);
+}
+
+fn event_time() -> u32 {
+    (glib::monotonic_time() / 1000) as u32
+}
+
+// Finds the window the event at `(x, y)`, relative to `widget`, would be delivered to, and
+// the coordinates relative to it. Events put with `gdk::Event::put()` are not hit-tested by
+// the windowing system, so this descends into child windows itself, like the input-only
+// window of a `GtkButton`.
+fn event_window(widget: &Widget, x: f64, y: f64) -> (gdk::Window, f64, f64) {
+    let mut window = widget
+        .window()
+        .expect("The widget has to be realized to receive events");
+    let (mut x, mut y) = if widget.has_window() {
+        (x, y)
+    } else {
+        let allocation = widget.allocation();
+        (x + allocation.x() as f64, y + allocation.y() as f64)
+    };
+
+    while let Some(child) = window.children().into_iter().find(|child| {
+        let (child_x, child_y) = child.position();
+        child.is_visible()
+            && x >= child_x as f64
+            && y >= child_y as f64
+            && x < (child_x + child.width()) as f64
+            && y < (child_y + child.height()) as f64
+    }) {
+        let (child_x, child_y) = child.position();
+        x -= child_x as f64;
+        y -= child_y as f64;
+        window = child;
+    }
+
+    (window, x, y)
+}
+
+fn put_event(event: &gdk::Event) {
+    event.put();
+    run_until_idle();
+}
+
+// rustdoc-stripper-ignore-next
+/// Presses and releases the mouse `button` at `(x, y)`, relative to the allocation of
+/// `widget`.
+///
+/// The events are put on the event queue with [`gdk::Event::put()`] and dispatched before
+/// this returns. `widget` has to be mapped.
+pub fn click_at(widget: &impl IsA<Widget>, x: f64, y: f64, button: u32) {
+    assert_initialized_main_thread!();
+    let widget = widget.as_ref();
+    let (window, x, y) = event_window(widget, x, y);
+    let device = window
+        .display()
+        .default_seat()
+        .and_then(|seat| seat.pointer());
+    let (x_root, y_root) = window.root_coords(x as i32, y as i32);
+
+    // The release event carries the state from while the button was held down
+    let button_mask = match button {
+        1 => gdk::ModifierType::BUTTON1_MASK,
+        2 => gdk::ModifierType::BUTTON2_MASK,
+        3 => gdk::ModifierType::BUTTON3_MASK,
+        4 => gdk::ModifierType::BUTTON4_MASK,
+        5 => gdk::ModifierType::BUTTON5_MASK,
+        _ => gdk::ModifierType::empty(),
+    };
+
+    for (event_type, state) in [
+        (gdk::EventType::ButtonPress, gdk::ModifierType::empty()),
+        (gdk::EventType::ButtonRelease, button_mask),
+    ] {
+        let event = gdk::Event::new(event_type);
+        unsafe {
+            let ptr = mut_override(event.to_glib_none().0);
+            let button_event = &mut (*ptr).button;
+            button_event.window = window.to_glib_full();
+            button_event.send_event = 1;
+            button_event.time = event_time();
+            button_event.x = x;
+            button_event.y = y;
+            button_event.x_root = x_root as f64;
+            button_event.y_root = y_root as f64;
+            button_event.state = state.into_glib();
+            button_event.button = button;
+            gdk::ffi::gdk_event_set_device(ptr, device.to_glib_none().0);
+        }
+        put_event(&event);
+    }
+}
+
+// rustdoc-stripper-ignore-next
+/// Clicks the primary mouse button in the center of `widget`.
+pub fn click(widget: &impl IsA<Widget>) {
+    assert_initialized_main_thread!();
+    let allocation = widget.allocation();
+    click_at(
+        widget,
+        allocation.width() as f64 / 2.0,
+        allocation.height() as f64 / 2.0,
+        gdk::BUTTON_PRIMARY,
+    );
+}
+
+fn send_key_event(
+    widget: &Widget,
+    event_type: gdk::EventType,
+    keyval: gdk::keys::Key,
+    modifiers: gdk::ModifierType,
+) {
+    let toplevel = widget
+        .toplevel()
+        .expect("The widget has to be inside a toplevel to receive key events");
+    let window = toplevel
+        .window()
+        .expect("The toplevel has to be realized to receive key events");
+    let display = window.display();
+    let device = display.default_seat().and_then(|seat| seat.keyboard());
+    let key = gdk::Keymap::for_display(&display)
+        .and_then(|keymap| keymap.entries_for_keyval(*keyval).into_iter().next());
+    let text = keyval.to_unicode().filter(|c| !c.is_control());
+
+    let event = gdk::Event::new(event_type);
+    unsafe {
+        let ptr = mut_override(event.to_glib_none().0);
+        let key_event = &mut (*ptr).key;
+        key_event.window = window.to_glib_full();
+        key_event.send_event = 1;
+        key_event.time = event_time();
+        key_event.state = modifiers.into_glib();
+        key_event.keyval = *keyval;
+        if let Some(c) = text {
+            let text = c.to_string();
+            key_event.length = text.len() as i32;
+            key_event.string = text.to_glib_full();
+        }
+        if let Some(key) = key {
+            key_event.hardware_keycode = key.keycode() as u16;
+            key_event.group = key.group() as u8;
+        }
+        gdk::ffi::gdk_event_set_device(ptr, device.to_glib_none().0);
+    }
+    put_event(&event);
+}
+
+// rustdoc-stripper-ignore-next
+/// Focuses `widget` and presses and releases `keyval` with the given modifiers.
+///
+/// Like with a real keyboard, the events are sent to the toplevel window, which forwards
+/// them to the focused widget.
+///
+/// ```no_run
+/// # let entry = gtk::Entry::new();
+/// gtk::test::press_key(
+///     &entry,
+///     gtk::gdk::keys::constants::Return,
+///     gtk::gdk::ModifierType::empty(),
+/// );
+/// ```
+pub fn press_key(widget: &impl IsA<Widget>, keyval: gdk::keys::Key, modifiers: gdk::ModifierType) {
+    assert_initialized_main_thread!();
+    let widget = widget.as_ref();
+    widget.grab_focus();
+    send_key_event(widget, gdk::EventType::KeyPress, keyval, modifiers);
+    send_key_event(widget, gdk::EventType::KeyRelease, keyval, modifiers);
+}
+
+// rustdoc-stripper-ignore-next
+/// Focuses `widget` and types `text` into it, one key press per character.
+pub fn type_text(widget: &impl IsA<Widget>, text: &str) {
+    assert_initialized_main_thread!();
+    for c in text.chars() {
+        press_key(
+            widget,
+            gdk::keys::Key::from_unicode(c),
+            gdk::ModifierType::empty(),
+        );
+    }
+}
+
+// rustdoc-stripper-ignore-next
+/// Sends a key press to `widget` through the windowing system, using
+/// `gtk_test_widget_send_key()`.
+///
+/// Unlike [`press_key()`] this needs support from the GDK backend, which isn't available on
+/// Broadway. Returns `false` if the key press could not be simulated.
+#[doc(alias = "gtk_test_widget_send_key")]
+pub fn send_key(
+    widget: &impl IsA<Widget>,
+    keyval: gdk::keys::Key,
+    modifiers: gdk::ModifierType,
+) -> bool {
+    assert_initialized_main_thread!();
+    let res = crate::test_widget_send_key(widget, *keyval, modifiers);
+    run_until_idle();
+    res
+}
+
+// rustdoc-stripper-ignore-next
+/// Returns the text shown by `widget`, if it is a `GtkLabel`, `GtkEditable` or `GtkTextView`.
+#[doc(alias = "gtk_test_text_get")]
+pub fn text(widget: &impl IsA<Widget>) -> Option<glib::GString> {
+    assert_initialized_main_thread!();
+    unsafe { from_glib_full(ffi::gtk_test_text_get(widget.as_ref().to_glib_none().0)) }
+}
+
+// rustdoc-stripper-ignore-next
+/// Asserts that `widget` and all of its parents are visible.
+#[track_caller]
+pub fn assert_visible(widget: &impl IsA<Widget>) {
+    assert!(
+        widget.is_visible() && widget.is_mapped(),
+        "{} is not visible",
+        widget.type_()
+    );
+}
+
+// rustdoc-stripper-ignore-next
+/// Asserts that `widget` is not shown.
+#[track_caller]
+pub fn assert_hidden(widget: &impl IsA<Widget>) {
+    assert!(!widget.is_mapped(), "{} is visible", widget.type_());
+}
+
+// rustdoc-stripper-ignore-next
+/// Asserts that `widget` reacts to input, taking the sensitivity of its parents into account.
+#[track_caller]
+pub fn assert_sensitive(widget: &impl IsA<Widget>) {
+    assert!(widget.is_sensitive(), "{} is insensitive", widget.type_());
+}
+
+// rustdoc-stripper-ignore-next
+/// Asserts that `widget` or one of its parents is insensitive.
+#[track_caller]
+pub fn assert_insensitive(widget: &impl IsA<Widget>) {
+    assert!(!widget.is_sensitive(), "{} is sensitive", widget.type_());
+}
+
+// rustdoc-stripper-ignore-next
+/// Asserts that `widget` is the focus widget of its toplevel.
+#[track_caller]
+pub fn assert_focused(widget: &impl IsA<Widget>) {
+    assert!(
+        widget.has_focus(),
+        "{} doesn't have the focus",
+        widget.type_()
+    );
+}
+
+// rustdoc-stripper-ignore-next
+/// Asserts that [`text()`] of `widget` equals `expected`.
+#[track_caller]
+pub fn assert_text(widget: &impl IsA<Widget>, expected: &str) {
+    let text = text(widget);
+    assert_eq!(
+        text.as_deref(),
+        Some(expected),
+        "Unexpected text of {}",
+        widget.type_()
+    );
+}
+
+// rustdoc-stripper-ignore-next
+/// Asserts that the property `property_name` of `widget` equals `expected`.
+///
+/// ```no_run
+/// # let toggle = gtk::ToggleButton::new();
+/// gtk::test::assert_property(&toggle, "active", true);
+/// ```
+#[track_caller]
+pub fn assert_property<V>(widget: &impl IsA<Widget>, property_name: &str, expected: V)
+where
+    V: for<'a> glib::value::FromValue<'a> + glib::value::ValueType + PartialEq + std::fmt::Debug,
+{
+    let value = widget.property::<V>(property_name);
+    assert_eq!(
+        value,
+        expected,
+        "Unexpected value of property '{property_name}' of {}",
+        widget.type_()
+    );
+}
+
+// rustdoc-stripper-ignore-next
+/// The environment variable that makes [`assert_widget_snapshot()`] write the renderings as
+/// the new references instead of comparing them.
+#[cfg(feature = "png")]
+#[cfg_attr(docsrs, doc(cfg(feature = "png")))]
+pub const SNAPSHOT_BLESS_ENV: &str = "GTK_SNAPSHOT_BLESS";
+
+// rustdoc-stripper-ignore-next
+/// Options of [`assert_widget_snapshot()`].
+#[cfg(feature = "png")]
+#[cfg_attr(docsrs, doc(cfg(feature = "png")))]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SnapshotOptions {
+    // rustdoc-stripper-ignore-next
+    /// The width to render the widget at, or its natural width if `None`.
+    pub width: Option<i32>,
+    // rustdoc-stripper-ignore-next
+    /// The height to render the widget at, or its natural height for the width if `None`.
+    pub height: Option<i32>,
+    // rustdoc-stripper-ignore-next
+    /// The scale to render with, which must be the scale factor of the widget, see
+    /// [`render_to_surface()`](crate::prelude::WidgetExtManual::render_to_surface).
+    pub scale: i32,
+    // rustdoc-stripper-ignore-next
+    /// How much each color channel of a pixel may differ from the reference.
+    pub tolerance: u8,
+}
+
+#[cfg(feature = "png")]
+impl Default for SnapshotOptions {
+    fn default() -> Self {
+        Self {
+            width: None,
+            height: None,
+            scale: 1,
+            tolerance: 0,
+        }
+    }
+}
+
+// rustdoc-stripper-ignore-next
+/// Renders `widget` offscreen and asserts that it matches the reference image `name.png` in
+/// `dir`.
+///
+/// On a mismatch the rendering and an image marking the differing pixels in red are written
+/// to `name.actual.png` and `name.diff.png` in `dir`. If [`SNAPSHOT_BLESS_ENV`] is set, the
+/// rendering is written as the new reference instead.
+///
+/// This is usually called through the
+/// [`assert_widget_snapshot!`](crate::assert_widget_snapshot!) macro.
+///
+/// # Panics
+///
+/// If the rendering doesn't match the reference or there is no reference, and on errors while
+/// rendering or accessing the images.
+#[cfg(feature = "png")]
+#[cfg_attr(docsrs, doc(cfg(feature = "png")))]
+#[track_caller]
+pub fn assert_widget_snapshot(
+    widget: &impl IsA<Widget>,
+    name: &str,
+    dir: impl AsRef<std::path::Path>,
+    options: &SnapshotOptions,
+) {
+    assert_initialized_main_thread!();
+    let dir = dir.as_ref();
+    let reference_path = dir.join(format!("{name}.png"));
+    let actual_path = dir.join(format!("{name}.actual.png"));
+    let diff_path = dir.join(format!("{name}.diff.png"));
+
+    let width = options.width.unwrap_or_else(|| widget.preferred_width().1);
+    let height = options
+        .height
+        .unwrap_or_else(|| widget.preferred_height_for_width(width).1);
+    let actual = widget
+        .render_to_surface(width, height, options.scale)
+        .unwrap_or_else(|err| panic!("Failed to render snapshot '{name}': {err}"));
+
+    let write_png = |surface: &cairo::ImageSurface, path: &std::path::Path| {
+        std::fs::create_dir_all(dir)
+            .and_then(|_| std::fs::File::create(path))
+            .map_err(cairo::IoError::from)
+            .and_then(|mut file| surface.write_to_png(&mut file))
+            .unwrap_or_else(|err| panic!("Failed to write {}: {err}", path.display()));
+    };
+    // Leftovers of a previous failure would be confusing once the snapshot matches again
+    let _ = std::fs::remove_file(&actual_path);
+    let _ = std::fs::remove_file(&diff_path);
+
+    if std::env::var_os(SNAPSHOT_BLESS_ENV).is_some() {
+        write_png(&actual, &reference_path);
+        return;
+    }
+
+    let reference = match std::fs::File::open(&reference_path) {
+        Ok(mut file) => cairo::ImageSurface::create_from_png(&mut file)
+            .unwrap_or_else(|err| panic!("Failed to read {}: {err}", reference_path.display())),
+        Err(err) if err.kind() == std::io::ErrorKind::NotFound => {
+            write_png(&actual, &actual_path);
+            panic!(
+                "No reference image {} for snapshot '{name}', the rendering was written to {}. \
+                 Set {SNAPSHOT_BLESS_ENV}=1 to use it as the reference.",
+                reference_path.display(),
+                actual_path.display(),
+            );
+        }
+        Err(err) => panic!("Failed to open {}: {err}", reference_path.display()),
+    };
+
+    if (reference.width(), reference.height()) != (actual.width(), actual.height()) {
+        write_png(&actual, &actual_path);
+        panic!(
+            "Snapshot '{name}' is {}×{} pixels but the reference is {}×{} pixels, see {}",
+            actual.width(),
+            actual.height(),
+            reference.width(),
+            reference.height(),
+            actual_path.display(),
+        );
+    }
+
+    let reference = argb32_pixels(&reference);
+    let pixels = argb32_pixels(&actual);
+    let mut n_different = 0;
+    let diff = pixels
+        .iter()
+        .zip(reference.iter())
+        .map(|(&pixel, &expected)| {
+            let differs = pixel
+                .to_be_bytes()
+                .iter()
+                .zip(expected.to_be_bytes().iter())
+                .any(|(a, b)| a.abs_diff(*b) > options.tolerance);
+            if differs {
+                n_different += 1;
+                0xffff_0000
+            } else {
+                // A faded, opaque gray version of the rendering, for orientation
+                let [_, r, g, b] = pixel.to_be_bytes();
+                let gray = (r as u32 + g as u32 + b as u32) / 3;
+                let faded = 0xc0 + gray / 4;
+                0xff00_0000 | (faded << 16) | (faded << 8) | faded
+            }
+        })
+        .collect::<Vec<u32>>();
+
+    if n_different > 0 {
+        let width = actual.width();
+        let data = diff
+            .iter()
+            .flat_map(|p| p.to_ne_bytes())
+            .collect::<Vec<u8>>();
+        let diff_surface = cairo::ImageSurface::create_for_data(
+            data,
+            cairo::Format::ARgb32,
+            width,
+            actual.height(),
+            width * 4,
+        )
+        .unwrap_or_else(|err| panic!("Failed to create the diff of snapshot '{name}': {err}"));
+        write_png(&actual, &actual_path);
+        write_png(&diff_surface, &diff_path);
+        panic!(
+            "Snapshot '{name}' differs from {} in {n_different} pixels with a tolerance of {}, \
+             see {} and {}. Set {SNAPSHOT_BLESS_ENV}=1 to update the reference.",
+            reference_path.display(),
+            options.tolerance,
+            actual_path.display(),
+            diff_path.display(),
+        );
+    }
+}
+
+// Returns the pixels of `surface` as premultiplied ARGB values, row by row without padding,
+// whatever format the surface has.
+#[cfg(feature = "png")]
+fn argb32_pixels(surface: &cairo::ImageSurface) -> Vec<u32> {
+    let (width, height) = (surface.width(), surface.height());
+    let mut converted = cairo::ImageSurface::create(cairo::Format::ARgb32, width, height)
+        .expect("Failed to create image surface");
+    {
+        let cr = cairo::Context::new(&converted).expect("Failed to create cairo context");
+        cr.set_source_surface(surface, 0.0, 0.0)
+            .expect("Failed to set source surface");
+        cr.set_operator(cairo::Operator::Source);
+        cr.paint().expect("Failed to paint");
+    }
+
+    let stride = converted.stride() as usize;
+    let data = converted.data().expect("Failed to access surface data");
+    data.chunks(stride)
+        .flat_map(|row| {
+            row[..width as usize * 4]
+                .chunks_exact(4)
+                .map(|p| u32::from_ne_bytes([p[0], p[1], p[2], p[3]]))
+        })
+        .collect()
+}
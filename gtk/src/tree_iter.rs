@@ -0,0 +1,36 @@
+// Take a look at the license at the top of the repository in the LICENSE file.
+
+use crate::{TreeIter, ffi};
+use glib::translate::*;
+
+impl TreeIter {
+    // rustdoc-stripper-ignore-next
+    /// Creates an iterator for a custom [`TreeModel`](crate::TreeModel) implementation.
+    ///
+    /// The `stamp` identifies the state of the model the iterator belongs to, so that the
+    /// model can recognize iterators that were invalidated by a change, and `handle`
+    /// identifies the row in the model, for example an index or a key.
+    pub fn from_handle(stamp: i32, handle: usize) -> TreeIter {
+        skip_assert_initialized!();
+        unsafe {
+            TreeIter::unsafe_from(ffi::GtkTreeIter {
+                stamp,
+                user_data: handle as glib::ffi::gpointer,
+                user_data2: std::ptr::null_mut(),
+                user_data3: std::ptr::null_mut(),
+            })
+        }
+    }
+
+    // rustdoc-stripper-ignore-next
+    /// Returns the stamp of the model the iterator was created for.
+    pub fn stamp(&self) -> i32 {
+        unsafe { (*self.to_glib_none().0).stamp }
+    }
+
+    // rustdoc-stripper-ignore-next
+    /// Returns the row handle stored with [`from_handle`](Self::from_handle).
+    pub fn handle(&self) -> usize {
+        unsafe { (*self.to_glib_none().0).user_data as usize }
+    }
+}
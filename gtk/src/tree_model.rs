@@ -0,0 +1,418 @@
+// Take a look at the license at the top of the repository in the LICENSE file.
+
+use std::boxed::Box as Box_;
+use std::cell::Cell;
+use std::fmt;
+use std::iter::FusedIterator;
+use std::mem::transmute;
+use std::rc::Rc;
+use std::slice;
+
+use crate::prelude::*;
+use crate::{TreeColumn, TreeIter, TreeModel, TreePath, TreeRow, ffi};
+use glib::signal::{SignalHandlerId, connect_raw};
+use glib::translate::*;
+use glib::value::FromValue;
+
+mod sealed {
+    pub trait Sealed {}
+    impl<T: glib::object::IsA<crate::TreeModel>> Sealed for T {}
+}
+
+pub trait TreeModelExtManual: IsA<TreeModel> + sealed::Sealed + 'static {
+    // rustdoc-stripper-ignore-next
+    /// Reads the row pointed to by `iter` into a [`TreeRow`].
+    ///
+    /// # Panics
+    ///
+    /// If a column of the model doesn't hold a value of the type of its field in `R`.
+    fn row<R: TreeRow>(&self, iter: &TreeIter) -> R {
+        R::from_model(self.as_ref(), iter)
+    }
+
+    // rustdoc-stripper-ignore-next
+    /// Reads the value of a single typed column of the row pointed to by `iter`.
+    ///
+    /// # Panics
+    ///
+    /// If the column doesn't hold a value of type `T`.
+    fn field<R, T>(&self, iter: &TreeIter, column: TreeColumn<R, T>) -> T
+    where
+        T: for<'a> FromValue<'a> + 'static,
+    {
+        let value = self.as_ref().value(iter, column.into());
+        value.get::<T>().unwrap_or_else(|err| {
+            panic!(
+                "column {} of type {} can't be read: {err}",
+                column.index(),
+                value.type_()
+            )
+        })
+    }
+
+    // rustdoc-stripper-ignore-next
+    /// Returns an iterator over the top-level rows of the model.
+    ///
+    /// The iterator stops early if rows are inserted, deleted or reordered while it is in
+    /// use, instead of following invalidated [`TreeIter`]s.
+    fn rows(&self) -> TreeModelRows {
+        TreeModelRows::new(self.as_ref(), None)
+    }
+
+    // rustdoc-stripper-ignore-next
+    /// Returns an iterator over the children of the row pointed to by `parent`.
+    ///
+    /// The iterator stops early if rows are inserted, deleted or reordered while it is in
+    /// use, instead of following invalidated [`TreeIter`]s.
+    fn children(&self, parent: &TreeIter) -> TreeModelRows {
+        TreeModelRows::new(self.as_ref(), Some(parent))
+    }
+
+    // rustdoc-stripper-ignore-next
+    /// Returns a depth-first iterator over all rows of the model, along with their
+    /// [`TreePath`] and depth.
+    ///
+    /// The iterator stops early if rows are inserted, deleted or reordered while it is in
+    /// use, instead of following invalidated [`TreeIter`]s.
+    ///
+    /// ```no_run
+    /// # use gtk::prelude::*;
+    /// # let store = gtk::TreeStore::new(&[gtk::glib::Type::STRING]);
+    /// for row in store.descendants() {
+    ///     let name = store.value(&row.iter, 0).get::<String>().unwrap();
+    ///     println!("{:indent$}{name} ({})", "", row.path, indent = row.depth * 2);
+    /// }
+    /// ```
+    fn descendants(&self) -> TreeModelDescendants {
+        TreeModelDescendants::new(self.as_ref())
+    }
+
+    // rustdoc-stripper-ignore-next
+    /// Like [`foreach()`](crate::prelude::TreeModelExt::foreach), but reads every row into a
+    /// [`TreeRow`] before passing it to `func`.
+    ///
+    /// Returning `true` from `func` stops the iteration.
+    ///
+    /// # Panics
+    ///
+    /// If a column of the model doesn't hold a value of the type of its field in `R`.
+    #[doc(alias = "gtk_tree_model_foreach")]
+    fn foreach_typed<R: TreeRow, F: FnMut(&TreePath, &TreeIter, R) -> bool>(&self, mut func: F) {
+        self.foreach(|model, path, iter| func(path, iter, R::from_model(model, iter)));
+    }
+
+    // rustdoc-stripper-ignore-next
+    /// Emits [`rows-reordered`](Self::connect_rows_reordered) for the children of `iter`, or
+    /// for the top-level rows if `iter` is `None`.
+    ///
+    /// `new_order[new_position]` is the former position of the row now at `new_position`.
+    ///
+    /// # Panics
+    ///
+    /// If `new_order` doesn't have one element per child of `iter`.
+    #[doc(alias = "gtk_tree_model_rows_reordered")]
+    fn rows_reordered(&self, path: &TreePath, iter: Option<&TreeIter>, new_order: &[i32]) {
+        let n_children = self.iter_n_children(iter);
+        assert_eq!(
+            new_order.len(),
+            n_children as usize,
+            "new order has {} elements but there are {n_children} rows",
+            new_order.len(),
+        );
+        self.rows_reordered_with_length(path, iter, new_order);
+    }
+
+    // rustdoc-stripper-ignore-next
+    /// Connects to the `rows-reordered` signal, emitted when the children of a row, or the
+    /// top-level rows, were reordered.
+    ///
+    /// The closure gets the path and iterator of the parent row, where the iterator is `None`
+    /// for the top-level rows, and the new order: `new_order[new_position]` is the former
+    /// position of the row now at `new_position`.
+    #[doc(alias = "rows-reordered")]
+    fn connect_rows_reordered<F: Fn(&Self, &TreePath, Option<&TreeIter>, &[i32]) + 'static>(
+        &self,
+        f: F,
+    ) -> SignalHandlerId {
+        unsafe extern "C" fn rows_reordered_trampoline<
+            P: IsA<TreeModel>,
+            F: Fn(&P, &TreePath, Option<&TreeIter>, &[i32]) + 'static,
+        >(
+            this: *mut ffi::GtkTreeModel,
+            path: *mut ffi::GtkTreePath,
+            iter: *mut ffi::GtkTreeIter,
+            new_order: glib::ffi::gpointer,
+            f: glib::ffi::gpointer,
+        ) {
+            unsafe {
+                let f: &F = &*(f as *const F);
+                let n_children = ffi::gtk_tree_model_iter_n_children(this, iter);
+                let new_order = if new_order.is_null() || n_children <= 0 {
+                    &[][..]
+                } else {
+                    slice::from_raw_parts(new_order as *const i32, n_children as usize)
+                };
+                let iter: Option<Borrowed<TreeIter>> =
+                    (!iter.is_null()).then(|| from_glib_borrow(iter));
+                f(
+                    TreeModel::from_glib_borrow(this).unsafe_cast_ref(),
+                    &from_glib_borrow(path),
+                    iter.as_deref(),
+                    new_order,
+                )
+            }
+        }
+        unsafe {
+            let f: Box_<F> = Box_::new(f);
+            connect_raw(
+                self.as_ptr() as *mut _,
+                c"rows-reordered".as_ptr(),
+                Some(transmute::<*const (), unsafe extern "C" fn()>(
+                    rows_reordered_trampoline::<Self, F> as *const (),
+                )),
+                Box_::into_raw(f),
+            )
+        }
+    }
+}
+
+impl<O: IsA<TreeModel>> TreeModelExtManual for O {}
+
+// Tracks whether the rows of a model changed since the guard was created, so that iterators
+// never advance from a `TreeIter` that was invalidated in the meantime.
+struct MutationGuard {
+    model: TreeModel,
+    stamp: Option<i32>,
+    mutated: Rc<Cell<bool>>,
+    handlers: Vec<SignalHandlerId>,
+}
+
+impl MutationGuard {
+    fn new(model: &TreeModel) -> Self {
+        let mutated = Rc::new(Cell::new(false));
+        let handlers = ["row-inserted", "row-deleted", "rows-reordered"]
+            .into_iter()
+            .map(|signal| {
+                let mutated = mutated.clone();
+                model.connect_local(signal, false, move |_| {
+                    mutated.set(true);
+                    None
+                })
+            })
+            .collect();
+        Self {
+            model: model.clone(),
+            stamp: model.iter_first().map(|iter| iter.stamp()),
+            mutated,
+            handlers,
+        }
+    }
+
+    fn is_valid(&self) -> bool {
+        !self.mutated.get() && self.model.iter_first().map(|iter| iter.stamp()) == self.stamp
+    }
+}
+
+impl Drop for MutationGuard {
+    fn drop(&mut self) {
+        for handler in self.handlers.drain(..) {
+            self.model.disconnect(handler);
+        }
+    }
+}
+
+// rustdoc-stripper-ignore-next
+/// An iterator over sibling rows of a [`TreeModel`], as returned by
+/// [`TreeModelExtManual::rows()`](crate::prelude::TreeModelExtManual::rows) and
+/// [`TreeModelExtManual::children()`](crate::prelude::TreeModelExtManual::children).
+pub struct TreeModelRows {
+    guard: MutationGuard,
+    next: Option<TreeIter>,
+}
+
+impl TreeModelRows {
+    fn new(model: &TreeModel, parent: Option<&TreeIter>) -> Self {
+        Self {
+            guard: MutationGuard::new(model),
+            next: model.iter_children(parent),
+        }
+    }
+}
+
+impl Iterator for TreeModelRows {
+    type Item = TreeIter;
+
+    fn next(&mut self) -> Option<TreeIter> {
+        if !self.guard.is_valid() {
+            self.next = None;
+        }
+        let iter = self.next.take()?;
+        let next = iter.clone();
+        if self.guard.model.iter_next(&next) {
+            self.next = Some(next);
+        }
+        Some(iter)
+    }
+}
+
+impl FusedIterator for TreeModelRows {}
+
+impl fmt::Debug for TreeModelRows {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("TreeModelRows")
+            .field("model", &self.guard.model)
+            .field("next", &self.next)
+            .finish()
+    }
+}
+
+// rustdoc-stripper-ignore-next
+/// A row yielded by [`TreeModelDescendants`].
+#[derive(Debug, Clone)]
+pub struct TreeModelDescendant {
+    pub iter: TreeIter,
+    pub path: TreePath,
+    // rustdoc-stripper-ignore-next
+    /// The depth of the row, `0` for top-level rows.
+    pub depth: usize,
+}
+
+// rustdoc-stripper-ignore-next
+/// A depth-first iterator over all rows of a [`TreeModel`], as returned by
+/// [`TreeModelExtManual::descendants()`](crate::prelude::TreeModelExtManual::descendants).
+pub struct TreeModelDescendants {
+    guard: MutationGuard,
+    next: Option<(TreeIter, TreePath)>,
+}
+
+impl TreeModelDescendants {
+    fn new(model: &TreeModel) -> Self {
+        Self {
+            guard: MutationGuard::new(model),
+            next: model.iter_first().map(|iter| (iter, TreePath::new_first())),
+        }
+    }
+
+    // Returns the row following `iter` in depth-first order
+    fn following(&self, iter: &TreeIter, path: &TreePath) -> Option<(TreeIter, TreePath)> {
+        let model = &self.guard.model;
+        let mut path = path.clone();
+        if let Some(child) = model.iter_children(Some(iter)) {
+            path.down();
+            return Some((child, path));
+        }
+
+        let mut iter = iter.clone();
+        loop {
+            let next = iter.clone();
+            if model.iter_next(&next) {
+                path.next();
+                return Some((next, path));
+            }
+            iter = model.iter_parent(&iter)?;
+            path.up();
+        }
+    }
+}
+
+impl Iterator for TreeModelDescendants {
+    type Item = TreeModelDescendant;
+
+    fn next(&mut self) -> Option<TreeModelDescendant> {
+        if !self.guard.is_valid() {
+            self.next = None;
+        }
+        let (iter, path) = self.next.take()?;
+        self.next = self.following(&iter, &path);
+        Some(TreeModelDescendant {
+            depth: path.depth() as usize - 1,
+            iter,
+            path,
+        })
+    }
+}
+
+impl FusedIterator for TreeModelDescendants {}
+
+impl fmt::Debug for TreeModelDescendants {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("TreeModelDescendants")
+            .field("model", &self.guard.model)
+            .field("next", &self.next)
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{TEST_THREAD_WORKER, TreeStore};
+
+    fn names(store: &TreeStore, rows: impl Iterator<Item = TreeIter>) -> Vec<String> {
+        rows.map(|iter| store.value(&iter, 0).get::<String>().unwrap())
+            .collect()
+    }
+
+    #[test]
+    fn iterate_rows() {
+        TEST_THREAD_WORKER
+            .push(|| {
+                let store = TreeStore::new(&[glib::Type::STRING]);
+                let a = store.insert_with_values(None, None, &[(0, &"a")]);
+                store.insert_with_values(Some(&a), None, &[(0, &"a1")]);
+                let a2 = store.insert_with_values(Some(&a), None, &[(0, &"a2")]);
+                store.insert_with_values(Some(&a2), None, &[(0, &"a2i")]);
+                store.insert_with_values(None, None, &[(0, &"b")]);
+
+                assert_eq!(names(&store, store.rows()), ["a", "b"]);
+                assert_eq!(names(&store, store.children(&a)), ["a1", "a2"]);
+
+                let descendants = store
+                    .descendants()
+                    .map(|row| (row.path.to_str().to_string(), row.depth))
+                    .collect::<Vec<_>>();
+                assert_eq!(
+                    descendants,
+                    [
+                        ("0".to_owned(), 0),
+                        ("0:0".to_owned(), 1),
+                        ("0:1".to_owned(), 1),
+                        ("0:1:0".to_owned(), 2),
+                        ("1".to_owned(), 0),
+                    ]
+                );
+
+                let mut rows = store.rows();
+                let first = rows.next().unwrap();
+                store.remove(&first);
+                assert!(rows.next().is_none());
+            })
+            .expect("Failed to schedule a test call");
+        while TEST_THREAD_WORKER.unprocessed() > 0 {}
+    }
+
+    #[test]
+    fn rows_reordered() {
+        TEST_THREAD_WORKER
+            .push(|| {
+                let store = crate::ListStore::new(&[glib::Type::STRING]);
+                for name in ["a", "b", "c"] {
+                    store.insert_with_values(None, &[(0, &name)]);
+                }
+
+                let orders = Rc::new(std::cell::RefCell::new(Vec::new()));
+                let orders_clone = orders.clone();
+                store.connect_rows_reordered(move |_, path, iter, new_order| {
+                    assert_eq!(path.depth(), 0);
+                    assert!(iter.is_none());
+                    orders_clone.borrow_mut().push(new_order.to_vec());
+                });
+
+                store.reorder(&[2, 0, 1]);
+                store.rows_reordered(&TreePath::new(), None, &[0, 1, 2]);
+                assert_eq!(*orders.borrow(), [vec![2, 0, 1], vec![0, 1, 2]]);
+            })
+            .expect("Failed to schedule a test call");
+        while TEST_THREAD_WORKER.unprocessed() > 0 {}
+    }
+}
@@ -2,8 +2,11 @@
 
 use glib::object::{Cast, IsA};
 use glib::translate::*;
+use glib::{Type, Value};
+use libc::c_int;
+use std::boxed::Box as Box_;
 
-use crate::{TreeModel, TreeModelFilter, TreePath, ffi};
+use crate::{TreeIter, TreeModel, TreeModelFilter, TreePath, ffi};
 
 impl TreeModelFilter {
     #[doc(alias = "gtk_tree_model_filter_new")]
@@ -18,3 +21,148 @@ impl TreeModelFilter {
         }
     }
 }
+
+mod sealed {
+    pub trait Sealed {}
+    impl<T: glib::object::IsA<crate::TreeModelFilter>> Sealed for T {}
+}
+
+pub trait TreeModelFilterExtManual: IsA<TreeModelFilter> + sealed::Sealed + 'static {
+    // rustdoc-stripper-ignore-next
+    /// Replaces the columns of the filter with `types`, and computes the value of every
+    /// cell with `func`.
+    ///
+    /// `func` is called with the filter itself, an iterator pointing to a row of the filter
+    /// and the column whose value is requested. Use
+    /// [`convert_iter_to_child_iter()`](crate::prelude::TreeModelFilterExt::convert_iter_to_child_iter)
+    /// to read the values of the child model it is derived from.
+    ///
+    /// This can only be called once, before the filter is used. If `func` returns a value that
+    /// can't be transformed to the type of the column, a critical is logged and the cell keeps
+    /// the default value of its type.
+    ///
+    /// ```no_run
+    /// # use gtk::prelude::*;
+    /// # use gtk::glib;
+    /// let store = gtk::ListStore::new(&[glib::Type::STRING, glib::Type::U64]);
+    /// let filter = gtk::TreeModelFilter::new(&store, None);
+    /// filter.set_modify_func(&[glib::Type::STRING, glib::Type::STRING], |model, iter, column| {
+    ///     let filter = model.downcast_ref::<gtk::TreeModelFilter>().unwrap();
+    ///     let child_iter = filter.convert_iter_to_child_iter(iter);
+    ///     let child = filter.model().unwrap();
+    ///     match column {
+    ///         0 => child.value(&child_iter, 0),
+    ///         _ => {
+    ///             let size = child.value(&child_iter, 1).get::<u64>().unwrap();
+    ///             glib::format_size(size).to_value()
+    ///         }
+    ///     }
+    /// });
+    /// ```
+    #[doc(alias = "gtk_tree_model_filter_set_modify_func")]
+    fn set_modify_func<P: Fn(&TreeModel, &TreeIter, i32) -> Value + 'static>(
+        &self,
+        types: &[Type],
+        func: P,
+    ) {
+        unsafe extern "C" fn func_func<P: Fn(&TreeModel, &TreeIter, i32) -> Value + 'static>(
+            model: *mut ffi::GtkTreeModel,
+            iter: *mut ffi::GtkTreeIter,
+            value: *mut glib::gobject_ffi::GValue,
+            column: c_int,
+            data: glib::ffi::gpointer,
+        ) {
+            unsafe {
+                let model = from_glib_borrow(model);
+                let iter = from_glib_borrow(iter);
+                let callback = &*(data as *mut P);
+                let result = (*callback)(&model, &iter, column);
+
+                // `value` is already initialized to the type of the column
+                let type_: Type = from_glib((*value).g_type);
+                let result = if result.type_() == type_ {
+                    result
+                } else {
+                    match result.transform_with_type(type_) {
+                        Ok(result) => result,
+                        Err(_) => {
+                            glib::g_critical!(
+                                "Gtk",
+                                "Column {} is of type {} but the modify function returned a value of type {}",
+                                column,
+                                type_,
+                                result.type_()
+                            );
+                            return;
+                        }
+                    }
+                };
+                glib::gobject_ffi::g_value_copy(result.to_glib_none().0, value);
+            }
+        }
+        unsafe extern "C" fn destroy_func<P: Fn(&TreeModel, &TreeIter, i32) -> Value + 'static>(
+            data: glib::ffi::gpointer,
+        ) {
+            unsafe {
+                let _callback = Box_::from_raw(data as *mut P);
+            }
+        }
+
+        let mut types = types.iter().map(|t| t.into_glib()).collect::<Vec<_>>();
+        let func_data: Box_<P> = Box_::new(func);
+        unsafe {
+            ffi::gtk_tree_model_filter_set_modify_func(
+                self.as_ref().to_glib_none().0,
+                types.len() as c_int,
+                types.as_mut_ptr(),
+                Some(func_func::<P> as _),
+                Box_::into_raw(func_data) as *mut _,
+                Some(destroy_func::<P> as _),
+            );
+        }
+    }
+}
+
+impl<O: IsA<TreeModelFilter>> TreeModelFilterExtManual for O {}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::prelude::*;
+    use crate::{ListStore, TEST_THREAD_WORKER};
+
+    #[test]
+    fn modify_func() {
+        TEST_THREAD_WORKER
+            .push(|| {
+                let store = ListStore::new(&[Type::U32]);
+                store.insert_with_values(None, &[(0, &3u32)]);
+                store.insert_with_values(None, &[(0, &5u32)]);
+
+                let filter = TreeModelFilter::new(&store, None);
+                filter.set_modify_func(
+                    &[Type::U32, Type::STRING, Type::I32],
+                    |model, iter, column| {
+                        let filter = model.downcast_ref::<TreeModelFilter>().unwrap();
+                        let child_iter = filter.convert_iter_to_child_iter(iter);
+                        let n = filter.model().unwrap().get::<u32>(&child_iter, 0);
+                        match column {
+                            0 => (n * 2).to_value(),
+                            1 => format!("#{n}").to_value(),
+                            _ => "not a number".to_value(),
+                        }
+                    },
+                );
+                let iter = filter.iter_first().unwrap();
+                assert_eq!(filter.get::<u32>(&iter, 0), 6);
+                assert_eq!(filter.get::<String>(&iter, 1), "#3");
+                assert!(filter.iter_next(&iter));
+                assert_eq!(filter.get::<u32>(&iter, 0), 10);
+                assert_eq!(filter.get::<String>(&iter, 1), "#5");
+                // A value of the wrong type leaves the cell at its default
+                assert_eq!(filter.get::<i32>(&iter, 2), 0);
+            })
+            .expect("Failed to schedule a test call");
+        while TEST_THREAD_WORKER.unprocessed() > 0 {}
+    }
+}
@@ -0,0 +1,105 @@
+// Take a look at the license at the top of the repository in the LICENSE file.
+
+use std::fmt;
+use std::marker::PhantomData;
+
+use glib::value::ToValue;
+
+use crate::{TreeIter, TreeModel};
+
+// rustdoc-stripper-ignore-next
+/// A Rust type describing the columns of a [`ListStore`](crate::ListStore) or
+/// [`TreeStore`](crate::TreeStore), with one field per column.
+///
+/// This is usually implemented with the [`TreeRow`](derive@crate::TreeRow) derive macro,
+/// which also generates a typed [`TreeColumn`] constant for every field.
+pub trait TreeRow: Sized + 'static {
+    // rustdoc-stripper-ignore-next
+    /// The types of the columns, in order, as passed to
+    /// [`ListStore::new()`](crate::ListStore::new) or [`TreeStore::new()`](crate::TreeStore::new).
+    fn column_types() -> Vec<glib::Type>;
+
+    // rustdoc-stripper-ignore-next
+    /// Converts the fields to the values of the columns, in order.
+    fn to_values(&self) -> Vec<glib::Value>;
+
+    // rustdoc-stripper-ignore-next
+    /// Reads the row pointed to by `iter` from `model`.
+    ///
+    /// # Panics
+    ///
+    /// If a column of `model` doesn't hold a value of the type of its field.
+    fn from_model(model: &TreeModel, iter: &TreeIter) -> Self;
+}
+
+// rustdoc-stripper-ignore-next
+/// The index of a column of the row type `R`, holding values of type `T`.
+///
+/// It converts to the `i32` column indices used by
+/// [`TreeViewColumn::with_attributes()`](crate::TreeViewColumn::with_attributes) and
+/// [`TreeModelExt::value()`](crate::prelude::TreeModelExt::value):
+///
+/// ```no_run
+/// # use gtk::prelude::*;
+/// #[derive(gtk::TreeRow)]
+/// struct Contact {
+///     name: String,
+///     age: u32,
+/// }
+///
+/// let cell = gtk::CellRendererText::new();
+/// let column = gtk::TreeViewColumn::with_attributes("Name", &cell, &[("text", Contact::NAME.into())]);
+/// ```
+pub struct TreeColumn<R, T> {
+    index: u32,
+    phantom: PhantomData<fn() -> (R, T)>,
+}
+
+impl<R, T> TreeColumn<R, T> {
+    pub const fn new(index: u32) -> Self {
+        Self {
+            index,
+            phantom: PhantomData,
+        }
+    }
+
+    pub const fn index(self) -> u32 {
+        self.index
+    }
+}
+
+impl<R, T> Clone for TreeColumn<R, T> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<R, T> Copy for TreeColumn<R, T> {}
+
+impl<R, T> fmt::Debug for TreeColumn<R, T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_tuple("TreeColumn").field(&self.index).finish()
+    }
+}
+
+impl<R, T> From<TreeColumn<R, T>> for u32 {
+    fn from(column: TreeColumn<R, T>) -> Self {
+        column.index
+    }
+}
+
+impl<R, T> From<TreeColumn<R, T>> for i32 {
+    fn from(column: TreeColumn<R, T>) -> Self {
+        column.index as i32
+    }
+}
+
+// Pairs the values of a row with their column indices, as taken by the `set()` and
+// `insert_with_values()` methods of the stores.
+pub(crate) fn row_columns_and_values(values: &[glib::Value]) -> Vec<(u32, &dyn ToValue)> {
+    values
+        .iter()
+        .enumerate()
+        .map(|(column, value)| (column as u32, value as &dyn ToValue))
+        .collect()
+}
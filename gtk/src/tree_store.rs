@@ -2,6 +2,8 @@
 
 use crate::TreeModel;
 use crate::TreeStore;
+use crate::tree_row::row_columns_and_values;
+use crate::{TreeColumn, TreeRow};
 use crate::{TreeIter, ffi};
 use glib::object::{Cast, IsA};
 use glib::translate::*;
@@ -205,6 +207,32 @@ pub trait TreeStoreExtManual: IsA<TreeStore> + sealed::Sealed + 'static {
             );
         }
     }
+
+    // rustdoc-stripper-ignore-next
+    /// Inserts a new row holding the fields of `row` as a child of `parent`, at `position` or
+    /// at the end if `position` is `None`.
+    fn insert_row<R: TreeRow>(
+        &self,
+        parent: Option<&TreeIter>,
+        position: Option<u32>,
+        row: &R,
+    ) -> TreeIter {
+        let values = row.to_values();
+        self.insert_with_values(parent, position, &row_columns_and_values(&values))
+    }
+
+    // rustdoc-stripper-ignore-next
+    /// Replaces all columns of the row pointed to by `iter` with the fields of `row`.
+    fn set_row<R: TreeRow>(&self, iter: &TreeIter, row: &R) {
+        let values = row.to_values();
+        self.set(iter, &row_columns_and_values(&values));
+    }
+
+    // rustdoc-stripper-ignore-next
+    /// Sets a single typed column of the row pointed to by `iter`.
+    fn set_field<R, T: ToValue>(&self, iter: &TreeIter, column: TreeColumn<R, T>, value: &T) {
+        self.set_value(iter, column.into(), &value.to_value());
+    }
 }
 
 impl<O: IsA<TreeStore>> TreeStoreExtManual for O {}
@@ -0,0 +1,382 @@
+// Take a look at the license at the top of the repository in the LICENSE file.
+
+use gdk::{DragAction, ModifierType};
+use glib::translate::*;
+use std::ptr;
+
+use crate::prelude::*;
+use crate::{
+    CellEditBinding, CellRenderer, CellRendererPixbuf, CellRendererProgress, CellRendererText,
+    CellRendererToggle, TargetEntry, TreeIter, TreeModel, TreePath, TreeView, TreeViewColumn, ffi,
+};
+
+mod sealed {
+    pub trait Sealed {}
+    impl<T: glib::object::IsA<crate::TreeView>> Sealed for T {}
+}
+
+pub trait TreeViewExtManual: IsA<TreeView> + sealed::Sealed + 'static {
+    // rustdoc-stripper-ignore-next
+    /// Turns the view into a drag source for automatic DnD of its rows.
+    ///
+    /// Rows can be moved within the view, and to other views, with the
+    /// [`TargetEntry::tree_model_row()`] target. The model has to implement
+    /// [`TreeDragSource`](crate::TreeDragSource), like [`ListStore`](crate::ListStore) and
+    /// [`TreeStore`](crate::TreeStore) do, or custom models through
+    /// [`TreeDragSourceImpl`](crate::subclass::tree_drag_source::TreeDragSourceImpl).
+    #[doc(alias = "gtk_tree_view_enable_model_drag_source")]
+    fn enable_model_drag_source(
+        &self,
+        start_button_mask: ModifierType,
+        targets: &[TargetEntry],
+        actions: DragAction,
+    ) {
+        let stashes: Vec<_> = targets.iter().map(|e| e.to_glib_none()).collect();
+        let t: Vec<_> = stashes.iter().map(|stash| unsafe { *stash.0 }).collect();
+        let t_ptr: *const ffi::GtkTargetEntry = if !t.is_empty() {
+            t.as_ptr()
+        } else {
+            ptr::null()
+        };
+        unsafe {
+            ffi::gtk_tree_view_enable_model_drag_source(
+                self.as_ref().to_glib_none().0,
+                start_button_mask.into_glib(),
+                t_ptr,
+                t.len() as i32,
+                actions.into_glib(),
+            )
+        };
+    }
+
+    // rustdoc-stripper-ignore-next
+    /// Turns the view into a drop destination for automatic DnD of rows.
+    ///
+    /// The model has to implement [`TreeDragDest`](crate::TreeDragDest), like
+    /// [`ListStore`](crate::ListStore) and [`TreeStore`](crate::TreeStore) do, or custom
+    /// models through
+    /// [`TreeDragDestImpl`](crate::subclass::tree_drag_dest::TreeDragDestImpl).
+    #[doc(alias = "gtk_tree_view_enable_model_drag_dest")]
+    fn enable_model_drag_dest(&self, targets: &[TargetEntry], actions: DragAction) {
+        let stashes: Vec<_> = targets.iter().map(|e| e.to_glib_none()).collect();
+        let t: Vec<_> = stashes.iter().map(|stash| unsafe { *stash.0 }).collect();
+        let t_ptr: *const ffi::GtkTargetEntry = if !t.is_empty() {
+            t.as_ptr()
+        } else {
+            ptr::null()
+        };
+        unsafe {
+            ffi::gtk_tree_view_enable_model_drag_dest(
+                self.as_ref().to_glib_none().0,
+                t_ptr,
+                t.len() as i32,
+                actions.into_glib(),
+            )
+        };
+    }
+
+    // rustdoc-stripper-ignore-next
+    /// Returns a builder appending columns to the view.
+    ///
+    /// Each column is started by one of the methods adding a cell renderer, like
+    /// [`text()`](TreeViewColumnsBuilder::text), and the methods following it configure that
+    /// column. The columns are appended when [`build()`](TreeViewColumnsBuilder::build) is
+    /// called.
+    ///
+    /// ```no_run
+    /// # use gtk::prelude::*;
+    /// # use gtk::glib;
+    /// const COL_ICON: i32 = 0;
+    /// const COL_NAME: i32 = 1;
+    /// const COL_DONE: i32 = 2;
+    ///
+    /// let store = gtk::ListStore::new(&[
+    ///     gtk::gdk_pixbuf::Pixbuf::static_type(),
+    ///     glib::Type::STRING,
+    ///     glib::Type::BOOL,
+    /// ]);
+    /// let tree_view = gtk::TreeView::with_model(&store);
+    /// tree_view
+    ///     .column_builder()
+    ///     .text("Name", COL_NAME)
+    ///     .sortable()
+    ///     .resizable()
+    ///     .pixbuf("Icon", COL_ICON)
+    ///     .toggle("Done", COL_DONE, move |path| {
+    ///         let iter = store.iter(&path).unwrap();
+    ///         let done = store.value(&iter, COL_DONE).get::<bool>().unwrap();
+    ///         store.set_value(&iter, COL_DONE as u32, &(!done).to_value());
+    ///     })
+    ///     .build();
+    /// ```
+    fn column_builder(&self) -> TreeViewColumnsBuilder {
+        TreeViewColumnsBuilder::new(self.as_ref())
+    }
+}
+
+impl<O: IsA<TreeView>> TreeViewExtManual for O {}
+
+struct ColumnEntry {
+    column: TreeViewColumn,
+    renderer: CellRenderer,
+    model_column: Option<i32>,
+}
+
+// rustdoc-stripper-ignore-next
+/// A builder for the columns of a [`TreeView`], as returned by
+/// [`TreeViewExtManual::column_builder()`].
+///
+/// The model columns can be given as `i32` indices or as typed
+/// [`TreeColumn`](crate::TreeColumn)s.
+#[must_use = "The columns are only appended to the view by `build()`"]
+pub struct TreeViewColumnsBuilder {
+    tree_view: TreeView,
+    columns: Vec<ColumnEntry>,
+}
+
+impl TreeViewColumnsBuilder {
+    fn new(tree_view: &TreeView) -> Self {
+        Self {
+            tree_view: tree_view.clone(),
+            columns: Vec::new(),
+        }
+    }
+
+    // rustdoc-stripper-ignore-next
+    /// Starts a column showing `renderer`, with each of its properties in `attributes` bound
+    /// to a model column.
+    ///
+    /// The first attribute is the model column used by [`sortable()`](Self::sortable).
+    pub fn custom(
+        mut self,
+        title: &str,
+        renderer: &impl IsA<CellRenderer>,
+        attributes: &[(&str, i32)],
+    ) -> Self {
+        let column = TreeViewColumn::with_attributes(title, renderer, attributes);
+        self.columns.push(ColumnEntry {
+            column,
+            renderer: renderer.clone().upcast(),
+            model_column: attributes.first().map(|(_, column)| *column),
+        });
+        self
+    }
+
+    // rustdoc-stripper-ignore-next
+    /// Starts a column showing the string in `column` as text.
+    pub fn text(self, title: &str, column: impl Into<i32>) -> Self {
+        self.custom(title, &CellRendererText::new(), &[("text", column.into())])
+    }
+
+    // rustdoc-stripper-ignore-next
+    /// Starts a column showing the [`Pixbuf`](gdk_pixbuf::Pixbuf) in `column`.
+    pub fn pixbuf(self, title: &str, column: impl Into<i32>) -> Self {
+        self.custom(
+            title,
+            &CellRendererPixbuf::new(),
+            &[("pixbuf", column.into())],
+        )
+    }
+
+    // rustdoc-stripper-ignore-next
+    /// Starts a column showing the percentage in `column` as a progress bar.
+    pub fn progress(self, title: &str, column: impl Into<i32>) -> Self {
+        self.custom(
+            title,
+            &CellRendererProgress::new(),
+            &[("value", column.into())],
+        )
+    }
+
+    // rustdoc-stripper-ignore-next
+    /// Starts a column showing the boolean in `column` as a check box.
+    ///
+    /// `on_toggled` is called with the path of the row whose check box was clicked. It is
+    /// responsible for updating the model.
+    pub fn toggle<F: Fn(TreePath) + 'static>(
+        self,
+        title: &str,
+        column: impl Into<i32>,
+        on_toggled: F,
+    ) -> Self {
+        let renderer = CellRendererToggle::new();
+        renderer.connect_toggled(move |_, path| on_toggled(path));
+        self.custom(title, &renderer, &[("active", column.into())])
+    }
+
+    fn current(&self) -> &ColumnEntry {
+        self.columns
+            .last()
+            .expect("no column was started, add one with `text()`, `pixbuf()`, `toggle()`, ...")
+    }
+
+    // rustdoc-stripper-ignore-next
+    /// Makes the text of the current column editable.
+    ///
+    /// `on_edited` is called with the path of the edited row and the new text. It is
+    /// responsible for updating the model.
+    ///
+    /// # Panics
+    ///
+    /// If the current column doesn't show a [`CellRendererText`] or one of its subclasses.
+    pub fn editable<F: Fn(TreePath, &str) + 'static>(self, on_edited: F) -> Self {
+        let renderer = self
+            .current()
+            .renderer
+            .downcast_ref::<CellRendererText>()
+            .expect("only text columns can be made editable");
+        renderer.set_editable(true);
+        renderer.connect_edited(move |_, path, text| on_edited(path, text));
+        self
+    }
+
+    // rustdoc-stripper-ignore-next
+    /// Makes the renderer of the current column editable and writes its edits to the model
+    /// with `binding`.
+    ///
+    /// # Panics
+    ///
+    /// If the current column doesn't show a [`CellRendererText`] or a [`CellRendererToggle`].
+    pub fn bind_edits<T: ToValue + 'static>(self, binding: CellEditBinding<T>) -> Self {
+        binding.bind(&self.current().renderer);
+        self
+    }
+
+    // rustdoc-stripper-ignore-next
+    /// Sorts the view by the model column of the current column when its header is clicked.
+    ///
+    /// # Panics
+    ///
+    /// If the current column was started with [`custom()`](Self::custom) without any
+    /// attributes, so there is no model column to sort by. Use
+    /// [`TreeViewColumnExt::set_sort_column_id()`](crate::prelude::TreeViewColumnExt::set_sort_column_id)
+    /// through [`configure()`](Self::configure) instead.
+    pub fn sortable(self) -> Self {
+        let entry = self.current();
+        let model_column = entry
+            .model_column
+            .expect("the current column has no attributes, so there is no model column to sort by");
+        entry.column.set_sort_column_id(model_column);
+        self
+    }
+
+    // rustdoc-stripper-ignore-next
+    /// Lets the user resize the current column by dragging the edge of its header.
+    pub fn resizable(self) -> Self {
+        self.current().column.set_resizable(true);
+        self
+    }
+
+    // rustdoc-stripper-ignore-next
+    /// Lets the user move the current column by dragging its header.
+    pub fn reorderable(self) -> Self {
+        self.current().column.set_reorderable(true);
+        self
+    }
+
+    // rustdoc-stripper-ignore-next
+    /// Gives the current column a share of the extra width of the view.
+    pub fn expand(self) -> Self {
+        self.current().column.set_expand(true);
+        self
+    }
+
+    // rustdoc-stripper-ignore-next
+    /// Keeps the current column at least `min_width` pixels wide.
+    pub fn min_width(self, min_width: i32) -> Self {
+        self.current().column.set_min_width(min_width);
+        self
+    }
+
+    // rustdoc-stripper-ignore-next
+    /// Gives the current column a fixed width, for
+    /// [`fixed_height_mode`](crate::prelude::TreeViewExt::set_fixed_height_mode).
+    pub fn fixed_width(self, fixed_width: i32) -> Self {
+        let column = &self.current().column;
+        column.set_sizing(crate::TreeViewColumnSizing::Fixed);
+        column.set_fixed_width(fixed_width);
+        self
+    }
+
+    // rustdoc-stripper-ignore-next
+    /// Calls `func` to set the properties of the renderer of the current column for each row,
+    /// after the attributes are applied.
+    pub fn cell_data_func<F: Fn(&CellRenderer, &TreeModel, &TreeIter) + 'static>(
+        self,
+        func: F,
+    ) -> Self {
+        let entry = self.current();
+        entry.column.set_cell_data_func(
+            &entry.renderer,
+            Some(Box::new(move |_, renderer, model, iter| {
+                func(renderer, model, iter)
+            })),
+        );
+        self
+    }
+
+    // rustdoc-stripper-ignore-next
+    /// Calls `func` with the current column, to configure it beyond what the builder offers.
+    pub fn configure<F: FnOnce(&TreeViewColumn, &CellRenderer)>(self, func: F) -> Self {
+        let entry = self.current();
+        func(&entry.column, &entry.renderer);
+        self
+    }
+
+    // rustdoc-stripper-ignore-next
+    /// Appends the columns to the view and returns them.
+    pub fn build(self) -> Vec<TreeViewColumn> {
+        self.columns
+            .into_iter()
+            .map(|entry| {
+                self.tree_view.append_column(&entry.column);
+                entry.column
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{ListStore, TEST_THREAD_WORKER};
+
+    #[test]
+    fn column_builder() {
+        TEST_THREAD_WORKER
+            .push(|| {
+                let store = ListStore::new(&[glib::Type::STRING, glib::Type::BOOL]);
+                let tree_view = TreeView::with_model(&store);
+                let columns = tree_view
+                    .column_builder()
+                    .text("Name", 0)
+                    .sortable()
+                    .resizable()
+                    .min_width(50)
+                    .toggle("Done", 1, |_| {})
+                    .reorderable()
+                    .expand()
+                    .fixed_width(30)
+                    .build();
+
+                assert_eq!(columns, tree_view.columns());
+                let [name, done] = &columns[..] else {
+                    panic!("expected two columns, got {}", columns.len());
+                };
+                assert_eq!(name.title().as_deref(), Some("Name"));
+                assert_eq!(name.sort_column_id(), 0);
+                assert!(name.is_resizable());
+                assert_eq!(name.min_width(), 50);
+                assert!(!name.is_reorderable());
+
+                assert_eq!(done.sort_column_id(), -1);
+                assert!(done.is_reorderable());
+                assert!(done.expands());
+                assert_eq!(done.sizing(), crate::TreeViewColumnSizing::Fixed);
+                assert_eq!(done.fixed_width(), 30);
+                assert!(done.cells()[0].is::<CellRendererToggle>());
+            })
+            .expect("Failed to schedule a test call");
+        while TEST_THREAD_WORKER.unprocessed() > 0 {}
+    }
+}
@@ -0,0 +1,490 @@
+// Take a look at the license at the top of the repository in the LICENSE file.
+
+use glib::subclass::prelude::*;
+
+use crate::{TreeIter, TreeModel, prelude::*};
+
+// rustdoc-stripper-ignore-next
+/// The data shown by a [`VirtualListModel`].
+///
+/// The model never copies the data: values are requested from the source whenever a view
+/// needs them, so the source only has to be able to look up a single cell quickly.
+///
+/// The methods take `&self`, a source that changes over time keeps its data behind a
+/// [`RefCell`](std::cell::RefCell) or similar, shared with the code changing it. After each
+/// change, that code tells the model which rows changed with
+/// [`VirtualListModel::splice()`] or [`VirtualListModel::invalidate_range()`].
+pub trait VirtualListSource: 'static {
+    // rustdoc-stripper-ignore-next
+    /// Returns the types of the columns of the model. It is called once, when the model is
+    /// created.
+    fn column_types(&self) -> Vec<glib::Type>;
+
+    // rustdoc-stripper-ignore-next
+    /// Returns the current number of rows.
+    fn n_rows(&self) -> u32;
+
+    // rustdoc-stripper-ignore-next
+    /// Returns the value of `column` in `row`, which must be of the type of the column.
+    fn value(&self, row: u32, column: u32) -> glib::Value;
+}
+
+mod imp {
+    use std::cell::{Cell, OnceCell};
+
+    use super::*;
+    use crate::{TreeModelFlags, TreePath, subclass::prelude::*};
+
+    // The rows of the model while a splice is being signalled, one row at a time.
+    //
+    // The source already holds its new rows while the model goes from its old layout to the
+    // new one: the rows of the model are `[position rows][inserted][stale][suffix]`, where
+    // the `inserted` rows are the first new rows of the source, the `stale` rows were removed
+    // from the source but not yet signalled as deleted, and the suffix follows the `added`
+    // new rows in the source.
+    #[derive(Debug, Clone, Copy)]
+    pub(super) struct Splice {
+        pub(super) position: u32,
+        pub(super) inserted: u32,
+        pub(super) stale: u32,
+        pub(super) added: u32,
+    }
+
+    #[derive(Default)]
+    pub struct VirtualListModel {
+        pub(super) source: OnceCell<Box<dyn VirtualListSource>>,
+        pub(super) column_types: OnceCell<Vec<glib::Type>>,
+        pub(super) n_rows: Cell<u32>,
+        pub(super) splice: Cell<Option<Splice>>,
+        pub(super) stamp: Cell<i32>,
+    }
+
+    #[glib::object_subclass]
+    impl ObjectSubclass for VirtualListModel {
+        const NAME: &'static str = "GtkRsVirtualListModel";
+        type Type = super::VirtualListModel;
+        type ParentType = glib::Object;
+        type Interfaces = (TreeModel,);
+    }
+
+    impl ObjectImpl for VirtualListModel {}
+
+    impl TreeModelImpl for VirtualListModel {
+        fn flags(&self) -> TreeModelFlags {
+            TreeModelFlags::LIST_ONLY
+        }
+
+        fn n_columns(&self) -> i32 {
+            self.column_types().len() as i32
+        }
+
+        fn column_type(&self, index: i32) -> glib::Type {
+            self.column_types()
+                .get(index as usize)
+                .copied()
+                .unwrap_or(glib::Type::INVALID)
+        }
+
+        fn iter(&self, path: &TreePath) -> Option<TreeIter> {
+            match path.indices()[..] {
+                [index] => self.iter_nth_child(None, index),
+                _ => None,
+            }
+        }
+
+        fn path(&self, iter: &TreeIter) -> TreePath {
+            match self.index(iter) {
+                Some(index) => TreePath::from_indicesv(&[index as i32]),
+                None => TreePath::new(),
+            }
+        }
+
+        fn value(&self, iter: &TreeIter, column: i32) -> glib::Value {
+            let Some(&type_) = self.column_types().get(column as usize) else {
+                glib::g_warning!(
+                    "Gtk",
+                    "Invalid column {} of a VirtualListModel with {} columns",
+                    column,
+                    self.column_types().len()
+                );
+                // A value of some type has to be returned, which the caller can't expect
+                return None::<&str>.to_value();
+            };
+            let Some(row) = self.index(iter).and_then(|index| self.source_row(index)) else {
+                return glib::Value::from_type(type_);
+            };
+            let value = self.source().value(row, column as u32);
+            if !value.type_().is_a(type_) {
+                glib::g_warning!(
+                    "Gtk",
+                    "VirtualListSource returned a {} for column {} of type {}",
+                    value.type_(),
+                    column,
+                    type_
+                );
+                return glib::Value::from_type(type_);
+            }
+            value
+        }
+
+        fn iter_next(&self, iter: &TreeIter) -> Option<TreeIter> {
+            self.iter_nth_child(None, self.index(iter)? as i32 + 1)
+        }
+
+        fn iter_previous(&self, iter: &TreeIter) -> Option<TreeIter> {
+            self.index(iter)?
+                .checked_sub(1)
+                .map(|index| self.iter_at(index))
+        }
+
+        fn iter_has_child(&self, _iter: &TreeIter) -> bool {
+            false
+        }
+
+        fn iter_n_children(&self, iter: Option<&TreeIter>) -> i32 {
+            match iter {
+                Some(_) => 0,
+                None => self.n_rows.get() as i32,
+            }
+        }
+
+        fn iter_nth_child(&self, parent: Option<&TreeIter>, n: i32) -> Option<TreeIter> {
+            if parent.is_some() || n < 0 || n as u32 >= self.n_rows.get() {
+                return None;
+            }
+            Some(self.iter_at(n as u32))
+        }
+
+        fn iter_parent(&self, _child: &TreeIter) -> Option<TreeIter> {
+            None
+        }
+    }
+
+    impl VirtualListModel {
+        pub(super) fn source(&self) -> &dyn VirtualListSource {
+            &**self.source.get().expect("model not initialized")
+        }
+
+        fn column_types(&self) -> &[glib::Type] {
+            self.column_types.get().expect("model not initialized")
+        }
+
+        pub(super) fn iter_at(&self, index: u32) -> TreeIter {
+            TreeIter::from_handle(self.stamp.get(), index as usize)
+        }
+
+        // Returns `None` for iterators from before the last insertion or removal, which
+        // GTK's own stores reject as well
+        fn index(&self, iter: &TreeIter) -> Option<u32> {
+            let index = iter.handle();
+            (iter.stamp() == self.stamp.get() && index < self.n_rows.get() as usize)
+                .then_some(index as u32)
+        }
+
+        // Iterators are row indices, so any insertion or removal invalidates them
+        fn invalidate_iters(&self) {
+            self.stamp.set(self.stamp.get().wrapping_add(1));
+        }
+
+        // Returns the row of the source shown at `index`, or `None` for a row that was
+        // removed from the source but not signalled as deleted yet
+        fn source_row(&self, index: u32) -> Option<u32> {
+            let Some(splice) = self.splice.get() else {
+                return Some(index);
+            };
+            let stale_start = splice.position + splice.inserted;
+            if index < stale_start {
+                Some(index)
+            } else if index < stale_start + splice.stale {
+                None
+            } else {
+                Some(index - splice.inserted - splice.stale + splice.added)
+            }
+        }
+
+        pub(super) fn emit_rows_changed(&self, position: u32, n: u32) {
+            for index in position..position + n {
+                self.emit_row_changed(&self.iter_at(index));
+            }
+        }
+
+        pub(super) fn splice(&self, position: u32, removed: u32, added: u32) {
+            assert!(
+                self.splice.get().is_none(),
+                "can't splice the model from one of its signal handlers"
+            );
+            let n_rows = self.n_rows.get();
+            assert!(
+                position <= n_rows && removed <= n_rows - position,
+                "can't remove rows {position}..{} from a model of {n_rows} rows",
+                position as u64 + removed as u64,
+            );
+            let new_n_rows = n_rows - removed + added;
+            assert_eq!(
+                self.source().n_rows(),
+                new_n_rows,
+                "the source doesn't have the number of rows left by the splice"
+            );
+
+            // Rows replaced by as many new rows are only changed, so that a view doesn't have
+            // to forget and rebuild them
+            let changed = removed.min(added);
+            let mut splice = Splice {
+                position: position + changed,
+                inserted: 0,
+                stale: removed - changed,
+                added: added - changed,
+            };
+            self.splice.set(Some(splice));
+            self.emit_rows_changed(position, changed);
+
+            let path = TreePath::from_indicesv(&[splice.position as i32]);
+            while splice.stale > 0 {
+                splice.stale -= 1;
+                self.splice.set(Some(splice));
+                self.n_rows.set(self.n_rows.get() - 1);
+                self.invalidate_iters();
+                self.emit_row_deleted(&path);
+            }
+            while splice.inserted < splice.added {
+                let index = splice.position + splice.inserted;
+                splice.inserted += 1;
+                self.splice.set(Some(splice));
+                self.n_rows.set(self.n_rows.get() + 1);
+                self.invalidate_iters();
+                self.emit_row_inserted(&self.iter_at(index));
+            }
+            self.splice.set(None);
+        }
+    }
+}
+
+glib::wrapper! {
+    // rustdoc-stripper-ignore-next
+    /// A flat [`TreeModel`] showing the rows of a [`VirtualListSource`] without storing them.
+    ///
+    /// Unlike a [`ListStore`](crate::ListStore), the model keeps no copy of the data: its
+    /// size doesn't depend on the number of rows, and values are only requested from the
+    /// source for the rows a view actually shows. Together with a [`TreeView`](crate::TreeView)
+    /// in [fixed height mode](crate::prelude::TreeViewExt::set_fixed_height_mode), this allows
+    /// browsing millions of rows.
+    ///
+    /// ```no_run
+    /// # use gtk::prelude::*;
+    /// # use gtk::glib;
+    /// use std::cell::RefCell;
+    /// use std::rc::Rc;
+    ///
+    /// struct LogLines(Rc<RefCell<Vec<String>>>);
+    ///
+    /// impl gtk::VirtualListSource for LogLines {
+    ///     fn column_types(&self) -> Vec<glib::Type> {
+    ///         vec![glib::Type::U32, glib::Type::STRING]
+    ///     }
+    ///
+    ///     fn n_rows(&self) -> u32 {
+    ///         self.0.borrow().len() as u32
+    ///     }
+    ///
+    ///     fn value(&self, row: u32, column: u32) -> glib::Value {
+    ///         match column {
+    ///             0 => (row + 1).to_value(),
+    ///             _ => self.0.borrow()[row as usize].to_value(),
+    ///         }
+    ///     }
+    /// }
+    ///
+    /// let lines = Rc::new(RefCell::new(Vec::new()));
+    /// let model = gtk::VirtualListModel::new(LogLines(lines.clone()));
+    /// let tree_view = gtk::TreeView::with_model(&model);
+    /// tree_view.set_fixed_height_mode(true);
+    /// tree_view.column_builder().text("Line", 0).text("Message", 1).build();
+    ///
+    /// // Later, when new lines were read from the log file:
+    /// let position = lines.borrow().len() as u32;
+    /// lines.borrow_mut().extend(["first".to_owned(), "second".to_owned()]);
+    /// model.splice(position, 0, 2);
+    /// ```
+    pub struct VirtualListModel(ObjectSubclass<imp::VirtualListModel>)
+        @implements TreeModel;
+}
+
+impl VirtualListModel {
+    // rustdoc-stripper-ignore-next
+    /// Creates a model showing the rows of `source`.
+    pub fn new(source: impl VirtualListSource) -> Self {
+        assert_initialized_main_thread!();
+        let n_rows = source.n_rows();
+        assert!(
+            n_rows <= i32::MAX as u32,
+            "a tree model can't have more than {} rows",
+            i32::MAX
+        );
+
+        let model: Self = glib::Object::new();
+        let imp = model.imp();
+        imp.column_types.set(source.column_types()).unwrap();
+        imp.source
+            .set(Box::new(source))
+            .unwrap_or_else(|_| unreachable!());
+        imp.n_rows.set(n_rows);
+        model
+    }
+
+    // rustdoc-stripper-ignore-next
+    /// Returns the number of rows of the model.
+    ///
+    /// It is the number of rows of the source when it was last signalled with
+    /// [`splice()`](Self::splice).
+    pub fn n_rows(&self) -> u32 {
+        self.imp().n_rows.get()
+    }
+
+    // rustdoc-stripper-ignore-next
+    /// Signals that `removed` rows starting at `position` were replaced by `added` new rows
+    /// in the source, which must already hold its new rows.
+    ///
+    /// Rows that were replaced by the same number of new rows are signalled as changed, and
+    /// only the remaining rows as deleted or inserted. Removed rows read while the deletions
+    /// are signalled hold the default value of each column.
+    ///
+    /// Each of these rows is signalled separately, and views update themselves for each
+    /// signal. When replacing a large part of the rows, it is much faster to unset the model
+    /// of the views, splice, and set the model again, so the views only read the rows they
+    /// show once.
+    ///
+    /// # Panics
+    ///
+    /// If the removed rows are not all part of the model, if the source doesn't have the
+    /// number of rows left by the splice, or if called from a handler of a signal emitted
+    /// by another splice.
+    pub fn splice(&self, position: u32, removed: u32, added: u32) {
+        assert!(
+            self.n_rows() as i64 - removed as i64 + added as i64 <= i32::MAX as i64,
+            "a tree model can't have more than {} rows",
+            i32::MAX
+        );
+        self.imp().splice(position, removed, added);
+    }
+
+    // rustdoc-stripper-ignore-next
+    /// Signals that the values of `n` rows starting at `position` changed in the source.
+    ///
+    /// Like [`splice()`](Self::splice), this signals each row separately. To refresh a
+    /// large part of the rows, unset the model of the views and set it again instead.
+    ///
+    /// # Panics
+    ///
+    /// If the rows are not all part of the model.
+    pub fn invalidate_range(&self, position: u32, n: u32) {
+        let n_rows = self.n_rows();
+        assert!(
+            position <= n_rows && n <= n_rows - position,
+            "can't invalidate rows {position}..{} of a model of {n_rows} rows",
+            position as u64 + n as u64,
+        );
+        self.imp().emit_rows_changed(position, n);
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::cell::RefCell;
+    use std::rc::Rc;
+
+    use super::*;
+    use crate::TEST_THREAD_WORKER;
+
+    struct Numbers(Rc<RefCell<Vec<u32>>>);
+
+    impl VirtualListSource for Numbers {
+        fn column_types(&self) -> Vec<glib::Type> {
+            vec![glib::Type::U32]
+        }
+
+        fn n_rows(&self) -> u32 {
+            self.0.borrow().len() as u32
+        }
+
+        fn value(&self, row: u32, _column: u32) -> glib::Value {
+            self.0.borrow()[row as usize].to_value()
+        }
+    }
+
+    fn values(model: &VirtualListModel) -> Vec<u32> {
+        model
+            .rows()
+            .map(|iter| model.value(&iter, 0).get::<u32>().unwrap())
+            .collect()
+    }
+
+    #[test]
+    fn splice() {
+        TEST_THREAD_WORKER
+            .push(|| {
+                let numbers = Rc::new(RefCell::new((0..5).collect::<Vec<u32>>()));
+                let model = VirtualListModel::new(Numbers(numbers.clone()));
+                assert_eq!(values(&model), [0, 1, 2, 3, 4]);
+
+                let signals = Rc::new(RefCell::new(Vec::new()));
+                let s = signals.clone();
+                model.connect_row_changed(move |_, path, _| {
+                    s.borrow_mut().push(format!("changed {path}"));
+                });
+                let s = signals.clone();
+                model.connect_row_deleted(move |model, path| {
+                    let rows = model.iter_n_children(None);
+                    s.borrow_mut().push(format!("deleted {path} ({rows} rows)"));
+                });
+                let s = signals.clone();
+                model.connect_row_inserted(move |model, path, iter| {
+                    let value = model.value(iter, 0).get::<u32>().unwrap();
+                    s.borrow_mut().push(format!("inserted {path} = {value}"));
+                });
+
+                numbers.borrow_mut().splice(1..4, [10]);
+                model.splice(1, 3, 1);
+                assert_eq!(values(&model), [0, 10, 4]);
+
+                numbers.borrow_mut().splice(3..3, [20, 21]);
+                model.splice(3, 0, 2);
+                assert_eq!(values(&model), [0, 10, 4, 20, 21]);
+
+                numbers.borrow_mut()[0] = 30;
+                model.invalidate_range(0, 1);
+                assert_eq!(values(&model), [30, 10, 4, 20, 21]);
+
+                assert_eq!(
+                    *signals.borrow(),
+                    [
+                        "changed 1",
+                        "deleted 2 (4 rows)",
+                        "deleted 2 (3 rows)",
+                        "inserted 3 = 20",
+                        "inserted 4 = 21",
+                        "changed 0",
+                    ]
+                );
+            })
+            .expect("Failed to schedule a test call");
+        while TEST_THREAD_WORKER.unprocessed() > 0 {}
+    }
+
+    #[test]
+    fn stale_iter() {
+        TEST_THREAD_WORKER
+            .push(|| {
+                let numbers = Rc::new(RefCell::new(vec![1, 2]));
+                let model = VirtualListModel::new(Numbers(numbers.clone()));
+                let iter = model.iter_first().unwrap();
+
+                numbers.borrow_mut().push(3);
+                model.splice(2, 0, 1);
+                assert_eq!(model.value(&iter, 0).get::<u32>().unwrap(), 0);
+                assert_eq!(model.path(&iter).unwrap().depth(), 0);
+                assert!(!model.iter_next(&iter));
+            })
+            .expect("Failed to schedule a test call");
+        while TEST_THREAD_WORKER.unprocessed() > 0 {}
+    }
+}
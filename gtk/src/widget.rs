@@ -301,6 +301,244 @@ pub trait WidgetExtManual: IsA<Widget> + sealed::Sealed + 'static {
             ))
         }
     }
+
+    // rustdoc-stripper-ignore-next
+    /// Returns an iterator over the ancestors of the widget, starting with its parent.
+    fn ancestors(&self) -> crate::WidgetAncestors {
+        crate::WidgetAncestors::new(self.as_ref())
+    }
+
+    // rustdoc-stripper-ignore-next
+    /// Returns a depth-first iterator over the descendants of the widget, including internal
+    /// children.
+    fn descendants(&self) -> crate::WidgetDescendants {
+        crate::WidgetDescendants::new(self.as_ref())
+    }
+
+    // rustdoc-stripper-ignore-next
+    /// Returns the first descendant of the widget, in depth-first order, that matches the CSS
+    /// selector `selector`.
+    ///
+    /// See [`WidgetSelector`](crate::WidgetSelector) for the supported syntax.
+    ///
+    /// ```no_run
+    /// use gtk::prelude::*;
+    ///
+    /// # let window = gtk::Window::new(gtk::WindowType::Toplevel);
+    /// let save_button = window
+    ///     .query_selector("box.toolbar > button.suggested-action")
+    ///     .expect("No save button");
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// If `selector` is not a valid selector.
+    fn query_selector(&self, selector: &str) -> Option<Widget> {
+        let selector = crate::WidgetSelector::parse(selector).unwrap_or_else(|err| panic!("{err}"));
+        self.descendants().find(|widget| selector.matches(widget))
+    }
+
+    // rustdoc-stripper-ignore-next
+    /// Returns all descendants of the widget, in depth-first order, that match the CSS selector
+    /// `selector`.
+    ///
+    /// # Panics
+    ///
+    /// If `selector` is not a valid selector.
+    fn query_selector_all(&self, selector: &str) -> Vec<Widget> {
+        let selector = crate::WidgetSelector::parse(selector).unwrap_or_else(|err| panic!("{err}"));
+        self.descendants()
+            .filter(|widget| selector.matches(widget))
+            .collect()
+    }
+
+    // rustdoc-stripper-ignore-next
+    /// Returns a description of the widget and all of its descendants, including internal
+    /// children, with the properties in [`DEFAULT_DUMP_PROPERTIES`](crate::DEFAULT_DUMP_PROPERTIES).
+    ///
+    /// The result can be printed as text, or serialized with the `serde` feature, for
+    /// example to compare dynamically built UIs in tests or to attach them to bug reports.
+    ///
+    /// ```no_run
+    /// use gtk::prelude::*;
+    ///
+    /// let window = gtk::Window::new(gtk::WindowType::Toplevel);
+    /// window.add(&gtk::Button::with_label("Quit"));
+    /// println!("{}", window.dump_tree());
+    /// ```
+    fn dump_tree(&self) -> crate::WidgetNode {
+        crate::WidgetNode::new(self.as_ref(), crate::DEFAULT_DUMP_PROPERTIES)
+    }
+
+    // rustdoc-stripper-ignore-next
+    /// Like [`dump_tree()`][Self::dump_tree], but includes the properties named in
+    /// `properties` instead of the default ones.
+    fn dump_tree_with_properties(&self, properties: &[&str]) -> crate::WidgetNode {
+        crate::WidgetNode::new(self.as_ref(), properties)
+    }
+
+    // rustdoc-stripper-ignore-next
+    /// Draws the widget at a size of `width` × `height` on `cr`, without showing it on screen.
+    ///
+    /// The widget is temporarily added to an [`OffscreenWindow`](crate::OffscreenWindow), so
+    /// that it is realized and size-allocated, and removed from it again afterwards. It is
+    /// shown while being drawn, but its children are not, so hidden children stay hidden.
+    ///
+    /// # Panics
+    ///
+    /// If the widget has a parent or is a toplevel.
+    fn render(&self, cr: &cairo::Context, width: i32, height: i32) -> Result<(), cairo::Error> {
+        let widget = self.as_ref();
+        assert!(
+            widget.parent().is_none() && !widget.is_toplevel(),
+            "Only widgets without a parent can be rendered offscreen"
+        );
+        cr.save()?;
+
+        let was_visible = widget.is_visible();
+        let offscreen = crate::OffscreenWindow::new();
+        offscreen.add(widget);
+        widget.show();
+        offscreen.show();
+
+        // The offscreen window allocates its child at its natural size, so allocate the
+        // requested size ourselves, but never less than the minimum size.
+        let (min_width, _) = widget.preferred_width();
+        let (min_height, _) = widget.preferred_height_for_width(width.max(min_width));
+        widget.size_allocate(&Rectangle::new(
+            0,
+            0,
+            width.max(min_width),
+            height.max(min_height),
+        ));
+
+        cr.rectangle(0.0, 0.0, width as f64, height as f64);
+        cr.clip();
+        widget.draw(cr);
+
+        offscreen.remove(widget);
+        if !was_visible {
+            widget.hide();
+        }
+        unsafe {
+            offscreen.destroy();
+        }
+        cr.restore()
+    }
+
+    // rustdoc-stripper-ignore-next
+    /// Renders the widget at a size of `width` × `height` to a new image surface, without
+    /// showing it on screen.
+    ///
+    /// The surface is `scale` times larger than the widget and has a device scale of `scale`,
+    /// which must be the [scale factor](WidgetExt::scale_factor) of the widget, so that it
+    /// looks like on a monitor with that scale factor. GTK 3 has no way to change the scale
+    /// factor of a single window: to render at another scale, run the program with
+    /// `GDK_SCALE` set to it.
+    ///
+    /// ```no_run
+    /// use gtk::prelude::*;
+    ///
+    /// # fn main() -> Result<(), cairo::Error> {
+    /// let button = gtk::Button::with_label("Save");
+    /// let scale = button.scale_factor();
+    /// let surface = button.render_to_surface(120, 40, scale)?;
+    /// assert_eq!(surface.width(), 120 * scale);
+    /// # Ok(())
+    /// # }
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// If the widget has a parent or is a toplevel, or `scale` is not its scale factor.
+    fn render_to_surface(
+        &self,
+        width: i32,
+        height: i32,
+        scale: i32,
+    ) -> Result<cairo::ImageSurface, cairo::Error> {
+        let scale_factor = self.as_ref().scale_factor();
+        assert_eq!(
+            scale, scale_factor,
+            "Widgets are rendered with a scale factor of {scale_factor}, set `GDK_SCALE` to render them at a scale of {scale}",
+        );
+        let surface =
+            cairo::ImageSurface::create(cairo::Format::ARgb32, width * scale, height * scale)?;
+        surface.set_device_scale(scale as f64, scale as f64);
+
+        let cr = cairo::Context::new(&surface)?;
+        self.render(&cr, width, height)?;
+        cr.status()?;
+        drop(cr);
+
+        surface.flush();
+        Ok(surface)
+    }
+
+    // rustdoc-stripper-ignore-next
+    /// Renders the widget like [`render_to_surface()`][Self::render_to_surface] and saves it
+    /// as a PNG file.
+    #[cfg(feature = "png")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "png")))]
+    fn render_to_png(
+        &self,
+        path: impl AsRef<std::path::Path>,
+        width: i32,
+        height: i32,
+        scale: i32,
+    ) -> Result<(), cairo::IoError> {
+        let surface = self.render_to_surface(width, height, scale)?;
+        let mut file = std::fs::File::create(path)?;
+        surface.write_to_png(&mut file)
+    }
+
+    // rustdoc-stripper-ignore-next
+    /// Renders the widget at a size of `width` × `height` points to an SVG file.
+    ///
+    /// # Panics
+    ///
+    /// If the widget has a parent or is a toplevel.
+    #[cfg(feature = "svg")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "svg")))]
+    fn render_to_svg(
+        &self,
+        path: impl AsRef<std::path::Path>,
+        width: i32,
+        height: i32,
+    ) -> Result<(), cairo::Error> {
+        let surface = cairo::SvgSurface::new(width as f64, height as f64, Some(path))?;
+        let cr = cairo::Context::new(&surface)?;
+        self.render(&cr, width, height)?;
+        cr.status()?;
+        drop(cr);
+
+        surface.finish();
+        surface.status()
+    }
+
+    // rustdoc-stripper-ignore-next
+    /// Renders the widget at a size of `width` × `height` points to a PDF file.
+    ///
+    /// # Panics
+    ///
+    /// If the widget has a parent or is a toplevel.
+    #[cfg(feature = "pdf")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "pdf")))]
+    fn render_to_pdf(
+        &self,
+        path: impl AsRef<std::path::Path>,
+        width: i32,
+        height: i32,
+    ) -> Result<(), cairo::Error> {
+        let surface = cairo::PdfSurface::new(width as f64, height as f64, path)?;
+        let cr = cairo::Context::new(&surface)?;
+        self.render(&cr, width, height)?;
+        cr.status()?;
+        drop(cr);
+
+        surface.finish();
+        surface.status()
+    }
 }
 
 impl<O: IsA<Widget>> WidgetExtManual for O {}
@@ -318,3 +556,47 @@ impl<T: crate::subclass::widget::WidgetImpl> InitializingWidgetExt
         }
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::TEST_THREAD_WORKER;
+
+    #[test]
+    fn render_to_surface() {
+        TEST_THREAD_WORKER
+            .push(|| {
+                let area = crate::DrawingArea::new();
+                area.connect_draw(|_, cr| {
+                    cr.set_source_rgb(1.0, 0.0, 0.0);
+                    cr.paint().unwrap();
+                    glib::Propagation::Stop
+                });
+                area.hide();
+
+                let scale = area.scale_factor();
+                let mut surface = area.render_to_surface(30, 20, scale).unwrap();
+                assert_eq!(
+                    (surface.width(), surface.height()),
+                    (30 * scale, 20 * scale)
+                );
+                assert_eq!(surface.device_scale(), (scale as f64, scale as f64));
+                let stride = surface.stride() as usize;
+                let data = surface.data().unwrap();
+                let last = stride * (20 * scale as usize - 1) + 4 * (30 * scale as usize - 1);
+                assert_eq!(data[last..last + 4], [0, 0, 255, 255]);
+                drop(data);
+
+                // The widget is left as it was
+                assert!(area.parent().is_none());
+                assert!(!area.is_visible());
+
+                let res = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+                    area.render_to_surface(30, 20, scale + 1)
+                }));
+                assert!(res.is_err());
+            })
+            .expect("Failed to schedule a test call");
+        while TEST_THREAD_WORKER.unprocessed() > 0 {}
+    }
+}
@@ -0,0 +1,472 @@
+// Take a look at the license at the top of the repository in the LICENSE file.
+
+use std::fmt;
+use std::iter::Peekable;
+use std::str::{Chars, FromStr};
+
+use glib::translate::*;
+
+use crate::{StateFlags, Widget, ffi, prelude::*};
+
+// rustdoc-stripper-ignore-next
+/// An iterator over the ancestors of a widget, from its parent up to the toplevel.
+///
+/// Returned by [`WidgetExtManual::ancestors()`](crate::prelude::WidgetExtManual::ancestors).
+#[derive(Debug, Clone)]
+pub struct WidgetAncestors(Option<Widget>);
+
+impl WidgetAncestors {
+    pub(crate) fn new(widget: &Widget) -> Self {
+        Self(widget.parent())
+    }
+}
+
+impl Iterator for WidgetAncestors {
+    type Item = Widget;
+
+    fn next(&mut self) -> Option<Widget> {
+        let widget = self.0.take()?;
+        self.0 = widget.parent();
+        Some(widget)
+    }
+}
+
+impl std::iter::FusedIterator for WidgetAncestors {}
+
+// rustdoc-stripper-ignore-next
+/// A depth-first iterator over the descendants of a widget, including internal children.
+///
+/// Returned by
+/// [`WidgetExtManual::descendants()`](crate::prelude::WidgetExtManual::descendants).
+#[derive(Debug, Clone)]
+pub struct WidgetDescendants(Vec<Widget>);
+
+impl WidgetDescendants {
+    pub(crate) fn new(widget: &Widget) -> Self {
+        Self(children_reversed(widget))
+    }
+}
+
+// Returns the children in reverse order, so popping them from a stack visits them in order
+fn children_reversed(widget: &Widget) -> Vec<Widget> {
+    let mut children = Vec::new();
+    if let Some(container) = widget.downcast_ref::<crate::Container>() {
+        container.forall(|child| children.push(child.clone()));
+    }
+    children.reverse();
+    children
+}
+
+impl Iterator for WidgetDescendants {
+    type Item = Widget;
+
+    fn next(&mut self) -> Option<Widget> {
+        let widget = self.0.pop()?;
+        self.0.extend(children_reversed(&widget));
+        Some(widget)
+    }
+}
+
+impl std::iter::FusedIterator for WidgetDescendants {}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Combinator {
+    Descendant,
+    Child,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Compound {
+    css_name: Option<String>,
+    name: Option<String>,
+    buildable_id: Option<String>,
+    classes: Vec<String>,
+    state: StateFlags,
+}
+
+// A compound selector and the ones before it, in reverse order, with the combinators that
+// connect each of them to the one after it
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Complex {
+    subject: Compound,
+    ancestors: Vec<(Combinator, Compound)>,
+}
+
+// rustdoc-stripper-ignore-next
+/// A parsed CSS selector for matching widgets.
+///
+/// The supported syntax is a subset of the selectors of GTK style sheets:
+///
+/// - `button` matches the CSS name of the widget, and `*` matches any widget,
+/// - `.suggested-action` matches a style class,
+/// - `#name` matches the name set with
+///   [`WidgetExt::set_widget_name()`](crate::prelude::WidgetExt::set_widget_name),
+/// - `[id="save"]` matches the id of the widget in the UI definition it was built from,
+/// - `:checked`, `:active`, `:hover`, `:selected`, `:disabled`, `:indeterminate`, `:focus`,
+///   `:backdrop`, `:link`, `:visited` and `:drop(active)` match the state flags,
+/// - `a b` and `a > b` match descendants and children, and
+/// - `a, b` matches widgets matching either selector.
+///
+/// Internal children, like the scrollbars of a `GtkScrolledWindow`, are part of the tree the
+/// selectors are matched against.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct WidgetSelector(Vec<Complex>);
+
+impl WidgetSelector {
+    pub fn parse(selector: &str) -> Result<Self, glib::BoolError> {
+        skip_assert_initialized!();
+        Parser {
+            chars: selector.chars().peekable(),
+        }
+        .parse_selector()
+        .map_err(|err| glib::bool_error!("Invalid selector '{}': {}", selector, err))
+    }
+
+    // rustdoc-stripper-ignore-next
+    /// Returns `true` if `widget` matches the selector.
+    pub fn matches(&self, widget: &impl IsA<Widget>) -> bool {
+        let widget = widget.as_ref();
+        self.0.iter().any(|complex| {
+            complex.subject.matches(widget) && matches_ancestors(&complex.ancestors, widget)
+        })
+    }
+}
+
+impl FromStr for WidgetSelector {
+    type Err = glib::BoolError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        skip_assert_initialized!();
+        Self::parse(s)
+    }
+}
+
+fn matches_ancestors(ancestors: &[(Combinator, Compound)], widget: &Widget) -> bool {
+    let Some(((combinator, compound), rest)) = ancestors.split_first() else {
+        return true;
+    };
+    match combinator {
+        Combinator::Child => widget
+            .parent()
+            .is_some_and(|parent| compound.matches(&parent) && matches_ancestors(rest, &parent)),
+        Combinator::Descendant => WidgetAncestors::new(widget)
+            .any(|ancestor| compound.matches(&ancestor) && matches_ancestors(rest, &ancestor)),
+    }
+}
+
+fn css_name(widget: &Widget) -> glib::GString {
+    unsafe {
+        let instance = widget.to_glib_none().0 as *mut glib::gobject_ffi::GTypeInstance;
+        let class = (*instance).g_class as *mut ffi::GtkWidgetClass;
+        from_glib_none(ffi::gtk_widget_class_get_css_name(class))
+    }
+}
+
+impl Compound {
+    fn matches(&self, widget: &Widget) -> bool {
+        if !widget.state_flags().contains(self.state) {
+            return false;
+        }
+        if self
+            .css_name
+            .as_ref()
+            .is_some_and(|expected| expected.as_str() != css_name(widget))
+        {
+            return false;
+        }
+        if self
+            .name
+            .as_ref()
+            .is_some_and(|name| name.as_str() != widget.widget_name())
+        {
+            return false;
+        }
+        if self
+            .buildable_id
+            .as_ref()
+            .is_some_and(|id| widget.buildable_name().as_ref() != Some(id))
+        {
+            return false;
+        }
+        let style_context = widget.style_context();
+        self.classes
+            .iter()
+            .all(|class| style_context.has_class(class))
+    }
+}
+
+struct Parser<'a> {
+    chars: Peekable<Chars<'a>>,
+}
+
+impl Parser<'_> {
+    fn skip_whitespace(&mut self) -> bool {
+        let mut skipped = false;
+        while self.chars.next_if(|c| c.is_whitespace()).is_some() {
+            skipped = true;
+        }
+        skipped
+    }
+
+    fn parse_selector(&mut self) -> Result<WidgetSelector, String> {
+        let mut complexes = Vec::new();
+        loop {
+            self.skip_whitespace();
+            complexes.push(self.parse_complex()?);
+            match self.chars.next() {
+                None => return Ok(WidgetSelector(complexes)),
+                Some(',') => continue,
+                Some(c) => return Err(format!("unexpected '{c}'")),
+            }
+        }
+    }
+
+    fn parse_complex(&mut self) -> Result<Complex, String> {
+        let mut subject = self.parse_compound()?;
+        let mut ancestors = Vec::new();
+        loop {
+            let had_whitespace = self.skip_whitespace();
+            let combinator = match self.chars.peek() {
+                None | Some(',') => break,
+                Some('>') => {
+                    self.chars.next();
+                    self.skip_whitespace();
+                    Combinator::Child
+                }
+                Some('+' | '~') => return Err("sibling combinators are not supported".into()),
+                Some(_) if had_whitespace => Combinator::Descendant,
+                Some(c) => return Err(format!("unexpected '{c}'")),
+            };
+            let compound = self.parse_compound()?;
+            ancestors.push((combinator, std::mem::replace(&mut subject, compound)));
+        }
+        ancestors.reverse();
+        Ok(Complex { subject, ancestors })
+    }
+
+    fn parse_ident(&mut self) -> Result<String, String> {
+        let mut ident = String::new();
+        while let Some(c) = self
+            .chars
+            .next_if(|c| c.is_alphanumeric() || *c == '-' || *c == '_')
+        {
+            ident.push(c);
+        }
+        if ident.is_empty() {
+            match self.chars.peek() {
+                Some(c) => Err(format!("expected a name, found '{c}'")),
+                None => Err("expected a name".into()),
+            }
+        } else {
+            Ok(ident)
+        }
+    }
+
+    fn parse_compound(&mut self) -> Result<Compound, String> {
+        let mut compound = Compound {
+            css_name: None,
+            name: None,
+            buildable_id: None,
+            classes: Vec::new(),
+            state: StateFlags::empty(),
+        };
+        let mut empty = true;
+
+        if self.chars.next_if_eq(&'*').is_some() {
+            empty = false;
+        } else if self
+            .chars
+            .peek()
+            .is_some_and(|c| c.is_alphanumeric() || *c == '_')
+        {
+            compound.css_name = Some(self.parse_ident()?);
+            empty = false;
+        }
+
+        loop {
+            match self.chars.peek() {
+                Some('.') => {
+                    self.chars.next();
+                    compound.classes.push(self.parse_ident()?);
+                }
+                Some('#') => {
+                    self.chars.next();
+                    compound.name = Some(self.parse_ident()?);
+                }
+                Some('[') => {
+                    self.chars.next();
+                    compound.buildable_id = Some(self.parse_attribute()?);
+                }
+                Some(':') => {
+                    self.chars.next();
+                    compound.state |= self.parse_pseudo_class()?;
+                }
+                _ => break,
+            }
+            empty = false;
+        }
+
+        if empty {
+            match self.chars.peek() {
+                Some(c) => Err(format!("expected a selector, found '{c}'")),
+                None => Err("expected a selector".into()),
+            }
+        } else {
+            Ok(compound)
+        }
+    }
+
+    fn parse_attribute(&mut self) -> Result<String, String> {
+        self.skip_whitespace();
+        let name = self.parse_ident()?;
+        if name != "id" {
+            return Err(format!(
+                "unsupported attribute '{name}', only 'id' is supported"
+            ));
+        }
+        self.skip_whitespace();
+        if self.chars.next_if_eq(&'=').is_none() {
+            return Err("expected '=' in attribute selector".into());
+        }
+        self.skip_whitespace();
+        let value = match self.chars.next_if(|c| *c == '"' || *c == '\'') {
+            Some(quote) => {
+                let mut value = String::new();
+                loop {
+                    match self.chars.next() {
+                        Some(c) if c == quote => break value,
+                        Some(c) => value.push(c),
+                        None => return Err("unterminated string in attribute selector".into()),
+                    }
+                }
+            }
+            None => self.parse_ident()?,
+        };
+        self.skip_whitespace();
+        if self.chars.next_if_eq(&']').is_none() {
+            return Err("expected ']' after attribute selector".into());
+        }
+        Ok(value)
+    }
+
+    fn parse_pseudo_class(&mut self) -> Result<StateFlags, String> {
+        let name = self.parse_ident()?;
+        let state = match name.as_str() {
+            "active" => StateFlags::ACTIVE,
+            "hover" => StateFlags::PRELIGHT,
+            "selected" => StateFlags::SELECTED,
+            "disabled" => StateFlags::INSENSITIVE,
+            "indeterminate" => StateFlags::INCONSISTENT,
+            "focus" => StateFlags::FOCUSED,
+            "backdrop" => StateFlags::BACKDROP,
+            "link" => StateFlags::LINK,
+            "visited" => StateFlags::VISITED,
+            "checked" => StateFlags::CHECKED,
+            "drop" => {
+                let argument = self
+                    .chars
+                    .next_if_eq(&'(')
+                    .map(|_| self.parse_ident())
+                    .transpose()?;
+                if argument.as_deref() != Some("active") || self.chars.next_if_eq(&')').is_none() {
+                    return Err("expected ':drop(active)'".into());
+                }
+                StateFlags::DROP_ACTIVE
+            }
+            _ => return Err(format!("unsupported pseudo-class ':{name}'")),
+        };
+        Ok(state)
+    }
+}
+
+impl fmt::Display for Compound {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(self.css_name.as_deref().unwrap_or("*"))?;
+        if let Some(name) = &self.name {
+            write!(f, "#{name}")?;
+        }
+        for class in &self.classes {
+            write!(f, ".{class}")?;
+        }
+        if let Some(id) = &self.buildable_id {
+            write!(f, "[id={id:?}]")?;
+        }
+        for (flag, pseudo_class) in [
+            (StateFlags::ACTIVE, ":active"),
+            (StateFlags::PRELIGHT, ":hover"),
+            (StateFlags::SELECTED, ":selected"),
+            (StateFlags::INSENSITIVE, ":disabled"),
+            (StateFlags::INCONSISTENT, ":indeterminate"),
+            (StateFlags::FOCUSED, ":focus"),
+            (StateFlags::BACKDROP, ":backdrop"),
+            (StateFlags::LINK, ":link"),
+            (StateFlags::VISITED, ":visited"),
+            (StateFlags::CHECKED, ":checked"),
+            (StateFlags::DROP_ACTIVE, ":drop(active)"),
+        ] {
+            if self.state.contains(flag) {
+                f.write_str(pseudo_class)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+impl fmt::Display for WidgetSelector {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for (i, complex) in self.0.iter().enumerate() {
+            if i > 0 {
+                f.write_str(", ")?;
+            }
+            for (combinator, compound) in complex.ancestors.iter().rev() {
+                match combinator {
+                    Combinator::Descendant => write!(f, "{compound} ")?,
+                    Combinator::Child => write!(f, "{compound} > ")?,
+                }
+            }
+            write!(f, "{}", complex.subject)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_and_display() {
+        for (selector, expected) in [
+            ("button", "button"),
+            ("*", "*"),
+            (".toolbar", "*.toolbar"),
+            (
+                "box.toolbar > button.suggested-action:checked",
+                "box.toolbar > button.suggested-action:checked",
+            ),
+            ("window  #main   label", "window *#main label"),
+            (
+                "[id='save'], entry:disabled",
+                "*[id=\"save\"], entry:disabled",
+            ),
+            ("row:drop(active)>label", "row:drop(active) > label"),
+        ] {
+            assert_eq!(
+                WidgetSelector::parse(selector).unwrap().to_string(),
+                expected
+            );
+        }
+    }
+
+    #[test]
+    fn parse_errors() {
+        for selector in [
+            "", "button >", "button,", "a + b", ":unknown", "[name=x]", "[id=\"x]", "label!",
+        ] {
+            assert!(
+                WidgetSelector::parse(selector).is_err(),
+                "'{selector}' should not parse"
+            );
+        }
+    }
+}
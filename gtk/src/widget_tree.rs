@@ -0,0 +1,353 @@
+// Take a look at the license at the top of the repository in the LICENSE file.
+
+use std::fmt;
+
+use glib::translate::*;
+
+use crate::{Buildable, Container, Widget, ffi, prelude::*};
+
+// rustdoc-stripper-ignore-next
+/// The properties [`WidgetExtManual::dump_tree()`](crate::prelude::WidgetExtManual::dump_tree)
+/// includes for the widgets that have them.
+pub const DEFAULT_DUMP_PROPERTIES: &[&str] = &[
+    "label",
+    "text",
+    "title",
+    "active",
+    "value",
+    "icon-name",
+    "orientation",
+    "tooltip-text",
+];
+
+// rustdoc-stripper-ignore-next
+/// The allocation of a widget in a [`WidgetNode`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct WidgetAllocation {
+    pub x: i32,
+    pub y: i32,
+    pub width: i32,
+    pub height: i32,
+}
+
+// rustdoc-stripper-ignore-next
+/// A snapshot of a widget and its children, as returned by
+/// [`WidgetExtManual::dump_tree()`](crate::prelude::WidgetExtManual::dump_tree).
+///
+/// Its [`Display`](fmt::Display) implementation prints one line per widget, indented by its
+/// depth in the hierarchy, for example:
+///
+/// ```text
+/// GtkBox id=main classes=[vertical] 0,0 200×80 orientation=vertical
+///   GtkLabel 0,0 200×40 label="Name" child[expand=false, fill=true, pack-type=start, padding=0, position=0]
+///   GtkEntry hidden 0,40 200×40 text="" child[expand=false, fill=true, pack-type=start, padding=0, position=1]
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct WidgetNode {
+    // rustdoc-stripper-ignore-next
+    /// The name of the type of the widget, like `GtkButton`.
+    pub type_name: String,
+    // rustdoc-stripper-ignore-next
+    /// The id of the widget in the UI definition it was built from.
+    pub buildable_id: Option<String>,
+    // rustdoc-stripper-ignore-next
+    /// The name set with
+    /// [`WidgetExt::set_widget_name()`](crate::prelude::WidgetExt::set_widget_name), if it
+    /// differs from the type name.
+    pub name: Option<String>,
+    pub css_classes: Vec<String>,
+    pub visible: bool,
+    pub sensitive: bool,
+    // rustdoc-stripper-ignore-next
+    /// Whether the widget is an internal child of its parent, like the scrollbars of a
+    /// `GtkScrolledWindow`, which is only found by
+    /// [`ContainerExt::forall()`](crate::prelude::ContainerExt::forall).
+    pub internal: bool,
+    pub allocation: WidgetAllocation,
+    // rustdoc-stripper-ignore-next
+    /// The names and values of the selected properties the widget has. Objects are written
+    /// as their type name, followed by their id in parentheses if they have one.
+    pub properties: Vec<(String, String)>,
+    // rustdoc-stripper-ignore-next
+    /// The names and values of the child properties the parent container set on the widget.
+    pub child_properties: Vec<(String, String)>,
+    pub children: Vec<WidgetNode>,
+}
+
+impl WidgetNode {
+    pub(crate) fn new(widget: &Widget, properties: &[&str]) -> Self {
+        Self::with_parent(widget, None, false, properties)
+    }
+
+    fn with_parent(
+        widget: &Widget,
+        parent: Option<&Container>,
+        internal: bool,
+        properties: &[&str],
+    ) -> Self {
+        let type_name = widget.type_().name().to_owned();
+        let name = Some(widget.widget_name().to_string()).filter(|name| *name != type_name);
+        let allocation = widget.allocation();
+        let mut css_classes = widget
+            .style_context()
+            .list_classes()
+            .into_iter()
+            .map(String::from)
+            .collect::<Vec<_>>();
+        css_classes.sort();
+
+        let mut children = Vec::new();
+        if let Some(container) = widget.downcast_ref::<Container>() {
+            let public_children = container.children();
+            container.forall(|child| {
+                let internal = !public_children.contains(child);
+                children.push(Self::with_parent(
+                    child,
+                    Some(container),
+                    internal,
+                    properties,
+                ));
+            });
+        }
+
+        Self {
+            type_name,
+            buildable_id: widget.buildable_name(),
+            name,
+            css_classes,
+            visible: widget.is_visible(),
+            sensitive: widget.is_sensitive(),
+            internal,
+            allocation: WidgetAllocation {
+                x: allocation.x(),
+                y: allocation.y(),
+                width: allocation.width(),
+                height: allocation.height(),
+            },
+            properties: properties
+                .iter()
+                .filter_map(|&name| {
+                    let pspec = widget.find_property(name)?;
+                    pspec.flags().contains(glib::ParamFlags::READABLE).then(|| {
+                        (
+                            name.to_owned(),
+                            value_to_string(&widget.property_value(name)),
+                        )
+                    })
+                })
+                .collect(),
+            child_properties: parent
+                .map(|parent| child_properties(parent, widget))
+                .unwrap_or_default(),
+            children,
+        }
+    }
+
+    // rustdoc-stripper-ignore-next
+    /// Returns an iterator over this node and all of its descendants, depth-first.
+    pub fn iter(&self) -> impl Iterator<Item = &WidgetNode> {
+        let mut stack = vec![self];
+        std::iter::from_fn(move || {
+            let node = stack.pop()?;
+            stack.extend(node.children.iter().rev());
+            Some(node)
+        })
+    }
+
+    fn fmt_with_depth(&self, f: &mut fmt::Formatter, depth: usize) -> fmt::Result {
+        write!(f, "{:indent$}{}", "", self.type_name, indent = depth * 2)?;
+        if let Some(id) = &self.buildable_id {
+            write!(f, " id={id}")?;
+        }
+        if let Some(name) = &self.name {
+            write!(f, " name={name}")?;
+        }
+        if !self.css_classes.is_empty() {
+            write!(f, " classes=[{}]", self.css_classes.join(", "))?;
+        }
+        if self.internal {
+            f.write_str(" internal")?;
+        }
+        if !self.visible {
+            f.write_str(" hidden")?;
+        }
+        if !self.sensitive {
+            f.write_str(" insensitive")?;
+        }
+        let WidgetAllocation {
+            x,
+            y,
+            width,
+            height,
+        } = self.allocation;
+        write!(f, " {x},{y} {width}×{height}")?;
+        for (name, value) in &self.properties {
+            write!(f, " {name}={value}")?;
+        }
+        if !self.child_properties.is_empty() {
+            let child_properties = self
+                .child_properties
+                .iter()
+                .map(|(name, value)| format!("{name}={value}"))
+                .collect::<Vec<_>>();
+            write!(f, " child[{}]", child_properties.join(", "))?;
+        }
+        writeln!(f)?;
+
+        for child in &self.children {
+            child.fmt_with_depth(f, depth + 1)?;
+        }
+        Ok(())
+    }
+}
+
+impl fmt::Display for WidgetNode {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        self.fmt_with_depth(f, 0)
+    }
+}
+
+fn child_properties(container: &Container, child: &Widget) -> Vec<(String, String)> {
+    let pspecs: Vec<glib::ParamSpec> = unsafe {
+        let container_class = glib::Class::<Container>::from_type(container.type_()).unwrap();
+        let mut n_properties = 0;
+        let pspecs = ffi::gtk_container_class_list_child_properties(
+            container_class.as_ref() as *const _ as *mut glib::gobject_ffi::GObjectClass,
+            &mut n_properties,
+        );
+        FromGlibContainer::from_glib_container_num(pspecs, n_properties as usize)
+    };
+
+    let mut properties = pspecs
+        .iter()
+        .filter(|pspec| pspec.flags().contains(glib::ParamFlags::READABLE))
+        .map(|pspec| {
+            let mut value = glib::Value::from_type(pspec.value_type());
+            unsafe {
+                ffi::gtk_container_child_get_property(
+                    container.to_glib_none().0,
+                    child.to_glib_none().0,
+                    pspec.name().to_glib_none().0,
+                    value.to_glib_none_mut().0,
+                );
+            }
+            (pspec.name().to_owned(), value_to_string(&value))
+        })
+        .collect::<Vec<_>>();
+    properties.sort();
+    properties
+}
+
+fn value_to_string(value: &glib::Value) -> String {
+    if let Ok(b) = value.get::<bool>() {
+        return b.to_string();
+    }
+    if let Ok(s) = value.get::<Option<&str>>() {
+        return match s {
+            Some(s) => format!("{s:?}"),
+            None => "null".to_owned(),
+        };
+    }
+    if let Some(enum_value) = glib::EnumValue::from_value(value) {
+        return enum_value.1.nick().to_owned();
+    }
+    // Objects are printed by type and id rather than by address, to keep dumps comparable
+    if let Ok(object) = value.get::<Option<glib::Object>>() {
+        return match object {
+            Some(object) => {
+                let id = object
+                    .dynamic_cast_ref::<Buildable>()
+                    .and_then(|buildable| buildable.buildable_name());
+                match id {
+                    Some(id) => format!("{}({id})", object.type_()),
+                    None => object.type_().to_string(),
+                }
+            }
+            None => "null".to_owned(),
+        };
+    }
+    match value.transform::<String>() {
+        Ok(transformed) => transformed
+            .get::<Option<String>>()
+            .ok()
+            .flatten()
+            .unwrap_or_else(|| "null".to_owned()),
+        Err(_) => format!("<{}>", value.type_()),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::TEST_THREAD_WORKER;
+
+    #[test]
+    fn dump_tree() {
+        TEST_THREAD_WORKER
+            .push(|| {
+                let root = crate::Box::new(crate::Orientation::Vertical, 0);
+                let label = crate::Label::new(Some("Name"));
+                label.set_buildable_name("title");
+                label.style_context().add_class("zeta");
+                label.style_context().add_class("alpha");
+                let entry = crate::Entry::new();
+                entry.set_buildable_name("entry");
+                label.set_mnemonic_widget(Some(&entry));
+                let scrolled = crate::ScrolledWindow::builder().build();
+                root.add(&label);
+                root.add(&entry);
+                root.add(&scrolled);
+                label.show();
+                scrolled.show();
+
+                let tree =
+                    root.dump_tree_with_properties(&["label", "mnemonic-widget", "hadjustment"]);
+                assert_eq!(tree.type_name, "GtkBox");
+                let [label_node, entry_node, scrolled_node] = &tree.children[..] else {
+                    panic!("expected three children, got {}", tree.children.len());
+                };
+
+                assert_eq!(label_node.buildable_id.as_deref(), Some("title"));
+                assert!(label_node.css_classes.is_sorted());
+                assert!(label_node.css_classes.iter().any(|class| class == "alpha"));
+                assert_eq!(
+                    label_node.properties,
+                    [
+                        ("label".to_owned(), "\"Name\"".to_owned()),
+                        ("mnemonic-widget".to_owned(), "GtkEntry(entry)".to_owned()),
+                    ]
+                );
+                assert!(label_node.child_properties.is_sorted());
+                assert!(
+                    label_node
+                        .child_properties
+                        .contains(&("position".to_owned(), "0".to_owned()))
+                );
+
+                assert!(!entry_node.visible);
+                assert!(!entry_node.internal);
+                assert_eq!(
+                    scrolled_node.properties,
+                    [("hadjustment".to_owned(), "GtkAdjustment".to_owned())]
+                );
+                assert!(
+                    scrolled_node
+                        .iter()
+                        .any(|node| node.internal && node.type_name == "GtkScrollbar")
+                );
+
+                let dump = tree.to_string();
+                let lines = dump.lines().collect::<Vec<_>>();
+                assert_eq!(lines.len(), tree.iter().count());
+                assert!(lines[0].starts_with("GtkBox "));
+                assert!(lines[1].starts_with("  GtkLabel id=title classes=["));
+                assert!(lines[1].contains(" mnemonic-widget=GtkEntry(entry) "));
+                assert!(lines[2].starts_with("  GtkEntry id=entry "));
+                assert!(lines[2].contains(" hidden "));
+            })
+            .expect("Failed to schedule a test call");
+        while TEST_THREAD_WORKER.unprocessed() > 0 {}
+    }
+}
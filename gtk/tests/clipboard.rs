@@ -0,0 +1,109 @@
+// Take a look at the license at the top of the repository in the LICENSE file.
+
+use std::cell::RefCell;
+use std::future::Future;
+use std::rc::Rc;
+use std::time::Duration;
+
+use gtk::{gdk, gdk_pixbuf, glib};
+
+// Runs the main loop until `future` spawned on it completes.
+fn wait<T: 'static>(future: impl Future<Output = T> + 'static) -> T {
+    let result = Rc::new(RefCell::new(None));
+    let slot = result.clone();
+    glib::MainContext::default().spawn_local(async move {
+        *slot.borrow_mut() = Some(future.await);
+    });
+    let done = gtk::test::run_until(Duration::from_secs(5), || result.borrow().is_some());
+    assert!(done, "The clipboard request timed out");
+    result.take().unwrap()
+}
+
+#[test]
+fn read_futures() {
+    gtk::test::run(|| {
+        let clipboard = gtk::Clipboard::get(&gdk::SELECTION_CLIPBOARD);
+        clipboard.set_text("Hello");
+
+        let text = wait(clipboard.read_text_future());
+        assert_eq!(text.as_deref(), Some("Hello"));
+
+        let targets = wait(clipboard.read_targets_future()).unwrap();
+        let utf8 = gdk::Atom::intern("UTF8_STRING");
+        assert!(targets.contains(&utf8), "{targets:?}");
+
+        let contents = wait(clipboard.read_contents_future(&utf8)).unwrap();
+        assert_eq!(contents.data(), b"Hello");
+        let missing = wait(clipboard.read_contents_future(&gdk::Atom::intern("image/png")));
+        assert!(missing.is_none());
+
+        let pixbuf = gdk_pixbuf::Pixbuf::new(gdk_pixbuf::Colorspace::Rgb, false, 8, 4, 3).unwrap();
+        pixbuf.fill(0xff0000ff);
+        clipboard.set_image(&pixbuf);
+        let image = wait(clipboard.read_image_future()).unwrap();
+        assert_eq!((image.width(), image.height()), (4, 3));
+        assert!(wait(clipboard.read_text_future()).is_none());
+
+        clipboard.clear();
+    });
+}
+
+#[test]
+fn provider() {
+    gtk::test::run(|| {
+        let clipboard = gtk::Clipboard::get(&gdk::SELECTION_PRIMARY);
+        let owner = glib::Object::new::<glib::Object>();
+
+        let text_calls = Rc::new(RefCell::new(0));
+        let html_calls = Rc::new(RefCell::new(0));
+        let cleared = Rc::new(RefCell::new(0));
+        let provider = gtk::ClipboardProvider::new()
+            .text({
+                let calls = text_calls.clone();
+                move || {
+                    *calls.borrow_mut() += 1;
+                    "Alice\t42".to_owned()
+                }
+            })
+            .mime("text/html", {
+                let calls = html_calls.clone();
+                move || {
+                    *calls.borrow_mut() += 1;
+                    b"<td>Alice</td>".to_vec()
+                }
+            })
+            .on_clear({
+                let cleared = cleared.clone();
+                move |_| *cleared.borrow_mut() += 1
+            });
+        assert!(clipboard.set_with_owner(&owner, provider));
+
+        let targets = wait(clipboard.read_targets_future()).unwrap();
+        assert!(
+            targets.contains(&gdk::Atom::intern("text/html")),
+            "{targets:?}"
+        );
+        assert!(
+            targets.contains(&gdk::Atom::intern("UTF8_STRING")),
+            "{targets:?}"
+        );
+        assert_eq!((*text_calls.borrow(), *html_calls.borrow()), (0, 0));
+
+        let text = wait(clipboard.read_text_future());
+        assert_eq!(text.as_deref(), Some("Alice\t42"));
+        assert_eq!((*text_calls.borrow(), *html_calls.borrow()), (1, 0));
+
+        let html = wait(clipboard.read_contents_future(&gdk::Atom::intern("text/html")));
+        assert_eq!(html.unwrap().data(), b"<td>Alice</td>");
+        assert_eq!((*text_calls.borrow(), *html_calls.borrow()), (1, 1));
+
+        // Clearing calls `on_clear` once and drops the closures
+        assert_eq!(*cleared.borrow(), 0);
+        clipboard.clear();
+        assert_eq!(*cleared.borrow(), 1);
+        assert_eq!(Rc::strong_count(&text_calls), 1);
+        assert_eq!(Rc::strong_count(&html_calls), 1);
+        assert_eq!(Rc::strong_count(&cleared), 1);
+        assert!(wait(clipboard.read_targets_future()).is_none());
+    });
+}
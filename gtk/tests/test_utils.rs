@@ -0,0 +1,79 @@
+// Take a look at the license at the top of the repository in the LICENSE file.
+
+use std::cell::Cell;
+use std::rc::Rc;
+use std::time::{Duration, Instant};
+
+use gtk::prelude::*;
+
+#[test]
+fn click_fires_clicked() {
+    gtk::test::run(|| {
+        let window = gtk::Window::new(gtk::WindowType::Toplevel);
+        let button = gtk::Button::with_label("Count");
+        let clicks = Rc::new(Cell::new(0));
+        let counter = clicks.clone();
+        button.connect_clicked(move |_| counter.set(counter.get() + 1));
+        window.add(&button);
+        window.show_all();
+        gtk::test::run_until_idle();
+
+        gtk::test::click(&button);
+        let clicked = gtk::test::run_until(Duration::from_secs(1), || clicks.get() == 1);
+        assert!(clicked);
+
+        unsafe { window.destroy() };
+    });
+}
+
+#[test]
+fn keys_reach_entry() {
+    gtk::test::run(|| {
+        let window = gtk::Window::new(gtk::WindowType::Toplevel);
+        let entry = gtk::Entry::new();
+        let activated = Rc::new(Cell::new(false));
+        let flag = activated.clone();
+        entry.connect_activate(move |_| flag.set(true));
+        window.add(&entry);
+        window.show_all();
+        gtk::test::run_until_idle();
+
+        gtk::test::type_text(&entry, "abc");
+        gtk::test::assert_text(&entry, "abc");
+        gtk::test::assert_focused(&entry);
+
+        gtk::test::press_key(
+            &entry,
+            gtk::gdk::keys::constants::BackSpace,
+            gtk::gdk::ModifierType::empty(),
+        );
+        gtk::test::assert_text(&entry, "ab");
+
+        gtk::test::press_key(
+            &entry,
+            gtk::gdk::keys::constants::Return,
+            gtk::gdk::ModifierType::empty(),
+        );
+        assert!(activated.get());
+
+        unsafe { window.destroy() };
+    });
+}
+
+#[test]
+fn run_until_times_out() {
+    gtk::test::run(|| {
+        let start = Instant::now();
+        assert!(!gtk::test::run_until(Duration::from_millis(50), || false));
+        assert!(start.elapsed() >= Duration::from_millis(50));
+        assert!(gtk::test::run_until(Duration::from_millis(50), || true));
+    });
+}
+
+#[test]
+fn run_propagates_panics() {
+    let res = std::panic::catch_unwind(|| gtk::test::run(|| panic!("failed on the GTK thread")));
+    assert!(res.is_err());
+    // The test thread keeps working after a panic
+    assert_eq!(gtk::test::run(|| 1 + 1), 2);
+}
@@ -2,7 +2,7 @@
 
 use proc_macro2::{Span, TokenStream};
 use syn::spanned::Spanned;
-use syn::{Attribute, DeriveInput, Field, Fields, Ident, LitStr, Meta, Token};
+use syn::{Attribute, DeriveInput, Field, Fields, Ident, LitBool, LitStr, Meta, Token, Type};
 use syn::{
     parse::{Error, Parse, ParseStream},
     punctuated::Punctuated,
@@ -14,6 +14,8 @@ mod kw {
     syn::custom_keyword!(string);
 
     syn::custom_keyword!(id);
+    syn::custom_keyword!(internal);
+    syn::custom_keyword!(optional);
 }
 
 pub enum TemplateSource {
@@ -93,7 +95,11 @@ pub fn parse_template_source(
 }
 
 pub enum FieldAttributeArg {
-    Id(String),
+    // The span is needed for xml_validation feature
+    #[cfg_attr(not(feature = "xml_validation"), allow(dead_code))]
+    Id(String, Span),
+    Internal(bool),
+    Optional(bool),
 }
 
 impl Parse for FieldAttributeArg {
@@ -103,7 +109,17 @@ impl Parse for FieldAttributeArg {
             let _: kw::id = input.parse()?;
             let _: Token![=] = input.parse()?;
             let lit: LitStr = input.parse()?;
-            Ok(Self::Id(lit.value()))
+            Ok(Self::Id(lit.value(), lit.span()))
+        } else if lookahead.peek(kw::internal) {
+            let _: kw::internal = input.parse()?;
+            let _: Token![=] = input.parse()?;
+            let lit: LitBool = input.parse()?;
+            Ok(Self::Internal(lit.value()))
+        } else if lookahead.peek(kw::optional) {
+            let _: kw::optional = input.parse()?;
+            let _: Token![=] = input.parse()?;
+            let lit: LitBool = input.parse()?;
+            Ok(Self::Optional(lit.value()))
         } else {
             Err(lookahead.error())
         }
@@ -122,9 +138,50 @@ pub struct FieldAttribute {
 
 pub struct AttributedField {
     pub ident: Ident,
+    #[cfg_attr(not(feature = "xml_validation"), allow(dead_code))]
+    pub ty: Type,
     pub attr: FieldAttribute,
 }
 
+impl AttributedField {
+    pub fn id(&self) -> String {
+        self.attr
+            .args
+            .iter()
+            .find_map(|arg| match arg {
+                FieldAttributeArg::Id(value, _) => Some(value.clone()),
+                _ => None,
+            })
+            .unwrap_or_else(|| self.ident.to_string())
+    }
+
+    #[cfg(feature = "xml_validation")]
+    pub fn id_span(&self) -> Span {
+        self.attr
+            .args
+            .iter()
+            .find_map(|arg| match arg {
+                FieldAttributeArg::Id(_, span) => Some(*span),
+                _ => None,
+            })
+            .unwrap_or_else(|| self.ident.span())
+    }
+
+    pub fn is_internal(&self) -> bool {
+        self.attr
+            .args
+            .iter()
+            .any(|arg| matches!(arg, FieldAttributeArg::Internal(true)))
+    }
+
+    pub fn is_optional(&self) -> bool {
+        self.attr
+            .args
+            .iter()
+            .any(|arg| matches!(arg, FieldAttributeArg::Optional(true)))
+    }
+}
+
 fn parse_field_attr_args(attr: &Attribute) -> Result<Vec<FieldAttributeArg>, Error> {
     let mut field_attribute_args = Vec::new();
     match &attr.meta {
@@ -189,8 +246,24 @@ fn parse_field(field: &Field) -> Result<Option<AttributedField>, Error> {
     }
 
     if let Some(attr) = attr {
+        if attr
+            .args
+            .iter()
+            .any(|arg| matches!(arg, FieldAttributeArg::Internal(true)))
+            && attr
+                .args
+                .iter()
+                .any(|arg| matches!(arg, FieldAttributeArg::Optional(true)))
+        {
+            return Err(Error::new(
+                field.span(),
+                "optional template children cannot be internal children",
+            ));
+        }
+
         Ok(Some(AttributedField {
             ident: ident.clone(),
+            ty: field.ty.clone(),
             attr,
         }))
     } else {
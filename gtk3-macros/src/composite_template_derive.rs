@@ -1,16 +1,23 @@
 // Take a look at the license at the top of the repository in the LICENSE file.
 
+#[cfg(feature = "xml_validation")]
+use std::collections::HashMap;
+
 use proc_macro2::Span;
 use proc_macro2::TokenStream;
+#[cfg(feature = "xml_validation")]
+use quick_xml::name::QName;
 use quote::quote;
+#[cfg(feature = "xml_validation")]
+use quote::quote_spanned;
 use syn::Data;
-
-use std::string::ToString;
+#[cfg(feature = "xml_validation")]
+use syn::spanned::Spanned;
 
 use crate::attribute_parser::*;
 use crate::util::*;
 
-fn gen_set_template(source: TemplateSource) -> TokenStream {
+fn gen_set_template(source: &TemplateSource) -> TokenStream {
     match source {
         TemplateSource::File(file) => quote! {
             let t = include_bytes!(#file);
@@ -20,33 +27,289 @@ fn gen_set_template(source: TemplateSource) -> TokenStream {
             klass.set_template_from_resource(&#resource);
         },
         TemplateSource::String(template) => quote! {
-            klass.set_template(&#template);
+            klass.set_template(#template.as_bytes());
         },
     }
 }
 
-fn gen_template_child_bindings(fields: &syn::Fields) -> TokenStream {
+#[cfg(feature = "xml_validation")]
+struct TemplateXml {
+    class: Option<String>,
+    // Maps the ids of the objects to their class.
+    objects: HashMap<String, String>,
+}
+
+// Loads the template XML, if it is available at compile time.
+#[cfg(feature = "xml_validation")]
+fn load_template_xml(source: &TemplateSource) -> Option<String> {
+    match source {
+        TemplateSource::File(file) => {
+            // Like `include_bytes!`, resolve the path relative to the invoking file.
+            let path = proc_macro::Span::call_site()
+                .local_file()?
+                .parent()?
+                .join(file);
+            // Reading errors are reported by `include_bytes!`.
+            std::fs::read_to_string(path).ok()
+        }
+        TemplateSource::Resource(_) => None,
+        TemplateSource::String(template) => Some(template.clone()),
+    }
+}
+
+#[cfg(feature = "xml_validation")]
+fn parse_template_xml(xml: &str) -> Result<TemplateXml, String> {
+    use quick_xml::events::Event;
+
+    let mut reader = quick_xml::Reader::from_str(xml);
+    let mut template = TemplateXml {
+        class: None,
+        objects: HashMap::new(),
+    };
+
+    loop {
+        let elem = match reader.read_event() {
+            Ok(Event::Start(e)) | Ok(Event::Empty(e)) => e,
+            Ok(Event::Eof) => break,
+            Ok(_) => continue,
+            Err(e) => {
+                return Err(format!(
+                    "Failed reading template XML at position {}: {:?}",
+                    reader.buffer_position(),
+                    e
+                ));
+            }
+        };
+
+        let attribute = |name: &[u8]| {
+            elem.attributes()
+                .filter_map(|a| a.ok())
+                .find(|a| a.key == QName(name))
+                .and_then(|a| String::from_utf8(a.value.into_owned()).ok())
+        };
+
+        let name = elem.name();
+        if name == QName(b"template") {
+            template.class = attribute(b"class");
+        } else if name == QName(b"object")
+            && let (Some(id), Some(class)) = (attribute(b"id"), attribute(b"class"))
+        {
+            template.objects.insert(id, class);
+        }
+    }
+
+    Ok(template)
+}
+
+// Returns `T` for a field of type `TemplateChild<T>`.
+#[cfg(feature = "xml_validation")]
+fn template_child_type(ty: &syn::Type) -> Option<&syn::Type> {
+    let syn::Type::Path(path) = ty else {
+        return None;
+    };
+    let segment = path.path.segments.last()?;
+    if segment.ident != "TemplateChild" {
+        return None;
+    }
+    let syn::PathArguments::AngleBracketed(args) = &segment.arguments else {
+        return None;
+    };
+    match args.args.first()? {
+        syn::GenericArgument::Type(ty) => Some(ty),
+        _ => None,
+    }
+}
+
+// Checks that a `TemplateChild<T>` can hold an object of the GTK class
+// `GtkName`. `Name` is resolved in the `gtk` crate through a glob import, which
+// shadows a fallback type of the same name declared in an outer block: if the
+// `gtk` crate has no such type, the fallback skips the check. The type of the
+// field is resolved outside of both, so the glob import doesn't change it.
+#[cfg(feature = "xml_validation")]
+fn gen_template_child_class_check(
+    id: &str,
+    class: &str,
+    class_ident: &syn::Ident,
+    ty: &syn::Type,
+) -> TokenStream {
     let crate_ident = crate_ident_new();
-    let attributed_fields = match parse_fields(fields) {
-        Ok(fields) => fields,
-        Err(err) => return err.into_compile_error(),
+    let message = format!(
+        "Template child `{}` is a `{}`, which can't be stored in a `TemplateChild<{{Self}}>`",
+        id.replace('{', "{{").replace('}', "}}"),
+        class.replace('{', "{{").replace('}', "}}"),
+    );
+
+    let check = quote_spanned! { ty.span() =>
+        let _ = #class_ident::template_child_is_a::<TemplateChildType>;
+    };
+    quote! {
+        const _: () = {
+            type TemplateChildType = #ty;
+            const _: () = {
+                #[allow(dead_code)]
+                struct #class_ident;
+                #[allow(dead_code)]
+                impl #class_ident {
+                    fn template_child_is_a<T>() {}
+                }
+                #[diagnostic::on_unimplemented(message = #message)]
+                trait CanHold<C> {}
+                impl<C, T> CanHold<C> for T
+                where
+                    C: #crate_ident::glib::prelude::ObjectType,
+                    T: #crate_ident::glib::prelude::IsA<C>,
+                {
+                }
+                #[allow(dead_code)]
+                trait TemplateClass: #crate_ident::glib::prelude::ObjectType + Sized {
+                    fn template_child_is_a<T: CanHold<Self>>() {}
+                }
+                impl<T: #crate_ident::glib::prelude::ObjectType> TemplateClass for T {}
+                const _: () = {
+                    #[allow(unused_imports)]
+                    use #crate_ident::*;
+                    #check
+                };
+            };
+        };
+    }
+}
+
+// Checks the `template_child` fields and the template class against the
+// template XML. Mismatches are reported as compile errors.
+#[cfg(feature = "xml_validation")]
+fn check_template_xml(
+    input: &syn::DeriveInput,
+    source: &TemplateSource,
+    fields: &[AttributedField],
+) -> Result<TokenStream, syn::Error> {
+    let Some(xml) = load_template_xml(source) else {
+        return Ok(TokenStream::new());
     };
+    let template_span = input
+        .attrs
+        .iter()
+        .find(|a| a.path().is_ident("template"))
+        .map(|a| a.span())
+        .unwrap_or_else(Span::call_site);
+    let template = parse_template_xml(&xml).map_err(|err| syn::Error::new(template_span, err))?;
+
+    let crate_ident = crate_ident_new();
+    let mut errors: Option<syn::Error> = None;
+    let mut push_error = |err: syn::Error| match &mut errors {
+        Some(errors) => errors.combine(err),
+        None => errors = Some(err),
+    };
+    let mut checks = Vec::new();
+
+    let Some(class) = &template.class else {
+        return Err(syn::Error::new(
+            template_span,
+            "Template XML has no `<template class=\"...\">` element",
+        ));
+    };
+    if input.generics.params.is_empty() {
+        let name = &input.ident;
+        let message = format!("Template class `{class}` does not match the `NAME` of `{name}`")
+            .replace('{', "{{")
+            .replace('}', "}}");
+        checks.push(quote_spanned! { template_span =>
+            const _: () = {
+                const fn str_eq(a: &str, b: &str) -> bool {
+                    let (a, b) = (a.as_bytes(), b.as_bytes());
+                    if a.len() != b.len() {
+                        return false;
+                    }
+                    let mut i = 0;
+                    while i < a.len() {
+                        if a[i] != b[i] {
+                            return false;
+                        }
+                        i += 1;
+                    }
+                    true
+                }
+                assert!(
+                    str_eq(
+                        <#name as #crate_ident::glib::subclass::types::ObjectSubclass>::NAME,
+                        #class,
+                    ),
+                    #message,
+                );
+            };
+        });
+    }
 
-    let recurse = attributed_fields.iter().map(|field| match field.attr.ty {
+    for field in fields {
+        let id = field.id();
+        let Some(object_class) = template.objects.get(&id) else {
+            if field.is_optional() {
+                continue;
+            }
+            push_error(syn::Error::new(
+                field.id_span(),
+                format!("Template child with id `{id}` not found in template XML"),
+            ));
+            continue;
+        };
+
+        // The type of the field may depend on the generic parameters, which
+        // the checks can't refer to.
+        if !input.generics.params.is_empty() {
+            continue;
+        }
+        let Some(class_ident) = object_class
+            .strip_prefix("Gtk")
+            .and_then(|class| syn::parse_str::<syn::Ident>(class).ok())
+        else {
+            continue;
+        };
+        let Some(ty) = template_child_type(&field.ty) else {
+            continue;
+        };
+        checks.push(gen_template_child_class_check(
+            &id,
+            object_class,
+            &class_ident,
+            ty,
+        ));
+    }
+
+    match errors {
+        Some(errors) => Err(errors),
+        None => Ok(quote! { #(#checks)* }),
+    }
+}
+
+fn gen_template_child_bindings(fields: &[AttributedField]) -> TokenStream {
+    let crate_ident = crate_ident_new();
+
+    let recurse = fields.iter().map(|field| match field.attr.ty {
         FieldAttributeType::TemplateChild => {
-            let mut value_id = &field.ident.to_string();
+            let value_id = field.id();
             let ident = &field.ident;
-            field.attr.args.iter().for_each(|arg| match arg {
-                FieldAttributeArg::Id(value) => {
-                    value_id = value;
-                }
-            });
 
-            quote! {
-                klass.bind_template_child_with_offset(
-                    &#value_id,
-                    #crate_ident::offset_of!(Self => #ident),
-                );
+            if field.is_optional() {
+                quote! {
+                    klass.bind_optional_template_child_with_offset(
+                        &#value_id,
+                        #crate_ident::offset_of!(Self => #ident),
+                    );
+                }
+            } else if field.is_internal() {
+                quote! {
+                    klass.bind_internal_template_child_with_offset(
+                        &#value_id,
+                        #crate_ident::offset_of!(Self => #ident),
+                    );
+                }
+            } else {
+                quote! {
+                    klass.bind_template_child_with_offset(
+                        &#value_id,
+                        #crate_ident::offset_of!(Self => #ident),
+                    );
+                }
             }
         }
     });
@@ -67,7 +330,7 @@ pub fn impl_composite_template(input: &syn::DeriveInput) -> TokenStream {
         ),
     };
 
-    let set_template = gen_set_template(source);
+    let set_template = gen_set_template(&source);
 
     let fields = match input.data {
         Data::Struct(ref s) => &s.fields,
@@ -80,7 +343,20 @@ pub fn impl_composite_template(input: &syn::DeriveInput) -> TokenStream {
         }
     };
 
-    let template_children = gen_template_child_bindings(fields);
+    let attributed_fields = match parse_fields(fields) {
+        Ok(fields) => fields,
+        Err(err) => return err.into_compile_error(),
+    };
+
+    #[cfg(feature = "xml_validation")]
+    let checks = match check_template_xml(input, &source, &attributed_fields) {
+        Ok(checks) => checks,
+        Err(err) => return err.into_compile_error(),
+    };
+    #[cfg(not(feature = "xml_validation"))]
+    let checks = TokenStream::new();
+
+    let template_children = gen_template_child_bindings(&attributed_fields);
 
     quote! {
         impl #crate_ident::subclass::widget::CompositeTemplate for #name {
@@ -92,5 +368,7 @@ pub fn impl_composite_template(input: &syn::DeriveInput) -> TokenStream {
                 }
             }
         }
+
+        #checks
     }
 }
@@ -2,10 +2,13 @@
 
 mod attribute_parser;
 mod composite_template_derive;
+mod template_callbacks_attribute;
+mod tree_row_derive;
 mod util;
+mod widget_snapshot_macro;
 
 use proc_macro::TokenStream;
-use syn::{DeriveInput, parse_macro_input};
+use syn::{DeriveInput, ItemImpl, parse_macro_input};
 
 /// Derive macro for using a composite template in a widget.
 ///
@@ -13,7 +16,22 @@ use syn::{DeriveInput, parse_macro_input};
 /// from;  it can be a `file`, a `resource`, or a `string`.
 ///
 /// The `template_child` attribute is used to mark all internal widgets
-/// we need to have programmatic access to.
+/// we need to have programmatic access to. It accepts the following
+/// optional arguments:
+///
+/// - `id = "..."`: the id of the object in the template, if it is not the
+///   same as the field name.
+/// - `internal = true`: the child can be referred to with
+///   `<child internal-child="...">` in the UI definitions using the widget.
+/// - `optional = true`: the child does not have to be declared in the
+///   template. It must be a widget, and it stays unbound if the template
+///   does not contain it; use [`TemplateChild::try_get`] to access it.
+///
+/// With the `xml_validation` feature, templates given as a `file` or a
+/// `string` are checked at compile time: the id of every `template_child`
+/// that is not optional must be declared in the template, the `<template>`
+/// class must match the [`NAME`] of the subclass, and the GTK class of each
+/// child object must be convertible to the type of its `TemplateChild`.
 ///
 /// # Example
 ///
@@ -25,6 +43,8 @@ use syn::{DeriveInput, parse_macro_input};
 /// [`instance_init`] function.
 ///
 /// [`ObjectSubclass`]: ../glib/subclass/types/trait.ObjectSubclass.html
+/// [`NAME`]: ../glib/subclass/types/trait.ObjectSubclass.html#associatedconstant.NAME
+/// [`TemplateChild::try_get`]: ../gtk/subclass/widget/struct.TemplateChild.html#method.try_get
 /// [`bind_template`]: ../gtk/subclass/widget/trait.CompositeTemplate.html#tymethod.bind_template
 /// [`class_init`]: ../glib/subclass/types/trait.ObjectSubclass.html#method.class_init
 /// [`init_template`]: ../gtk/prelude/trait.InitializingWidgetExt.html#tymethod.init_template
@@ -43,7 +63,7 @@ use syn::{DeriveInput, parse_macro_input};
 ///     pub struct MyWidget {
 ///         #[template_child]
 ///         pub label: TemplateChild<gtk::Label>,
-///         #[template_child(id = "my_button_id")]
+///         #[template_child(id = "my_button")]
 ///         pub button: TemplateChild<gtk::Button>,
 ///     }
 ///
@@ -85,3 +105,213 @@ pub fn composite_template_derive(input: TokenStream) -> TokenStream {
     let generated = composite_template_derive::impl_composite_template(&input);
     generated.into()
 }
+
+/// Derive macro for describing the columns of a `ListStore` or `TreeStore`
+/// with a struct.
+///
+/// Every named field becomes a column, in declaration order, and must have
+/// a type that can be stored in a [`glib::Value`]. The macro implements
+/// [`TreeRow`] for the struct and adds one typed [`TreeColumn`] constant
+/// per field, named after the field in uppercase, with the same visibility
+/// as the struct.
+///
+/// The constants can be passed to the typed helpers of the stores and
+/// models, like `set_field` and `field`, and converted into the `i32`
+/// column indices expected by [`TreeViewColumn::with_attributes`].
+///
+/// [`glib::Value`]: ../glib/value/struct.Value.html
+/// [`TreeRow`]: ../gtk/trait.TreeRow.html
+/// [`TreeColumn`]: ../gtk/struct.TreeColumn.html
+/// [`TreeViewColumn::with_attributes`]: ../gtk/struct.TreeViewColumn.html#method.with_attributes
+///
+/// # Example
+///
+/// ```no_run
+/// use gtk::prelude::*;
+///
+/// #[derive(gtk::TreeRow)]
+/// struct Task {
+///     title: String,
+///     done: bool,
+///     priority: u32,
+/// }
+///
+/// let store = gtk::ListStore::new(&Task::column_types());
+/// let iter = store.insert_row(
+///     None,
+///     &Task {
+///         title: "Write the docs".to_owned(),
+///         done: false,
+///         priority: 2,
+///     },
+/// );
+/// store.set_field(&iter, Task::DONE, &true);
+///
+/// let task: Task = store.row(&iter);
+/// assert!(task.done);
+/// assert_eq!(store.field(&iter, Task::PRIORITY), 2);
+///
+/// let cell = gtk::CellRendererText::new();
+/// let column = gtk::TreeViewColumn::with_attributes("Title", &cell, &[("text", Task::TITLE.into())]);
+/// ```
+#[proc_macro_derive(TreeRow)]
+pub fn tree_row_derive(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    tree_row_derive::impl_tree_row(&input)
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
+
+/// Attribute macro for binding signal handlers of a composite template to
+/// Rust methods.
+///
+/// It is used on an `impl` block of the [`ObjectSubclass`] implementing
+/// [`CompositeTemplate`]. Every method marked with `#[template_callback]`
+/// is registered under its name, or under the name given with
+/// `#[template_callback(name = "...")]`, and is called when a signal with
+/// the matching `handler` attribute in the template is emitted.
+///
+/// Methods can either take `&self`, which is the private struct of the
+/// template instance, or no receiver at all. The other arguments are
+/// converted from the signal handler arguments with [`Value::get`], in the
+/// order a C handler receives them: the emitting object, the signal
+/// arguments, and the user data object, which is the object given with the
+/// `object` attribute of the `<signal>` element or the template instance
+/// itself. For handlers declared with `swapped="yes"` the emitting object
+/// and the user data object trade places. Trailing arguments that are not
+/// needed can be left out. The return value, if any, is converted with
+/// [`ToValue`].
+///
+/// The handlers are bound by calling [`bind_template_callbacks`] in the
+/// [`class_init`] function, after the template has been set.
+///
+/// [`ObjectSubclass`]: ../glib/subclass/types/trait.ObjectSubclass.html
+/// [`CompositeTemplate`]: ../gtk/subclass/widget/trait.CompositeTemplate.html
+/// [`Value::get`]: ../glib/value/struct.Value.html#method.get
+/// [`ToValue`]: ../glib/value/trait.ToValue.html
+/// [`bind_template_callbacks`]: ../gtk/subclass/widget/trait.CompositeTemplateCallbacks.html#method.bind_template_callbacks
+/// [`class_init`]: ../glib/subclass/types/trait.ObjectSubclass.html#method.class_init
+///
+/// # Example
+///
+/// ```no_run
+/// use gtk::prelude::*;
+/// use gtk::glib;
+/// use gtk::subclass::prelude::*;
+///
+/// mod imp {
+///     use super::*;
+///
+///     #[derive(Debug, Default, gtk::CompositeTemplate)]
+///     #[template(string = r#"
+///     <interface>
+///       <template class="MyWidget" parent="GtkBox">
+///         <child>
+///           <object class="GtkButton" id="button">
+///             <property name="label">Save</property>
+///             <signal name="clicked" handler="on_save_clicked"/>
+///           </object>
+///         </child>
+///         <child>
+///           <object class="GtkEntry">
+///             <signal name="activate" handler="entry_activated" swapped="yes"/>
+///           </object>
+///         </child>
+///       </template>
+///     </interface>
+///     "#)]
+///     pub struct MyWidget {
+///         #[template_child]
+///         pub button: TemplateChild<gtk::Button>,
+///     }
+///
+///     #[glib::object_subclass]
+///     impl ObjectSubclass for MyWidget {
+///         const NAME: &'static str = "MyWidget";
+///         type Type = super::MyWidget;
+///         type ParentType = gtk::Box;
+///
+///         fn class_init(klass: &mut Self::Class) {
+///             Self::bind_template(klass);
+///             Self::bind_template_callbacks(klass);
+///         }
+///
+///         fn instance_init(obj: &glib::subclass::InitializingObject<Self>) {
+///             obj.init_template();
+///         }
+///     }
+///
+///     #[gtk::template_callbacks]
+///     impl MyWidget {
+///         #[template_callback]
+///         fn on_save_clicked(&self, button: &gtk::Button) {
+///             println!("{:?} clicked", button.label());
+///         }
+///
+///         #[template_callback(name = "entry_activated")]
+///         fn activated(widget: &super::MyWidget, entry: &gtk::Entry) {
+///             println!("{} activated in {widget:?}", entry.text());
+///         }
+///     }
+///
+///     impl ObjectImpl for MyWidget {}
+///     impl WidgetImpl for MyWidget {}
+///     impl ContainerImpl for MyWidget {}
+///     impl BoxImpl for MyWidget {}
+/// }
+///
+/// glib::wrapper! {
+///     pub struct MyWidget(ObjectSubclass<imp::MyWidget>) @extends gtk::Widget, gtk::Container, gtk::Box;
+/// }
+/// # fn main() {}
+/// ```
+#[proc_macro_attribute]
+pub fn template_callbacks(attr: TokenStream, item: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(item as ItemImpl);
+    template_callbacks_attribute::impl_template_callbacks(input, attr.into()).into()
+}
+
+/// Macro for asserting that a widget looks like a stored reference image.
+///
+/// `assert_widget_snapshot!(widget, "name")` renders the widget offscreen
+/// and compares it with `tests/snapshots/name.png` in the directory of the
+/// crate's `Cargo.toml`. It accepts the following optional arguments:
+///
+/// - `width = ...`, `height = ...`: the size to render the widget at. It
+///   defaults to the natural size of the widget.
+/// - `scale = ...`: the scale factor to render with, `1` by default.
+/// - `tolerance = ...`: how much each color channel of a pixel may differ
+///   from the reference, `0` by default.
+/// - `dir = ...`: the directory containing the reference images, instead
+///   of `tests/snapshots`.
+///
+/// When the rendering doesn't match, the test fails and the rendering and
+/// an image highlighting the differing pixels are written next to the
+/// reference as `name.actual.png` and `name.diff.png`. Run the tests with
+/// the `GTK_SNAPSHOT_BLESS` environment variable set to write the current
+/// renderings as the new references instead.
+///
+/// This requires the `png` and `test-utils` features of `gtk`, and has to
+/// be called from the GTK thread, for example inside [`gtk::test::run`].
+///
+/// [`gtk::test::run`]: ../gtk/test/fn.run.html
+///
+/// # Example
+///
+/// ```no_run
+/// use gtk::prelude::*;
+///
+/// #[test]
+/// fn chart_renders() {
+///     gtk::test::run(|| {
+///         let label = gtk::Label::new(Some("42 %"));
+///         gtk::assert_widget_snapshot!(label, "chart", width = 200, height = 100, tolerance = 2);
+///     });
+/// }
+/// # fn main() {}
+/// ```
+#[proc_macro]
+pub fn assert_widget_snapshot(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as widget_snapshot_macro::SnapshotInput);
+    widget_snapshot_macro::impl_widget_snapshot(input).into()
+}
@@ -0,0 +1,233 @@
+// Take a look at the license at the top of the repository in the LICENSE file.
+
+use proc_macro2::{Span, TokenStream};
+use quote::{format_ident, quote, quote_spanned};
+use syn::spanned::Spanned;
+use syn::{
+    Error, FnArg, ImplItem, ImplItemFn, ItemImpl, LitStr, Meta, ReceiverKind, ReturnType, Token,
+};
+
+use crate::util::*;
+
+mod kw {
+    syn::custom_keyword!(name);
+}
+
+struct CallbackAttributeArg {
+    name: LitStr,
+}
+
+impl syn::parse::Parse for CallbackAttributeArg {
+    fn parse(input: syn::parse::ParseStream<'_>) -> syn::Result<Self> {
+        let _: kw::name = input.parse()?;
+        let _: Token![=] = input.parse()?;
+        let name: LitStr = input.parse()?;
+        Ok(Self { name })
+    }
+}
+
+// Removes the `#[template_callback]` attribute of a method and returns the name
+// of the handler it is bound to, if it was present.
+fn take_callback_attr(method: &mut ImplItemFn) -> Result<Option<String>, Error> {
+    let Some(index) = method
+        .attrs
+        .iter()
+        .position(|a| a.path().is_ident("template_callback"))
+    else {
+        return Ok(None);
+    };
+    let attr = method.attrs.remove(index);
+
+    if method
+        .attrs
+        .iter()
+        .any(|a| a.path().is_ident("template_callback"))
+    {
+        return Err(Error::new(
+            method.sig.ident.span(),
+            "multiple `template_callback` attributes on the same method are not supported",
+        ));
+    }
+
+    match &attr.meta {
+        Meta::Path(_) => Ok(Some(method.sig.ident.to_string())),
+        Meta::List(list) => {
+            let arg: CallbackAttributeArg = list.parse_args()?;
+            Ok(Some(arg.name.value()))
+        }
+        meta => Err(Error::new(
+            meta.span(),
+            "invalid attribute argument type, expected `name = value` or nothing",
+        )),
+    }
+}
+
+fn gen_callback(method: &ImplItemFn, name: &str) -> Result<TokenStream, Error> {
+    let crate_ident = crate_ident_new();
+    let sig = &method.sig;
+    let fn_ident = &sig.ident;
+
+    if let Some(asyncness) = &sig.asyncness {
+        return Err(Error::new(
+            asyncness.span(),
+            "async template callbacks are not supported",
+        ));
+    }
+    if !sig.generics.params.is_empty() {
+        return Err(Error::new(
+            sig.generics.span(),
+            "template callbacks cannot be generic",
+        ));
+    }
+
+    let mut has_receiver = false;
+    let mut arg_idents = Vec::new();
+    let mut conversions = Vec::new();
+
+    for input in &sig.inputs {
+        match input {
+            FnArg::Receiver(receiver) => {
+                if !matches!(receiver.kind, ReceiverKind::Reference(_, _, None)) {
+                    return Err(Error::new(
+                        receiver.span(),
+                        "template callbacks can only take `&self` as receiver",
+                    ));
+                }
+                has_receiver = true;
+            }
+            FnArg::Typed(pat_type) => {
+                let index = arg_idents.len();
+                let arg_ident = format_ident!("arg{}", index);
+                let ty = &pat_type.ty;
+
+                conversions.push(quote_spanned! { ty.span() =>
+                    let #arg_ident = match values.get(#index) {
+                        ::std::option::Option::Some(value) => {
+                            match #crate_ident::glib::Value::get::<#ty>(value) {
+                                ::std::result::Result::Ok(value) => value,
+                                ::std::result::Result::Err(err) => ::std::panic!(
+                                    "Wrong type for argument {} of template callback '{}': {:?}",
+                                    #index,
+                                    #name,
+                                    err,
+                                ),
+                            }
+                        }
+                        ::std::option::Option::None => ::std::panic!(
+                            "Template callback '{}' expects at least {} arguments, got {}",
+                            #name,
+                            #index + 1,
+                            values.len(),
+                        ),
+                    };
+                });
+                arg_idents.push(arg_ident);
+            }
+        }
+    }
+
+    let this_ident = if has_receiver {
+        format_ident!("this")
+    } else {
+        format_ident!("_this")
+    };
+    let values_ident = if arg_idents.is_empty() {
+        format_ident!("_values")
+    } else {
+        format_ident!("values")
+    };
+
+    let call = if has_receiver {
+        quote! {
+            #crate_ident::glib::subclass::prelude::ObjectSubclassIsExt::imp(this)
+                .#fn_ident(#(#arg_idents),*)
+        }
+    } else {
+        quote! { Self::#fn_ident(#(#arg_idents),*) }
+    };
+
+    let body = match &sig.output {
+        ReturnType::Default => quote! {
+            #call;
+            ::std::option::Option::None
+        },
+        ReturnType::Type(_, _) => quote! {
+            let ret = #call;
+            ::std::option::Option::Some(#crate_ident::glib::value::ToValue::to_value(&ret))
+        },
+    };
+
+    Ok(quote! {
+        (
+            #name,
+            |#this_ident: &<Self as #crate_ident::glib::subclass::types::ObjectSubclass>::Type,
+             #values_ident: &[#crate_ident::glib::Value]|
+             -> ::std::option::Option<#crate_ident::glib::Value> {
+                #(#conversions)*
+                #body
+            },
+        )
+    })
+}
+
+pub fn impl_template_callbacks(mut input: ItemImpl, args: TokenStream) -> TokenStream {
+    if !args.is_empty() {
+        return Error::new(
+            args.span(),
+            "`template_callbacks` does not take any arguments",
+        )
+        .into_compile_error();
+    }
+    if let Some((path, _)) = &input.trait_ {
+        return Error::new(
+            path.span(),
+            "`template_callbacks` must be used on an inherent impl block",
+        )
+        .into_compile_error();
+    }
+
+    let crate_ident = crate_ident_new();
+    let mut callbacks = Vec::new();
+    let mut errors = Vec::new();
+
+    for item in input.items.iter_mut() {
+        let ImplItem::Fn(method) = item else {
+            continue;
+        };
+        match take_callback_attr(method)
+            .and_then(|name| name.map(|name| gen_callback(method, &name)).transpose())
+        {
+            Ok(Some(callback)) => callbacks.push(callback),
+            Ok(None) => (),
+            Err(err) => errors.push(err.into_compile_error()),
+        }
+    }
+
+    if callbacks.is_empty() && errors.is_empty() {
+        errors.push(
+            Error::new(
+                Span::call_site(),
+                "`template_callbacks` requires at least one `#[template_callback]` method",
+            )
+            .into_compile_error(),
+        );
+    }
+
+    let self_ty = &input.self_ty;
+    let (impl_generics, _, where_clause) = input.generics.split_for_impl();
+
+    quote! {
+        #input
+
+        impl #impl_generics #crate_ident::subclass::widget::TemplateCallbacks for #self_ty #where_clause {
+            const CALLBACKS: &'static [(
+                &'static str,
+                #crate_ident::subclass::widget::TemplateCallback<
+                    <Self as #crate_ident::glib::subclass::types::ObjectSubclass>::Type,
+                >,
+            )] = &[#(#callbacks),*];
+        }
+
+        #(#errors)*
+    }
+}
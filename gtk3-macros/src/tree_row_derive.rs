@@ -0,0 +1,104 @@
+// Take a look at the license at the top of the repository in the LICENSE file.
+
+use proc_macro2::TokenStream;
+use quote::{format_ident, quote};
+use syn::{Data, DeriveInput, Error, Fields};
+
+use crate::util::*;
+
+pub fn impl_tree_row(input: &DeriveInput) -> syn::Result<TokenStream> {
+    let crate_ident = crate_ident_new();
+    let name = &input.ident;
+    let vis = &input.vis;
+
+    if !input.generics.params.is_empty() {
+        return Err(Error::new_spanned(
+            &input.generics,
+            "TreeRow can't be derived for generic types",
+        ));
+    }
+
+    let fields = match &input.data {
+        Data::Struct(data) => match &data.fields {
+            Fields::Named(fields) => &fields.named,
+            _ => {
+                return Err(Error::new_spanned(
+                    input,
+                    "TreeRow can only be derived for structs with named fields",
+                ));
+            }
+        },
+        _ => {
+            return Err(Error::new_spanned(
+                input,
+                "TreeRow can only be derived for structs",
+            ));
+        }
+    };
+
+    let mut constants = Vec::new();
+    let mut column_types = Vec::new();
+    let mut to_values = Vec::new();
+    let mut from_model = Vec::new();
+    for (index, field) in fields.iter().enumerate() {
+        let ident = field.ident.as_ref().unwrap();
+        let ty = &field.ty;
+        let column = index as u32;
+        let column_i32 = index as i32;
+        let constant = format_ident!(
+            "{}",
+            ident.to_string().trim_start_matches("r#").to_uppercase(),
+            span = ident.span()
+        );
+        let doc = format!("The column holding [`{name}::{ident}`].");
+
+        constants.push(quote! {
+            #[doc = #doc]
+            #vis const #constant: #crate_ident::TreeColumn<Self, #ty> =
+                #crate_ident::TreeColumn::new(#column);
+        });
+        column_types.push(quote! {
+            <#ty as #crate_ident::glib::types::StaticType>::static_type()
+        });
+        to_values.push(quote! {
+            #crate_ident::glib::value::ToValue::to_value(&self.#ident)
+        });
+        from_model.push(quote! {
+            #ident: {
+                let value = #crate_ident::prelude::TreeModelExt::value(model, iter, #column_i32);
+                value.get::<#ty>().unwrap_or_else(|err| {
+                    ::std::panic!(
+                        "column {} of type {} can't be read into `{}::{}`: {}",
+                        #column,
+                        value.type_(),
+                        ::std::stringify!(#name),
+                        ::std::stringify!(#ident),
+                        err,
+                    )
+                })
+            }
+        });
+    }
+
+    Ok(quote! {
+        impl #name {
+            #(#constants)*
+        }
+
+        impl #crate_ident::TreeRow for #name {
+            fn column_types() -> ::std::vec::Vec<#crate_ident::glib::Type> {
+                ::std::vec![#(#column_types),*]
+            }
+
+            fn to_values(&self) -> ::std::vec::Vec<#crate_ident::glib::Value> {
+                ::std::vec![#(#to_values),*]
+            }
+
+            fn from_model(model: &#crate_ident::TreeModel, iter: &#crate_ident::TreeIter) -> Self {
+                Self {
+                    #(#from_model,)*
+                }
+            }
+        }
+    })
+}
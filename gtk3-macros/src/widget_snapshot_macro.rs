@@ -0,0 +1,86 @@
+// Take a look at the license at the top of the repository in the LICENSE file.
+
+use proc_macro2::TokenStream;
+use quote::quote;
+use syn::parse::{Parse, ParseStream};
+use syn::{Error, Expr, Ident, Token};
+
+use crate::util::*;
+
+const OPTIONS: &[&str] = &["width", "height", "scale", "tolerance", "dir"];
+
+pub struct SnapshotInput {
+    widget: Expr,
+    name: Expr,
+    options: Vec<(Ident, Expr)>,
+}
+
+impl Parse for SnapshotInput {
+    fn parse(input: ParseStream<'_>) -> syn::Result<Self> {
+        let widget = input.parse()?;
+        let _: Token![,] = input.parse()?;
+        let name = input.parse()?;
+
+        let mut options: Vec<(Ident, Expr)> = Vec::new();
+        while !input.is_empty() {
+            let _: Token![,] = input.parse()?;
+            if input.is_empty() {
+                break;
+            }
+            let key: Ident = input.parse()?;
+            if !OPTIONS.iter().any(|option| key == *option) {
+                return Err(Error::new(
+                    key.span(),
+                    format!("unknown option `{key}`, expected one of {OPTIONS:?}"),
+                ));
+            }
+            if options.iter().any(|(other, _)| *other == key) {
+                return Err(Error::new(key.span(), format!("duplicate option `{key}`")));
+            }
+            let _: Token![=] = input.parse()?;
+            let value: Expr = input.parse()?;
+            options.push((key, value));
+        }
+
+        Ok(Self {
+            widget,
+            name,
+            options,
+        })
+    }
+}
+
+pub fn impl_widget_snapshot(input: SnapshotInput) -> TokenStream {
+    let crate_ident = crate_ident_new();
+    let SnapshotInput {
+        widget,
+        name,
+        options,
+    } = input;
+
+    let mut dir = quote! {
+        ::std::path::Path::new(::std::env!("CARGO_MANIFEST_DIR"))
+            .join("tests")
+            .join("snapshots")
+    };
+    let mut fields = Vec::new();
+    for (key, value) in options {
+        match key.to_string().as_str() {
+            "dir" => dir = quote! { #value },
+            "width" | "height" => fields.push(quote! { #key: ::std::option::Option::Some(#value) }),
+            _ => fields.push(quote! { #key: #value }),
+        }
+    }
+
+    quote! {
+        #crate_ident::test::assert_widget_snapshot(
+            &#widget,
+            #name,
+            #dir,
+            &#crate_ident::test::SnapshotOptions {
+                #(#fields,)*
+                ..::std::default::Default::default()
+            },
+        )
+    }
+}
@@ -0,0 +1,154 @@
+// Take a look at the license at the top of the repository in the LICENSE file.
+
+use std::cell::Cell;
+
+use gtk::glib;
+use gtk::prelude::*;
+use gtk::subclass::prelude::*;
+
+thread_local! {
+    static DROPPED_PARSERS: Cell<usize> = const { Cell::new(0) };
+}
+
+#[derive(Default)]
+struct ItemsParser {
+    items: Vec<String>,
+}
+
+impl BuildableParser for ItemsParser {
+    fn start_element(
+        &mut self,
+        element_name: &str,
+        attributes: &[(&str, &str)],
+    ) -> Result<(), glib::Error> {
+        match element_name {
+            "items" => Ok(()),
+            "item" => {
+                let name = attributes
+                    .iter()
+                    .find(|(name, _)| *name == "name")
+                    .map_or("", |(_, value)| value);
+                self.items.push(name.to_owned());
+                Ok(())
+            }
+            _ => Err(glib::Error::new(
+                gtk::BuilderError::InvalidTag,
+                &format!("Unexpected <{element_name}>"),
+            )),
+        }
+    }
+}
+
+impl Drop for ItemsParser {
+    fn drop(&mut self) {
+        DROPPED_PARSERS.with(|dropped| dropped.set(dropped.get() + 1));
+    }
+}
+
+mod imp {
+    use std::cell::RefCell;
+
+    use super::*;
+
+    #[derive(Debug, Default)]
+    pub struct ItemList {
+        pub items: RefCell<Vec<String>>,
+    }
+
+    #[glib::object_subclass]
+    impl ObjectSubclass for ItemList {
+        const NAME: &'static str = "ItemList";
+        type Type = super::ItemList;
+        type Interfaces = (gtk::Buildable,);
+    }
+
+    impl ObjectImpl for ItemList {}
+
+    impl BuildableImpl for ItemList {
+        fn custom_tag_start(
+            &self,
+            _builder: &gtk::Builder,
+            _child: Option<&glib::Object>,
+            tagname: &str,
+        ) -> Option<Box<dyn BuildableParser>> {
+            (tagname == "items")
+                .then(|| Box::new(ItemsParser::default()) as Box<dyn BuildableParser>)
+        }
+
+        fn custom_finished(
+            &self,
+            _builder: &gtk::Builder,
+            _child: Option<&glib::Object>,
+            _tagname: &str,
+            parser: Box<dyn BuildableParser>,
+        ) {
+            let parser = parser.downcast::<ItemsParser>().ok().unwrap();
+            self.items.borrow_mut().extend(parser.items.iter().cloned());
+        }
+    }
+}
+
+glib::wrapper! {
+    pub struct ItemList(ObjectSubclass<imp::ItemList>) @implements gtk::Buildable;
+}
+
+fn dropped_parsers() -> usize {
+    DROPPED_PARSERS.with(Cell::get)
+}
+
+#[test]
+fn custom_tag() {
+    gtk::init().expect("Tests failed to initialize gtk");
+    ItemList::static_type();
+
+    let builder = gtk::Builder::from_string(
+        r#"
+        <interface>
+          <object class="ItemList" id="list">
+            <items>
+              <item name="first"/>
+              <item name="second"/>
+            </items>
+          </object>
+        </interface>
+        "#,
+    );
+    let list = builder.object::<ItemList>("list").unwrap();
+    assert_eq!(*list.imp().items.borrow(), ["first", "second"]);
+    assert_eq!(dropped_parsers(), 1);
+
+    // The content of the custom tag is rejected by the parser
+    let builder = gtk::Builder::new();
+    let err = builder
+        .add_from_string(
+            r#"
+            <interface>
+              <object class="ItemList" id="list">
+                <items><unknown/></items>
+              </object>
+            </interface>
+            "#,
+        )
+        .unwrap_err();
+    assert!(err.message().contains("Unexpected <unknown>"), "{err}");
+    drop(builder);
+    assert_eq!(dropped_parsers(), 2);
+
+    // The XML is malformed after the custom tag, so `custom_finished` is never called
+    let builder = gtk::Builder::new();
+    builder
+        .add_from_string(
+            r#"
+            <interface>
+              <object class="ItemList" id="list">
+                <items><item name="first"/></items>
+              </object>
+            </interfac>
+            "#,
+        )
+        .unwrap_err();
+    let list = builder.object::<ItemList>("list");
+    assert!(list.is_none_or(|list| list.imp().items.borrow().is_empty()));
+    drop(builder);
+    assert_eq!(dropped_parsers(), 3);
+}
@@ -0,0 +1,36 @@
+use gtk::glib;
+use gtk::subclass::prelude::*;
+
+mod imp {
+    use super::*;
+
+    #[derive(Debug, Default, gtk::CompositeTemplate)]
+    #[template(string = r#"
+    <interface>
+      <template class="OtherWidget" parent="GtkBox"/>
+    </interface>
+    "#)]
+    pub struct MyWidget {}
+
+    #[glib::object_subclass]
+    impl ObjectSubclass for MyWidget {
+        const NAME: &'static str = "MyWidget";
+        type Type = super::MyWidget;
+        type ParentType = gtk::Box;
+
+        fn class_init(klass: &mut Self::Class) {
+            Self::bind_template(klass);
+        }
+    }
+
+    impl ObjectImpl for MyWidget {}
+    impl WidgetImpl for MyWidget {}
+    impl ContainerImpl for MyWidget {}
+    impl BoxImpl for MyWidget {}
+}
+
+glib::wrapper! {
+    pub struct MyWidget(ObjectSubclass<imp::MyWidget>) @extends gtk::Widget, gtk::Container, gtk::Box;
+}
+
+fn main() {}
@@ -0,0 +1,39 @@
+use gtk::glib;
+use gtk::subclass::prelude::*;
+
+mod imp {
+    use super::*;
+
+    #[derive(Debug, Default, gtk::CompositeTemplate)]
+    #[template(string = r#"
+    <interface>
+      <template class="MyWidget" parent="GtkBox">
+        <child>
+          <object class="GtkLabel" id="label"/>
+        </child>
+      </template>
+    </interface>
+    "#)]
+    pub struct MyWidget {
+        #[template_child]
+        pub button: TemplateChild<gtk::Button>,
+    }
+
+    #[glib::object_subclass]
+    impl ObjectSubclass for MyWidget {
+        const NAME: &'static str = "MyWidget";
+        type Type = super::MyWidget;
+        type ParentType = gtk::Box;
+    }
+
+    impl ObjectImpl for MyWidget {}
+    impl WidgetImpl for MyWidget {}
+    impl ContainerImpl for MyWidget {}
+    impl BoxImpl for MyWidget {}
+}
+
+glib::wrapper! {
+    pub struct MyWidget(ObjectSubclass<imp::MyWidget>) @extends gtk::Widget, gtk::Container, gtk::Box;
+}
+
+fn main() {}
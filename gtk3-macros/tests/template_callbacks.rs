@@ -0,0 +1,105 @@
+// Take a look at the license at the top of the repository in the LICENSE file.
+
+use gtk::glib;
+use gtk::prelude::*;
+use gtk::subclass::prelude::*;
+
+mod imp {
+    use std::cell::Cell;
+
+    use super::*;
+
+    #[derive(Debug, Default, gtk::CompositeTemplate)]
+    #[template(string = r#"
+    <interface>
+      <template class="CallbacksWidget" parent="GtkBox">
+        <child>
+          <object class="GtkButton" id="button">
+            <signal name="clicked" handler="on_clicked"/>
+            <signal name="mnemonic-activate" handler="on_mnemonic_activate"/>
+          </object>
+        </child>
+        <child>
+          <object class="GtkEntry" id="entry">
+            <signal name="activate" handler="entry_activated" swapped="yes"/>
+          </object>
+        </child>
+      </template>
+    </interface>
+    "#)]
+    pub struct CallbacksWidget {
+        #[template_child]
+        pub button: TemplateChild<gtk::Button>,
+        #[template_child]
+        pub entry: TemplateChild<gtk::Entry>,
+        pub clicked: Cell<u32>,
+        pub activated: Cell<u32>,
+    }
+
+    #[glib::object_subclass]
+    impl ObjectSubclass for CallbacksWidget {
+        const NAME: &'static str = "CallbacksWidget";
+        type Type = super::CallbacksWidget;
+        type ParentType = gtk::Box;
+
+        fn class_init(klass: &mut Self::Class) {
+            Self::bind_template(klass);
+            Self::bind_template_callbacks(klass);
+        }
+
+        fn instance_init(obj: &glib::subclass::InitializingObject<Self>) {
+            obj.init_template();
+        }
+    }
+
+    #[gtk::template_callbacks]
+    impl CallbacksWidget {
+        #[template_callback]
+        fn on_clicked(&self, _button: &gtk::Button) {
+            self.clicked.set(self.clicked.get() + 1);
+        }
+
+        #[template_callback]
+        fn on_mnemonic_activate(&self, _button: &gtk::Button, _group_cycling: bool) -> bool {
+            true
+        }
+
+        #[template_callback(name = "entry_activated")]
+        fn activated(widget: &super::CallbacksWidget, _entry: &gtk::Entry) {
+            let imp = widget.imp();
+            imp.activated.set(imp.activated.get() + 1);
+        }
+    }
+
+    impl ObjectImpl for CallbacksWidget {}
+    impl WidgetImpl for CallbacksWidget {}
+    impl ContainerImpl for CallbacksWidget {}
+    impl BoxImpl for CallbacksWidget {}
+}
+
+glib::wrapper! {
+    pub struct CallbacksWidget(ObjectSubclass<imp::CallbacksWidget>) @extends gtk::Widget, gtk::Container, gtk::Box;
+}
+
+#[test]
+fn template_callbacks() {
+    gtk::init().expect("Tests failed to initialize gtk");
+
+    let widget = glib::Object::new::<CallbacksWidget>();
+    let button = widget.imp().button.get();
+    let entry = widget.imp().entry.get();
+
+    button.clicked();
+    entry.activate();
+    assert!(button.mnemonic_activate(false));
+    assert_eq!(widget.imp().clicked.get(), 1);
+    assert_eq!(widget.imp().activated.get(), 1);
+
+    // Handlers of signals with a return value must still return a value once
+    // the template instance is gone.
+    unsafe { widget.destroy() };
+    drop(widget);
+    button.clicked();
+    button.mnemonic_activate(false);
+    entry.activate();
+}
@@ -0,0 +1,84 @@
+// Take a look at the license at the top of the repository in the LICENSE file.
+
+use gtk::glib;
+use gtk::prelude::*;
+use gtk::subclass::prelude::*;
+
+mod imp {
+    use super::*;
+
+    #[derive(Debug, Default, gtk::CompositeTemplate)]
+    #[template(string = r#"
+    <interface>
+      <template class="TemplateChildWidget" parent="GtkBox">
+        <child>
+          <object class="GtkLabel" id="label"/>
+        </child>
+        <child>
+          <object class="GtkButton" id="button"/>
+        </child>
+      </template>
+    </interface>
+    "#)]
+    pub struct TemplateChildWidget {
+        #[template_child(internal = true)]
+        pub label: TemplateChild<gtk::Label>,
+        #[template_child(optional = true)]
+        pub button: TemplateChild<gtk::Button>,
+        #[template_child(optional = true)]
+        pub missing: TemplateChild<gtk::Entry>,
+    }
+
+    #[glib::object_subclass]
+    impl ObjectSubclass for TemplateChildWidget {
+        const NAME: &'static str = "TemplateChildWidget";
+        type Type = super::TemplateChildWidget;
+        type ParentType = gtk::Box;
+
+        fn class_init(klass: &mut Self::Class) {
+            Self::bind_template(klass);
+        }
+
+        fn instance_init(obj: &glib::subclass::InitializingObject<Self>) {
+            obj.init_template();
+        }
+    }
+
+    impl ObjectImpl for TemplateChildWidget {}
+    impl WidgetImpl for TemplateChildWidget {}
+    impl ContainerImpl for TemplateChildWidget {}
+    impl BoxImpl for TemplateChildWidget {}
+}
+
+glib::wrapper! {
+    pub struct TemplateChildWidget(ObjectSubclass<imp::TemplateChildWidget>) @extends gtk::Widget, gtk::Container, gtk::Box;
+}
+
+#[test]
+fn template_child() {
+    gtk::init().expect("Tests failed to initialize gtk");
+    TemplateChildWidget::static_type();
+
+    // The internal child is configured by the UI definition using the widget
+    let builder = gtk::Builder::from_string(
+        r#"
+        <interface>
+          <object class="TemplateChildWidget" id="widget">
+            <child internal-child="label">
+              <object class="GtkLabel">
+                <property name="label">Internal</property>
+              </object>
+            </child>
+          </object>
+        </interface>
+        "#,
+    );
+    let widget = builder.object::<TemplateChildWidget>("widget").unwrap();
+    let imp = widget.imp();
+    assert_eq!(imp.label.label(), "Internal");
+
+    // Optional children are bound only if the template declares them
+    let button = imp.button.try_get().unwrap();
+    assert_eq!(button.parent().as_ref(), Some(widget.upcast_ref()));
+    assert!(imp.missing.try_get().is_none());
+}
@@ -0,0 +1,60 @@
+// Take a look at the license at the top of the repository in the LICENSE file.
+
+use gtk::glib;
+use gtk::prelude::*;
+
+#[derive(Debug, PartialEq, gtk::TreeRow)]
+struct Task {
+    title: String,
+    done: bool,
+    priority: u32,
+}
+
+impl Task {
+    fn new(title: &str, priority: u32) -> Self {
+        Self {
+            title: title.to_owned(),
+            done: false,
+            priority,
+        }
+    }
+}
+
+#[test]
+fn tree_row() {
+    gtk::init().expect("Tests failed to initialize gtk");
+
+    assert_eq!(
+        Task::column_types(),
+        [glib::Type::STRING, glib::Type::BOOL, glib::Type::U32]
+    );
+    assert_eq!(i32::from(Task::TITLE), 0);
+    assert_eq!(u32::from(Task::PRIORITY), 2);
+
+    let store = gtk::ListStore::new(&Task::column_types());
+    store.insert_row(None, &Task::new("b", 2));
+    let iter = store.insert_row(Some(0), &Task::new("a", 1));
+    assert_eq!(store.row::<Task>(&iter), Task::new("a", 1));
+    assert_eq!(store.iter_n_children(None), 2);
+
+    store.set_field(&iter, Task::DONE, &true);
+    assert!(store.field(&iter, Task::DONE));
+    store.set_row(&iter, &Task::new("c", 3));
+    assert_eq!(store.field(&iter, Task::TITLE), "c");
+    assert_eq!(store.field(&iter, Task::PRIORITY), 3);
+    assert!(!store.field(&iter, Task::DONE));
+
+    let store = gtk::TreeStore::new(&Task::column_types());
+    let parent = store.insert_row(None, None, &Task::new("parent", 1));
+    let child = store.insert_row(Some(&parent), None, &Task::new("child", 2));
+    store.set_field(&child, Task::DONE, &true);
+    assert_eq!(
+        store.row::<Task>(&child),
+        Task {
+            title: "child".to_owned(),
+            done: true,
+            priority: 2,
+        }
+    );
+    assert_eq!(store.iter_n_children(Some(&parent)), 1);
+}
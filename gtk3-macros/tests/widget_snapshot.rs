@@ -0,0 +1,135 @@
+// Take a look at the license at the top of the repository in the LICENSE file.
+
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+use gtk::prelude::*;
+
+// The directory of the snapshots written by `bless_child`
+const CHILD_DIR_ENV: &str = "GTK_RS_SNAPSHOT_TEST_DIR";
+
+// An 8×6 widget, red on the left half and blue on the right half, with `red` as the red
+// channel of the left half.
+fn two_colors(red: f64) -> gtk::DrawingArea {
+    let area = gtk::DrawingArea::new();
+    area.connect_draw(move |_, cr| {
+        cr.set_source_rgb(red, 0.0, 0.0);
+        cr.rectangle(0.0, 0.0, 4.0, 6.0);
+        cr.fill().unwrap();
+        cr.set_source_rgb(0.0, 0.0, 1.0);
+        cr.rectangle(4.0, 0.0, 4.0, 6.0);
+        cr.fill().unwrap();
+        gtk::glib::Propagation::Stop
+    });
+    area
+}
+
+fn temp_dir(name: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("gtk-rs-snapshot-{}-{name}", std::process::id()));
+    let _ = std::fs::remove_dir_all(&dir);
+    std::fs::create_dir_all(&dir).unwrap();
+    dir
+}
+
+fn reference() -> PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/snapshots/two_colors.png")
+}
+
+#[test]
+fn matches_reference() {
+    gtk::test::run(|| {
+        let area = two_colors(1.0);
+        gtk::assert_widget_snapshot!(area, "two_colors", width = 8, height = 6);
+    });
+}
+
+#[test]
+fn tolerance() {
+    gtk::test::run(|| {
+        // 250 instead of 255 in the red channel
+        let area = two_colors(250.0 / 255.0);
+        gtk::assert_widget_snapshot!(area, "two_colors", width = 8, height = 6, tolerance = 5);
+
+        let res = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+            let dir = temp_dir("tolerance");
+            std::fs::copy(reference(), dir.join("two_colors.png")).unwrap();
+            gtk::assert_widget_snapshot!(
+                area,
+                "two_colors",
+                width = 8,
+                height = 6,
+                tolerance = 4,
+                dir = &dir,
+            );
+        }));
+        assert!(res.is_err());
+    });
+}
+
+#[test]
+fn mismatch_writes_actual_and_diff() {
+    gtk::test::run(|| {
+        let dir = temp_dir("mismatch");
+        std::fs::copy(reference(), dir.join("two_colors.png")).unwrap();
+
+        let area = two_colors(0.0);
+        let res = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+            gtk::assert_widget_snapshot!(area, "two_colors", width = 8, height = 6, dir = &dir);
+        }));
+        let message = res.unwrap_err();
+        let message = message.downcast_ref::<String>().unwrap();
+        assert!(message.contains("differs"), "{message}");
+        assert!(message.contains("in 24 pixels"), "{message}");
+
+        let read = |name: &str| {
+            let mut file = std::fs::File::open(dir.join(name)).unwrap();
+            gtk::cairo::ImageSurface::create_from_png(&mut file).unwrap()
+        };
+        let actual = read("two_colors.actual.png");
+        assert_eq!((actual.width(), actual.height()), (8, 6));
+        let diff = read("two_colors.diff.png");
+        assert_eq!((diff.width(), diff.height()), (8, 6));
+
+        // Once it matches again, the leftovers are removed
+        let area = two_colors(1.0);
+        gtk::assert_widget_snapshot!(area, "two_colors", width = 8, height = 6, dir = &dir);
+        assert!(!dir.join("two_colors.actual.png").exists());
+        assert!(!dir.join("two_colors.diff.png").exists());
+    });
+}
+
+// Run by `bless_env_var` in a process of its own, with the environment variables set.
+#[test]
+#[ignore]
+fn bless_child() {
+    let Some(dir) = std::env::var_os(CHILD_DIR_ENV) else {
+        return;
+    };
+    gtk::test::run(move || {
+        let area = two_colors(0.0);
+        gtk::assert_widget_snapshot!(area, "two_colors", width = 8, height = 6, dir = &dir);
+    });
+}
+
+#[test]
+fn bless_env_var() {
+    let dir = temp_dir("bless");
+    std::fs::copy(reference(), dir.join("two_colors.png")).unwrap();
+
+    let status = Command::new(std::env::current_exe().unwrap())
+        .args(["bless_child", "--exact", "--ignored"])
+        .env(gtk::test::SNAPSHOT_BLESS_ENV, "1")
+        .env(CHILD_DIR_ENV, &dir)
+        .status()
+        .unwrap();
+    assert!(status.success());
+
+    // The rendering, which differs from the original reference, became the reference
+    let blessed = std::fs::read(dir.join("two_colors.png")).unwrap();
+    assert_ne!(blessed, std::fs::read(reference()).unwrap());
+    assert!(!dir.join("two_colors.actual.png").exists());
+    gtk::test::run(move || {
+        let area = two_colors(0.0);
+        gtk::assert_widget_snapshot!(area, "two_colors", width = 8, height = 6, dir = &dir);
+    });
+}